@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+use crate::runner::{Outcome, Run};
+
+/// The known-good answers, one per day and part. On disk this is a plain text
+/// file with a `day part answer` line per entry, i.e. `6 1 41`, and `#` comments
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AnswerKey {
+    answers: BTreeMap<(u8, u8), String>,
+}
+
+impl AnswerKey {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut answers = BTreeMap::new();
+        for line in input.lines() {
+            let content = line.split('#').next().unwrap_or_default();
+            if content.trim().is_empty() {
+                continue;
+            }
+            let mut fields = content.split_whitespace();
+            let (Some(day), Some(part), Some(answer), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(Error::malformed(
+                    Location::of(input, line),
+                    "an answer like 6 1 41",
+                ));
+            };
+            let day = day
+                .parse::<u8>()
+                .ok()
+                .filter(|d| (1..=25).contains(d))
+                .ok_or_else(|| Error::malformed(Location::of(input, day), "a day 1-25"))?;
+            let part = part
+                .parse::<u8>()
+                .ok()
+                .filter(|p| (1..=2).contains(p))
+                .ok_or_else(|| Error::malformed(Location::of(input, part), "a part 1 or 2"))?;
+            answers.insert((day, part), answer.to_string());
+        }
+        Ok(Self { answers })
+    }
+
+    /// load the answers from `path`, a file that doesn't exist yet is empty
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = read_input(path)?;
+        Self::parse(&content).map_err(|e| e.in_file(path))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_string()).map_err(|e| Error::from(e).in_file(path))
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, answer: impl ToString) {
+        self.answers.insert((day, part), answer.to_string());
+    }
+
+    /// store the answers of every solved run, leaving the rest of the key alone
+    pub fn record(&mut self, runs: &[Run]) {
+        for run in runs {
+            if let Outcome::Solved { answers, .. } = &run.outcome {
+                self.insert(run.day.number, 1, &answers.part_one);
+                self.insert(run.day.number, 2, &answers.part_two);
+            }
+        }
+    }
+}
+
+impl fmt::Display for AnswerKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# day part answer")?;
+        for ((day, part), answer) in &self.answers {
+            writeln!(f, "{day} {part} {answer}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How a single part compared against the answer key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// the day was solved but there is nothing in the key to compare against
+    Missing {
+        actual: String,
+    },
+    /// the day couldn't be solved, either there was no input or it errored
+    NotRun {
+        reason: String,
+    },
+}
+
+/// The verdict on a single day and part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub status: Status,
+}
+
+/// compare every part of every run against the key
+pub fn verify(runs: &[Run], key: &AnswerKey) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for run in runs {
+        let day = run.day.number;
+        for part in [1, 2] {
+            let status = match &run.outcome {
+                Outcome::Solved { answers, .. } => {
+                    let actual = if part == 1 {
+                        answers.part_one.to_string()
+                    } else {
+                        answers.part_two.to_string()
+                    };
+                    match key.get(day, part) {
+                        Some(expected) if expected == actual => Status::Pass,
+                        Some(expected) => Status::Fail {
+                            expected: expected.to_string(),
+                            actual,
+                        },
+                        None => Status::Missing { actual },
+                    }
+                }
+                Outcome::Skipped { path } => Status::NotRun {
+                    reason: format!("no input at {}", path.display()),
+                },
+                Outcome::Failed(e) => match key.get(day, part) {
+                    // an error is only a regression when there was an answer to give
+                    Some(expected) => Status::Fail {
+                        expected: expected.to_string(),
+                        actual: format!("error, {e}"),
+                    },
+                    None => Status::NotRun {
+                        reason: e.to_string(),
+                    },
+                },
+            };
+            checks.push(Check { day, part, status });
+        }
+    }
+    checks
+}
+
+/// a line per check and a tally at the end
+pub fn report(checks: &[Check]) -> String {
+    let mut out = String::new();
+    let (mut pass, mut fail, mut missing, mut not_run) = (0, 0, 0, 0);
+    for check in checks {
+        let verdict = match &check.status {
+            Status::Pass => {
+                pass += 1;
+                "pass".to_string()
+            }
+            Status::Fail { expected, actual } => {
+                fail += 1;
+                format!("FAIL expected {expected}, got {actual}")
+            }
+            Status::Missing { actual } => {
+                missing += 1;
+                format!("missing, got {actual}")
+            }
+            Status::NotRun { reason } => {
+                not_run += 1;
+                format!("not run, {reason}")
+            }
+        };
+        out.push_str(&format!(
+            "day {:>2} part {}: {verdict}\n",
+            check.day, check.part
+        ));
+    }
+    out.push_str(&format!(
+        "{pass} passed, {fail} failed, {missing} missing, {not_run} not run\n"
+    ));
+    out
+}
+
+/// whether any part came out different to the key
+pub fn has_failures(checks: &[Check]) -> bool {
+    checks
+        .iter()
+        .any(|c| matches!(c.status, Status::Fail { .. }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::find;
+    use crate::solver::{Answer, Answers, Timings};
+
+    fn solved(name: &str, part_one: u64, part_two: u64) -> Run {
+        Run {
+            day: find(name).unwrap(),
+            outcome: Outcome::Solved {
+                answers: Answers {
+                    part_one: Answer::Number(part_one),
+                    part_two: Answer::Number(part_two),
+                },
+                timings: Timings::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let key = AnswerKey::parse("# comment\n1 1 11\n\n1 2 31 # trailing\n6 1 41\n").unwrap();
+        assert_eq!(key.get(1, 2), Some("31"));
+        assert_eq!(key.get(6, 2), None);
+        assert_eq!(AnswerKey::parse(&key.to_string()).unwrap(), key);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = AnswerKey::parse("1 1 11\n1 3 4\n").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 3)));
+        let error = AnswerKey::parse("1 1\n").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(1, 1)));
+    }
+
+    #[test]
+    fn test_verify() {
+        let key = AnswerKey::parse("1 1 11\n1 2 30\n").unwrap();
+        let checks = verify(&[solved("day_one", 11, 31), solved("day_two", 2, 4)], &key);
+        let statuses: Vec<&Status> = checks.iter().map(|c| &c.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                &Status::Pass,
+                &Status::Fail {
+                    expected: "30".into(),
+                    actual: "31".into()
+                },
+                &Status::Missing { actual: "2".into() },
+                &Status::Missing { actual: "4".into() },
+            ]
+        );
+        assert!(has_failures(&checks));
+        assert!(report(&checks).ends_with("1 passed, 1 failed, 2 missing, 0 not run\n"));
+    }
+
+    #[test]
+    fn test_record() {
+        let mut key = AnswerKey::parse("2 1 99\n").unwrap();
+        key.record(&[solved("day_one", 11, 31)]);
+        assert_eq!(
+            key.to_string(),
+            "# day part answer\n1 1 11\n1 2 31\n2 1 99\n"
+        );
+    }
+}
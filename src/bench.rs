@@ -0,0 +1,218 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::days::Day;
+use crate::error::Result;
+use crate::params::Params;
+use crate::solver::Timings;
+
+/// Summary statistics over the samples of one phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// the statistics of a non empty set of samples
+    pub fn of(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarise");
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = sorted
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        Self {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// The timings of a day over many runs, split by phase
+#[derive(Debug, Clone)]
+pub struct Bench {
+    pub day: &'static Day,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub part_one: Stats,
+    pub part_two: Stats,
+    pub total: Stats,
+}
+
+impl Bench {
+    pub fn phases(&self) -> [(&'static str, &Stats); 4] {
+        [
+            ("parse", &self.parse),
+            ("part_one", &self.part_one),
+            ("part_two", &self.part_two),
+            ("total", &self.total),
+        ]
+    }
+}
+
+/// run `day` on `input` `warmup` times without measuring and then `iterations`
+/// more times, timing each phase separately
+pub fn bench(
+    day: &'static Day,
+    input: &str,
+    params: &Params,
+    warmup: usize,
+    iterations: usize,
+) -> Result<Bench> {
+    for _ in 0..warmup {
+        day.solve_timed(input, params)?;
+    }
+    let samples = (0..iterations.max(1))
+        .map(|_| day.solve_timed(input, params).map(|(_, timings)| timings))
+        .collect::<Result<Vec<Timings>>>()?;
+    let phase = |f: fn(&Timings) -> Duration| Stats::of(&samples.iter().map(f).collect::<Vec<_>>());
+    Ok(Bench {
+        day,
+        iterations: samples.len(),
+        parse: phase(|t| t.parse),
+        part_one: phase(|t| t.part_one),
+        part_two: phase(|t| t.part_two),
+        total: phase(Timings::total),
+    })
+}
+
+fn micros(d: Duration) -> String {
+    format!("{:.1}", d.as_secs_f64() * 1e6)
+}
+
+/// a human readable table, all times in microseconds
+pub fn table(benches: &[Bench]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:>3}  {:<8}  {:>12}  {:>12}  {:>12}  {:>12}",
+        "day", "phase", "min us", "median us", "mean us", "stddev us"
+    );
+    for bench in benches {
+        for (phase, stats) in bench.phases() {
+            let _ = writeln!(
+                out,
+                "{:>3}  {:<8}  {:>12}  {:>12}  {:>12}  {:>12}",
+                bench.day.number,
+                phase,
+                micros(stats.min),
+                micros(stats.median),
+                micros(stats.mean),
+                micros(stats.stddev),
+            );
+        }
+    }
+    out
+}
+
+/// a row per day and phase under a header, times in nanoseconds
+pub fn tsv(benches: &[Bench]) -> String {
+    let mut out = String::from("day\tphase\titerations\tmin_ns\tmedian_ns\tmean_ns\tstddev_ns\n");
+    for bench in benches {
+        for (phase, stats) in bench.phases() {
+            let _ = writeln!(
+                out,
+                "{}\t{phase}\t{}\t{}\t{}\t{}\t{}",
+                bench.day.number,
+                bench.iterations,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos(),
+            );
+        }
+    }
+    out
+}
+
+// a phase of a bench the way it's written out as JSON
+#[derive(Serialize)]
+struct JsonPhase {
+    day: u8,
+    phase: &'static str,
+    iterations: usize,
+    min_ns: u128,
+    median_ns: u128,
+    mean_ns: u128,
+    stddev_ns: u128,
+}
+
+/// one JSON object per day and phase, times in nanoseconds, for comparing runs
+/// across commits
+pub fn json_lines(benches: &[Bench]) -> String {
+    let mut out = String::new();
+    for bench in benches {
+        for (phase, stats) in bench.phases() {
+            let json = JsonPhase {
+                day: bench.day.number,
+                phase,
+                iterations: bench.iterations,
+                min_ns: stats.min.as_nanos(),
+                median_ns: stats.median.as_nanos(),
+                mean_ns: stats.mean.as_nanos(),
+                stddev_ns: stats.stddev.as_nanos(),
+            };
+            let json = serde_json::to_string(&json).expect("a phase always serializes");
+            let _ = writeln!(out, "{json}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::find;
+
+    #[test]
+    fn test_stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::of(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        // sqrt(1.25) ms
+        assert_eq!(stats.stddev.as_micros(), 1118);
+
+        let stats = Stats::of(&[Duration::from_millis(7)]);
+        assert_eq!(stats.median, Duration::from_millis(7));
+        assert_eq!(stats.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_bench() {
+        let day = find("day_one").unwrap();
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        let bench = bench(day, input, &Params::new(), 1, 5).unwrap();
+        assert_eq!(bench.iterations, 5);
+        assert!(bench.total.min >= bench.parse.min);
+        assert_eq!(table(std::slice::from_ref(&bench)).lines().count(), 5);
+        let json = json_lines(std::slice::from_ref(&bench));
+        assert!(json.starts_with(r#"{"day":1,"phase":"parse","iterations":5,"#));
+        assert_eq!(json.lines().count(), 4);
+        let tsv = tsv(&[bench]);
+        assert!(tsv.lines().nth(4).unwrap().starts_with("1\ttotal\t5\t"));
+    }
+
+    #[test]
+    fn test_bench_reports_parse_errors() {
+        let day = find("day_one").unwrap();
+        assert!(bench(day, "1 x\n", &Params::new(), 0, 3).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use std::any::Any;
+use std::env;
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::generate::Rng;
+
+/// the seed a plain `cargo test` checks with, so a failure turns up the same
+/// way on every run
+pub const SEED: u64 = 0x2024;
+/// how many cases a property is checked on unless told otherwise
+pub const CASES: usize = 100;
+/// how big the cases grow, the first ones being tiny
+pub const MAX_SIZE: usize = 32;
+/// the most smaller inputs tried before settling for the one there is
+const MAX_SHRINKS: usize = 10_000;
+
+/// the environment variable for checking more cases, the long run being
+/// something like `AOC_CHECK_CASES=100000 cargo test --release`
+pub const CASES_VAR: &str = "AOC_CHECK_CASES";
+/// the environment variable for another seed, i.e. the one a failure reports
+pub const SEED_VAR: &str = "AOC_CHECK_SEED";
+
+/// Whether a property held for an input, and why not when it didn't
+pub type Outcome = std::result::Result<(), String>;
+
+/// The seed and number of cases properties are checked with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub seed: u64,
+    pub cases: usize,
+}
+
+impl Config {
+    /// the config from the values of `SEED_VAR` and `CASES_VAR`, the defaults
+    /// for any not given
+    pub fn new(seed: Option<&str>, cases: Option<&str>) -> Self {
+        fn value<T: std::str::FromStr>(var: &str, value: Option<&str>, default: T) -> T {
+            value.map_or(default, |v| {
+                v.trim()
+                    .parse()
+                    .unwrap_or_else(|_| panic!("{var} has to be a number, not '{v}'"))
+            })
+        }
+        Self {
+            seed: value(SEED_VAR, seed, SEED),
+            cases: value(CASES_VAR, cases, CASES),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let seed = env::var(SEED_VAR).ok();
+        let cases = env::var(CASES_VAR).ok();
+        Self::new(seed.as_deref(), cases.as_deref())
+    }
+}
+
+/// A property that didn't hold, with the smallest input it still fails for
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure<T> {
+    pub seed: u64,
+    pub case: usize,
+    pub original: T,
+    pub input: T,
+    pub reason: String,
+    pub shrinks: usize,
+}
+
+/// why `property` doesn't hold for `input`, a panic counting as not holding
+fn fails<T>(property: &impl Fn(&T) -> Outcome, input: &T) -> Option<String> {
+    match panic::catch_unwind(AssertUnwindSafe(|| property(input))) {
+        Ok(Ok(())) => None,
+        Ok(Err(reason)) => Some(reason),
+        Err(payload) => Some(format!("panicked: {}", panic_message(&*payload))),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "something that isn't a message"
+    }
+}
+
+/// check `property` on `config.cases` inputs from `generate`, which is given
+/// how big to make each one. The first input it fails for is shrunk, taking
+/// the first of the smaller inputs from `shrink` that still fails until none
+/// do, and handed back
+pub fn run<T: Clone>(
+    config: Config,
+    generate: impl Fn(&mut Rng, usize) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    property: impl Fn(&T) -> Outcome,
+) -> Option<Failure<T>> {
+    let mut rng = Rng::new(config.seed);
+    for case in 0..config.cases {
+        let size = (1 + case * MAX_SIZE / config.cases).min(MAX_SIZE);
+        let original = generate(&mut rng, size);
+        let Some(mut reason) = fails(&property, &original) else {
+            continue;
+        };
+        let mut input = original.clone();
+        let mut shrinks = 0;
+        'shrinking: while shrinks < MAX_SHRINKS {
+            for smaller in shrink(&input) {
+                if let Some(why) = fails(&property, &smaller) {
+                    input = smaller;
+                    reason = why;
+                    shrinks += 1;
+                    continue 'shrinking;
+                }
+            }
+            break;
+        }
+        return Some(Failure {
+            seed: config.seed,
+            case,
+            original,
+            input,
+            reason,
+            shrinks,
+        });
+    }
+    None
+}
+
+/// check the property `name` with the config from the environment, panicking
+/// with the smallest input it fails for and how to get it again
+pub fn check<T: Clone + Debug>(
+    name: &str,
+    generate: impl Fn(&mut Rng, usize) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    property: impl Fn(&T) -> Outcome,
+) {
+    if let Some(failure) = run(Config::from_env(), generate, shrink, property) {
+        panic!(
+            "{name} doesn't hold for {:?}: {}\n\
+             shrunk {} times from {:?}\n\
+             that's case {} from seed {}, run it again with {SEED_VAR}={}",
+            failure.input,
+            failure.reason,
+            failure.shrinks,
+            failure.original,
+            failure.case,
+            failure.seed,
+            failure.seed,
+        );
+    }
+}
+
+/// `found` is `expected`, saying what was found when it isn't
+pub fn equal<T: PartialEq + Debug>(what: &str, found: T, expected: T) -> Outcome {
+    if found == expected {
+        Ok(())
+    } else {
+        Err(format!("{what} is {found:?}, it should be {expected:?}"))
+    }
+}
+
+/// numbers between `n` and `target` to try in its place, `target` first then
+/// halving the way back to `n` so even a big number shrinks quickly
+pub fn shrink_number(n: u64, target: u64) -> Vec<u64> {
+    let mut smaller = Vec::new();
+    let mut distance = n.saturating_sub(target);
+    while distance > 0 {
+        smaller.push(n - distance);
+        distance /= 2;
+    }
+    smaller
+}
+
+/// shorter and smaller versions of `items`, half of them gone, then any one of
+/// them gone, then any one of them made smaller by `shrink_item`
+pub fn shrink_vec<T: Clone>(items: &[T], shrink_item: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    let mut smaller = Vec::new();
+    if items.len() > 1 {
+        let half = items.len() / 2;
+        smaller.push(items[..half].to_vec());
+        smaller.push(items[half..].to_vec());
+    }
+    for i in 0..items.len() {
+        let mut without = items.to_vec();
+        without.remove(i);
+        smaller.push(without);
+    }
+    for (i, item) in items.iter().enumerate() {
+        for s in shrink_item(item) {
+            let mut with = items.to_vec();
+            with[i] = s;
+            smaller.push(with);
+        }
+    }
+    smaller
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(rng: &mut Rng, size: usize) -> Vec<u64> {
+        (0..rng.below(size as u64 + 1))
+            .map(|_| rng.below(51))
+            .collect()
+    }
+
+    fn shrink_numbers(numbers: &[u64]) -> Vec<Vec<u64>> {
+        shrink_vec(numbers, |&n| shrink_number(n, 0))
+    }
+
+    const CONFIG: Config = Config {
+        seed: SEED,
+        cases: CASES,
+    };
+
+    #[test]
+    fn test_passing_property() {
+        let failure = run(
+            CONFIG,
+            numbers,
+            |n| shrink_numbers(n),
+            |numbers| equal("the biggest", numbers.iter().all(|&n| n <= 50), true),
+        );
+        assert_eq!(failure, None);
+    }
+
+    #[test]
+    fn test_shrinks_to_smallest() {
+        let failure = run(
+            CONFIG,
+            numbers,
+            |n| shrink_numbers(n),
+            |numbers| {
+                let sum: u64 = numbers.iter().sum();
+                if sum < 100 {
+                    Ok(())
+                } else {
+                    Err(format!("the sum is {sum}"))
+                }
+            },
+        )
+        .unwrap();
+        // nothing can come out or go down without the sum dropping under 100
+        assert_eq!(failure.input.iter().sum::<u64>(), 100);
+        assert!(failure.input.len() <= failure.original.len());
+        assert!(failure.shrinks > 0);
+        assert_eq!(failure.reason, "the sum is 100");
+    }
+
+    #[test]
+    fn test_panics_fail() {
+        let failure = run(
+            CONFIG,
+            numbers,
+            |n| shrink_numbers(n),
+            |numbers| {
+                assert!(!numbers.contains(&7), "there's a seven");
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(failure.input, vec![7]);
+        assert_eq!(failure.reason, "panicked: there's a seven");
+    }
+
+    #[test]
+    fn test_same_seed_same_failure() {
+        let first_big = |config| {
+            run(
+                config,
+                numbers,
+                |n| shrink_numbers(n),
+                |numbers| equal("the first", numbers.first().is_some_and(|&n| n > 40), false),
+            )
+            .map(|failure| (failure.case, failure.original))
+        };
+        assert_eq!(first_big(CONFIG), first_big(CONFIG));
+        assert!(first_big(CONFIG).is_some());
+        let other = Config { seed: 7, ..CONFIG };
+        assert_ne!(first_big(CONFIG), first_big(other));
+    }
+
+    #[test]
+    #[should_panic(expected = "run it again with AOC_CHECK_SEED=")]
+    fn test_check_panics() {
+        check(
+            "nothing is seven",
+            numbers,
+            |n| shrink_numbers(n),
+            |numbers| equal("sevens", numbers.iter().filter(|&&n| n == 7).count(), 0),
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        assert_eq!(Config::new(None, None), CONFIG);
+        assert_eq!(
+            Config::new(Some("12"), Some(" 5000 ")),
+            Config {
+                seed: 12,
+                cases: 5000
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "AOC_CHECK_CASES has to be a number, not 'lots'")]
+    fn test_config_not_a_number() {
+        Config::new(None, Some("lots"));
+    }
+
+    #[test]
+    fn test_shrink_number() {
+        assert_eq!(shrink_number(10, 0), vec![0, 5, 8, 9]);
+        assert_eq!(shrink_number(2, 1), vec![1]);
+        assert_eq!(shrink_number(1, 1), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_shrink_vec() {
+        let smaller = shrink_vec(&[1, 2, 3], |&n| shrink_number(n, 0));
+        assert_eq!(smaller[..2], [vec![1], vec![2, 3]]);
+        assert!(smaller.contains(&vec![1, 3]));
+        assert!(smaller.contains(&vec![1, 2, 0]));
+        assert!(!smaller.contains(&vec![1, 2, 3]));
+    }
+}
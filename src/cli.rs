@@ -0,0 +1,932 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::days::{DAYS, Day};
+use crate::image;
+use crate::inputs;
+use crate::output::Format;
+use crate::params::Params;
+use crate::solver::Part;
+use crate::visual::{self, FrameRange};
+
+/// Where the puzzle text comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Stdin,
+    File(PathBuf),
+    /// the examples the day ships with
+    Example,
+    /// the day's file in the data directory, fetched if it isn't there yet
+    Stored,
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::Stdin => write!(f, "<stdin>"),
+            Input::File(path) => write!(f, "{}", path.display()),
+            Input::Example => write!(f, "<example>"),
+            Input::Stored => write!(f, "<stored input>"),
+        }
+    }
+}
+
+/// What the binary has been asked to do
+#[derive(Debug)]
+pub enum Command {
+    Solve {
+        day: &'static Day,
+        input: Input,
+        /// only this part, both when not given
+        part: Option<Part>,
+    },
+    /// play the day out in the terminal
+    Visualize {
+        day: &'static Day,
+        input: Input,
+        fps: f64,
+        /// wait on the first frame, to step through from the start
+        paused: bool,
+        frames: FrameRange,
+        /// write the frames to an image or GIF rather than playing them
+        export: Option<PathBuf>,
+        /// pixels a side for each cell of an exported image
+        scale: usize,
+    },
+    RunAll {
+        dir: PathBuf,
+        parallel: bool,
+    },
+    Verify {
+        dir: PathBuf,
+        /// defaults to `answers.txt` inside `dir`
+        answers: Option<PathBuf>,
+        record: bool,
+        parallel: bool,
+    },
+    Bench {
+        days: Vec<&'static Day>,
+        dir: PathBuf,
+        iterations: usize,
+        warmup: usize,
+    },
+    /// make sure the data directory has the inputs for these days
+    Fetch {
+        days: Vec<&'static Day>,
+    },
+    /// send an answer for a part of a day, the day's own when not given
+    Submit {
+        day: &'static Day,
+        part: Part,
+        answer: Option<String>,
+        /// defaults to `history.txt` in the data directory
+        history: Option<PathBuf>,
+    },
+    /// make a random input for a day, to stdout when there's no file
+    Generate {
+        day: &'static Day,
+        seed: u64,
+        /// sizes given with `--size`, the rest are the day's defaults
+        sizes: Params,
+        output: Option<PathBuf>,
+    },
+    /// rank the members of an exported private leaderboard
+    Leaderboard {
+        path: PathBuf,
+    },
+    /// make the module for a day that hasn't been started and register it
+    NewDay {
+        day: u8,
+        /// the root of the crate, holding `src/days`
+        root: PathBuf,
+    },
+    Help,
+}
+
+/// The parsed command line
+#[derive(Debug)]
+pub struct Cli {
+    pub format: Format,
+    /// a file of per-day parameters
+    pub config: Option<PathBuf>,
+    /// parameters given with `--param`, these win over the config file
+    pub params: Params,
+    /// where stored inputs are kept and fetched to
+    pub data_dir: PathBuf,
+    /// the server inputs are fetched from
+    pub base_url: String,
+    /// the file with the session token, `.session` in the data directory when
+    /// not given
+    pub session_file: Option<PathBuf>,
+    /// how much is logged to stderr, 0 for warnings and errors, up with each
+    /// `-v` and down with each `-q`
+    pub verbosity: i8,
+    pub command: Command,
+}
+
+/// A command line that couldn't be understood, the message says what was wrong
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgError(pub String);
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+fn arg_error<T>(message: impl Into<String>) -> Result<T, ArgError> {
+    Err(ArgError(message.into()))
+}
+
+const DEFAULT_INPUTS: &str = "inputs";
+
+/// find a registered day from any of `6`, `06`, `day6`, `day_6` or `day_six`
+pub fn parse_day(s: &str) -> Result<&'static Day, ArgError> {
+    if let Some(day) = DAYS.iter().find(|d| d.name == s) {
+        return Ok(day);
+    }
+    let number = s.strip_prefix("day").unwrap_or(s);
+    let number = number.strip_prefix('_').unwrap_or(number);
+    match number.parse::<u8>() {
+        Ok(n) => DAYS.iter().find(|d| d.number == n).ok_or_else(|| {
+            if (1..=25).contains(&n) {
+                ArgError(format!("day {n} hasn't been solved yet, see --help"))
+            } else {
+                ArgError(format!("there is no day {n}, the calendar runs 1-25"))
+            }
+        }),
+        Err(_) => arg_error(format!(
+            "unknown day '{s}', expected a number like 6 or a name like day_six"
+        )),
+    }
+}
+
+/// a single day, or every registered day for `all`
+fn parse_days(s: &str) -> Result<Vec<&'static Day>, ArgError> {
+    if s == "all" {
+        Ok(DAYS.iter().collect())
+    } else {
+        Ok(vec![parse_day(s)?])
+    }
+}
+
+fn parse_part(s: &str) -> Result<Part, ArgError> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => arg_error(format!("--part expects 1 or 2, found '{s}'")),
+    }
+}
+
+/// how many times a short flag is given together, i.e. 3 for `-vvv`
+fn repeats(option: &str, flag: char) -> Option<i8> {
+    let flags = option.strip_prefix('-')?;
+    (!flags.is_empty() && flags.chars().all(|c| c == flag))
+        .then(|| flags.len().min(i8::MAX as usize) as i8)
+}
+
+/// Walks the arguments, handing out the value after an option
+struct Args {
+    args: std::vec::IntoIter<String>,
+}
+
+impl Args {
+    /// the value of `option`, either given inline as `--option=value` or as the
+    /// next argument
+    fn value(
+        &mut self,
+        option: &str,
+        inline: Option<&str>,
+        what: &str,
+    ) -> Result<String, ArgError> {
+        match inline {
+            Some(value) => Ok(value.to_string()),
+            None => self
+                .args
+                .next()
+                .ok_or_else(|| ArgError(format!("{option} needs {what}"))),
+        }
+    }
+}
+
+impl Iterator for Args {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.args.next()
+    }
+}
+
+/// parse the arguments, not including the program name
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Cli, ArgError> {
+    let mut format = Format::Plain;
+    let mut help = false;
+    let mut positional = Vec::new();
+    let mut part = None;
+    let mut parallel = true;
+    let mut record = false;
+    let mut answers = None;
+    let mut iterations = 10;
+    let mut warmup = 2;
+    let mut config = None;
+    let mut params = Params::new();
+    let mut example = false;
+    let mut data_dir = PathBuf::from(DEFAULT_INPUTS);
+    let mut base_url = inputs::DEFAULT_BASE_URL.to_string();
+    let mut session_file = None;
+    let mut history = None;
+    let mut visualize = false;
+    let mut fps = 20.0;
+    let mut step = false;
+    let mut frames = FrameRange::ALL;
+    let mut export = None;
+    let mut scale = 4;
+    let mut verbosity: i8 = 0;
+    let mut seed = 0;
+    let mut sizes = Params::new();
+
+    let mut args = Args {
+        args: args.into_iter().collect::<Vec<_>>().into_iter(),
+    };
+    while let Some(arg) = args.next() {
+        let (option, inline) = match arg.split_once('=') {
+            Some((option, value)) if option.starts_with("--") => (option, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+        match option {
+            "-h" | "--help" | "help" => help = true,
+            "--verbose" => verbosity = verbosity.saturating_add(1),
+            "--quiet" => verbosity = verbosity.saturating_sub(1),
+            _ if let Some(count) = repeats(option, 'v') => {
+                verbosity = verbosity.saturating_add(count)
+            }
+            _ if let Some(count) = repeats(option, 'q') => {
+                verbosity = verbosity.saturating_sub(count)
+            }
+            "--format" => {
+                format = args
+                    .value(option, inline, "one of plain, json or tsv")?
+                    .parse()
+                    .map_err(ArgError)?
+            }
+            "--part" => part = Some(parse_part(&args.value(option, inline, "1 or 2")?)?),
+            "--sequential" => parallel = false,
+            "--example" => example = true,
+            "--visualize" => visualize = true,
+            "--step" => step = true,
+            "--fps" => {
+                let value = args.value(option, inline, "frames a second")?;
+                fps = value
+                    .parse()
+                    .ok()
+                    .filter(|fps: &f64| *fps >= visual::MIN_FPS && fps.is_finite())
+                    .ok_or_else(|| {
+                        ArgError(format!(
+                            "--fps expects at least {} frames a second, found '{value}'",
+                            visual::MIN_FPS
+                        ))
+                    })?;
+            }
+            "--frames" => {
+                frames = args
+                    .value(option, inline, "a frame or a range of them")?
+                    .parse()
+                    .map_err(|e| ArgError(format!("--frames {e}")))?
+            }
+            "--export" => {
+                let path = PathBuf::from(args.value(option, inline, "a file")?);
+                if image::Kind::of(&path).is_none() {
+                    return arg_error(format!(
+                        "--export writes a .ppm, .png or .gif, found '{}'",
+                        path.display()
+                    ));
+                }
+                export = Some(path);
+            }
+            "--scale" => {
+                let value = args.value(option, inline, "pixels a cell")?;
+                scale = value
+                    .parse()
+                    .ok()
+                    .filter(|scale| *scale > 0)
+                    .ok_or_else(|| {
+                        ArgError(format!("--scale expects pixels a cell, found '{value}'"))
+                    })?;
+            }
+            "--record" => record = true,
+            "--answers" => answers = Some(PathBuf::from(args.value(option, inline, "a file")?)),
+            "--config" => config = Some(PathBuf::from(args.value(option, inline, "a file")?)),
+            "--data-dir" => data_dir = PathBuf::from(args.value(option, inline, "a directory")?),
+            "--base-url" => base_url = args.value(option, inline, "a url")?,
+            "--session-file" => {
+                session_file = Some(PathBuf::from(args.value(option, inline, "a file")?))
+            }
+            "--history" => history = Some(PathBuf::from(args.value(option, inline, "a file")?)),
+            "--param" => {
+                let value = args.value(option, inline, "a name=value")?;
+                let Some((name, value)) = Params::parse_assignment(&value) else {
+                    return arg_error(format!("--param expects name=value, found '{value}'"));
+                };
+                params.set(name, value);
+            }
+            "--seed" => {
+                let value = args.value(option, inline, "a number")?;
+                seed = value
+                    .parse()
+                    .map_err(|_| ArgError(format!("--seed expects a number, found '{value}'")))?;
+            }
+            "--size" => {
+                let value = args.value(option, inline, "a name=value")?;
+                let Some((name, value)) = Params::parse_assignment(&value) else {
+                    return arg_error(format!("--size expects name=value, found '{value}'"));
+                };
+                sizes.set(name, value);
+            }
+            "--iterations" | "--warmup" => {
+                let value = args.value(option, inline, "a count")?;
+                let count = value
+                    .parse()
+                    .map_err(|_| ArgError(format!("{option} expects a count, found '{value}'")))?;
+                if option == "--iterations" {
+                    iterations = count;
+                } else {
+                    warmup = count;
+                }
+            }
+            "-" => positional.push(arg),
+            _ if option.starts_with('-') => {
+                return arg_error(format!("unknown option '{arg}', see --help"));
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    if help {
+        return Ok(Cli {
+            format,
+            config,
+            params,
+            data_dir,
+            base_url,
+            session_file,
+            verbosity,
+            command: Command::Help,
+        });
+    }
+
+    let mut positional = positional.into_iter();
+    let Some(first) = positional.next() else {
+        return arg_error("expected a day and an input, see --help");
+    };
+    let dir = |p: Option<String>| PathBuf::from(p.unwrap_or_else(|| DEFAULT_INPUTS.into()));
+    let command = match first.as_str() {
+        "run-all" => Command::RunAll {
+            dir: dir(positional.next()),
+            parallel,
+        },
+        "verify" => Command::Verify {
+            dir: dir(positional.next()),
+            answers,
+            record,
+            parallel,
+        },
+        "bench" => {
+            let Some(which) = positional.next() else {
+                return arg_error("bench expects a day or all");
+            };
+            let days = parse_days(&which)?;
+            Command::Bench {
+                days,
+                dir: dir(positional.next()),
+                iterations,
+                warmup,
+            }
+        }
+        "fetch" => {
+            let Some(which) = positional.next() else {
+                return arg_error("fetch expects a day or all");
+            };
+            Command::Fetch {
+                days: parse_days(&which)?,
+            }
+        }
+        "new-day" => {
+            let Some(day) = positional.next() else {
+                return arg_error("new-day expects a day 1-25");
+            };
+            let day = day
+                .parse()
+                .ok()
+                .filter(|d| (1..=25).contains(d))
+                .ok_or_else(|| ArgError(format!("new-day expects a day 1-25, found '{day}'")))?;
+            Command::NewDay {
+                day,
+                root: PathBuf::from(positional.next().unwrap_or_else(|| ".".into())),
+            }
+        }
+        "generate" => {
+            let Some(day) = positional.next() else {
+                return arg_error("generate expects a day");
+            };
+            Command::Generate {
+                day: parse_day(&day)?,
+                seed,
+                sizes,
+                output: positional.next().map(PathBuf::from),
+            }
+        }
+        "leaderboard" => {
+            let Some(path) = positional.next() else {
+                return arg_error("leaderboard expects the exported JSON file");
+            };
+            Command::Leaderboard {
+                path: PathBuf::from(path),
+            }
+        }
+        "submit" => {
+            let (Some(day), Some(submitted)) = (positional.next(), positional.next()) else {
+                return arg_error("submit expects a day and a part");
+            };
+            let Ok(part) = parse_part(&submitted) else {
+                return arg_error(format!("submit expects a part 1 or 2, found '{submitted}'"));
+            };
+            Command::Submit {
+                day: parse_day(&day)?,
+                part,
+                answer: positional.next(),
+                history,
+            }
+        }
+        day => {
+            let day = parse_day(day)?;
+            let input = match positional.next().as_deref() {
+                None if example => Input::Example,
+                Some(path) if example => {
+                    return arg_error(format!(
+                        "--example is used instead of an input, found '{path}'"
+                    ));
+                }
+                None => Input::Stored,
+                Some("-") => Input::Stdin,
+                Some(path) => Input::File(PathBuf::from(path)),
+            };
+            if !visualize && export.is_none() {
+                Command::Solve { day, input, part }
+            } else if part.is_some() {
+                return arg_error("--visualize plays the whole day, it can't take --part");
+            } else {
+                Command::Visualize {
+                    day,
+                    input,
+                    fps,
+                    paused: step,
+                    frames,
+                    export,
+                    scale,
+                }
+            }
+        }
+    };
+    if let Some(extra) = positional.next() {
+        return arg_error(format!("unexpected argument '{extra}', see --help"));
+    }
+    if example && !matches!(command, Command::Solve { .. } | Command::Visualize { .. }) {
+        return arg_error("--example only works when solving a single day");
+    }
+    let single_day = match &command {
+        Command::Solve { .. } | Command::Visualize { .. } | Command::Submit { .. } => true,
+        Command::Bench { days, .. } => days.len() == 1,
+        _ => false,
+    };
+    if params != Params::new() && !single_day {
+        return arg_error("--param only works with a single day, use a --config file for more");
+    }
+    Ok(Cli {
+        format,
+        config,
+        params,
+        data_dir,
+        base_url,
+        session_file,
+        verbosity,
+        command,
+    })
+}
+
+/// the help text, including every registered day
+pub fn usage() -> String {
+    let mut out = String::from(
+        "usage:
+  aoc24 [options] <day> [input|-]    solve a day from a file, or stdin with -, or
+                                     without either its input in the data dir
+  aoc24 [options] <day> --example    solve the day's examples and check the answers
+  aoc24 [options] <day> [input|-] --visualize
+                                     play the day out in the terminal, for the
+                                     days that have something to show
+  aoc24 [options] run-all [dir]      solve every day with an input in dir
+  aoc24 [options] verify [dir]       check every day against the answers file
+  aoc24 [options] bench <day|all> [dir]
+                                     time the parse and both parts
+  aoc24 [options] fetch <day|all>    download any inputs missing from the data dir
+  aoc24 [options] submit <day> <part> [answer]
+                                     send an answer, or the day's own from its
+                                     stored input, unless the history rules it out
+  aoc24 new-day <day> [root]         start a day from the template in the crate at
+                                     root, registered and with a failing test
+  aoc24 [options] leaderboard <file> rank a private leaderboard from its JSON
+                                     export, with each member's times per day
+  aoc24 [options] generate <day> [file]
+                                     make a random input for the day, the same
+                                     one every time for the same seed and sizes
+
+a day can be given as 6, 06, day6 or day_six, dir defaults to inputs/ and holds
+inputs named like day06.txt
+
+options:
+  --format plain|json|tsv   how answers are printed (default plain)
+  --part 1|2                only solve one part
+  --example                 use the examples from the puzzle rather than an input
+  --visualize               play the day rather than solve it, space pauses, n
+                            steps a frame, + and - change speed, q quits
+  --fps N                   frames a second for --visualize, at least 0.01
+                            (default 20)
+  --step                    start --visualize paused on the first frame
+  --frames N|A..B|A..       only the frames numbered N, A up to B, or A on,
+                            counting from 0
+  --export FILE             write the frames to FILE rather than playing them,
+                            a .gif of them all or a .png or .ppm of one, or one
+                            each when FILE has {} in it for the frame's number
+  --scale N                 pixels a side for each cell when exporting
+                            (default 4)
+  --sequential              run-all and verify one day at a time
+  --answers FILE            verify against FILE rather than dir/answers.txt
+  --record                  verify writes the current answers instead
+  --param NAME=VALUE        override one of the day's parameters, can be repeated
+  --config FILE             read parameters for any day from FILE, which has
+                            sections like [day14] holding lines like width = 11
+  --data-dir DIR            where inputs are kept and fetched to (default inputs/)
+  --base-url URL            the server to fetch inputs from
+                            (default https://adventofcode.com)
+  --session-file FILE       the session token to fetch with, when AOC_SESSION
+                            isn't set (default .session in the data dir)
+  --history FILE            submit keeps its attempts in FILE rather than
+                            history.txt in the data dir
+  --seed N                  what generate makes its input from (default 0)
+  --size NAME=VALUE         how big generate makes one part of the input, like
+                            robots=1000, can be repeated
+  --iterations N            bench runs per day (default 10)
+  --warmup N                bench runs thrown away first (default 2)
+  -v, --verbose             log more of what the days are doing to stderr, can
+                            be repeated as in -vv for debugging or -vvv for
+                            everything
+  -q, --quiet               log less, only errors, or nothing with -qq
+  -h, --help                show this
+
+days:
+",
+    );
+    for day in DAYS {
+        out.push_str(&format!("  {:>2}  {}\n", day.number, day.name));
+        for param in day.params {
+            out.push_str(&format!(
+                "        --param {}={}  {}\n",
+                param.name, param.default, param.help
+            ));
+        }
+        for size in day.sizes {
+            out.push_str(&format!(
+                "        --size {}={}  {}\n",
+                size.name, size.default, size.help
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_day() {
+        for name in ["6", "06", "day6", "day06", "day_6", "day_six"] {
+            assert_eq!(parse_day(name).unwrap().number, 6, "{name}");
+        }
+        assert!(
+            parse_day("day_sixty")
+                .unwrap_err()
+                .0
+                .contains("'day_sixty'")
+        );
+        assert!(
+            parse_day("25")
+                .unwrap_err()
+                .0
+                .contains("hasn't been solved")
+        );
+        assert!(parse_day("26").unwrap_err().0.contains("no day 26"));
+    }
+
+    #[test]
+    fn test_parse_solve() {
+        let cli = parse(args("--format json 06 - --part 2")).unwrap();
+        assert_eq!(cli.format, Format::Json);
+        let Command::Solve { day, input, part } = cli.command else {
+            panic!("expected a solve");
+        };
+        assert_eq!(day.number, 6);
+        assert_eq!(input, Input::Stdin);
+        assert_eq!(part, Some(Part::Two));
+
+        let cli = parse(args(
+            "day_one input.txt --format=tsv --param a=1 --param=b=2",
+        ))
+        .unwrap();
+        assert_eq!(cli.format, Format::Tsv);
+        let mut params = Params::new();
+        params.set("a", "1");
+        params.set("b", "2");
+        assert_eq!(cli.params, params);
+        assert!(matches!(
+            cli.command,
+            Command::Solve {
+                input: Input::File(_),
+                part: None,
+                ..
+            }
+        ));
+
+        let cli = parse(args("14 --example --part 1")).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Solve {
+                input: Input::Example,
+                part: Some(Part::One),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        let cli = parse(args("bench all --iterations 3 --warmup=0")).unwrap();
+        let Command::Bench {
+            days,
+            dir,
+            iterations,
+            warmup,
+        } = cli.command
+        else {
+            panic!("expected a bench");
+        };
+        assert_eq!(days.len(), DAYS.len());
+        assert_eq!(dir, PathBuf::from("inputs"));
+        assert_eq!((iterations, warmup), (3, 0));
+
+        let cli = parse(args("verify data --record --sequential")).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Verify {
+                record: true,
+                parallel: false,
+                ..
+            }
+        ));
+        assert!(matches!(
+            parse(args("--help")).unwrap().command,
+            Command::Help
+        ));
+
+        let cli = parse(args(
+            "fetch all --data-dir data --base-url=http://localhost",
+        ))
+        .unwrap();
+        assert!(matches!(cli.command, Command::Fetch { ref days } if days.len() == DAYS.len()));
+        assert_eq!(cli.data_dir, PathBuf::from("data"));
+        assert_eq!(cli.base_url, "http://localhost");
+        assert_eq!(cli.session_file, None);
+    }
+
+    #[test]
+    fn test_parse_submit() {
+        let cli = parse(args("submit 6 2 1234 --history tries.txt")).unwrap();
+        let Command::Submit {
+            day,
+            part,
+            answer,
+            history,
+        } = cli.command
+        else {
+            panic!("expected a submit");
+        };
+        assert_eq!(day.number, 6);
+        assert_eq!(part, Part::Two);
+        assert_eq!(answer.as_deref(), Some("1234"));
+        assert_eq!(history, Some(PathBuf::from("tries.txt")));
+        assert!(matches!(
+            parse(args("submit 6 1")).unwrap().command,
+            Command::Submit { answer: None, .. }
+        ));
+
+        let error = |s: &str| parse(args(s)).unwrap_err().0;
+        assert!(error("submit 6").contains("expects a day and a part"));
+        assert!(error("submit 6 3").contains("part 1 or 2, found '3'"));
+        assert!(error("submit 6 1 2 3").contains("unexpected argument '3'"));
+    }
+
+    #[test]
+    fn test_parse_leaderboard() {
+        let cli = parse(args("leaderboard board.json --format tsv")).unwrap();
+        assert_eq!(cli.format, Format::Tsv);
+        assert!(matches!(
+            cli.command,
+            Command::Leaderboard { ref path } if path == &PathBuf::from("board.json")
+        ));
+        assert!(
+            parse(args("leaderboard"))
+                .unwrap_err()
+                .0
+                .contains("expects the exported JSON")
+        );
+    }
+
+    #[test]
+    fn test_parse_new_day() {
+        assert!(matches!(
+            parse(args("new-day 15")).unwrap().command,
+            Command::NewDay { day: 15, ref root } if root == &PathBuf::from(".")
+        ));
+        assert!(matches!(
+            parse(args("new-day 21 ../aoc")).unwrap().command,
+            Command::NewDay { day: 21, ref root } if root == &PathBuf::from("../aoc")
+        ));
+        let error = |s: &str| parse(args(s)).unwrap_err().0;
+        assert!(error("new-day").contains("expects a day 1-25"));
+        assert!(error("new-day 26").contains("found '26'"));
+        assert!(error("new-day day_six").contains("found 'day_six'"));
+    }
+
+    #[test]
+    fn test_parse_visualize() {
+        let cli = parse(args("6 --visualize --fps 5 --step input.txt")).unwrap();
+        let Command::Visualize {
+            day,
+            input,
+            fps,
+            paused,
+            ..
+        } = cli.command
+        else {
+            panic!("expected a visualize");
+        };
+        assert_eq!(day.number, 6);
+        assert_eq!(input, Input::File(PathBuf::from("input.txt")));
+        assert_eq!(fps, 5.0);
+        assert!(paused);
+        assert!(matches!(
+            parse(args("14 --visualize --example")).unwrap().command,
+            Command::Visualize {
+                input: Input::Example,
+                paused: false,
+                ..
+            }
+        ));
+
+        let error = |s: &str| parse(args(s)).unwrap_err().0;
+        assert!(error("6 --visualize --part 1").contains("can't take --part"));
+        assert!(error("6 --visualize --fps 0").contains("found '0'"));
+        assert_eq!(
+            error("6 --visualize --fps 1e-300"),
+            "--fps expects at least 0.01 frames a second, found '1e-300'"
+        );
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        let cli = parse(args("generate 14 --seed 7 --size robots=50 robots.txt")).unwrap();
+        let Command::Generate {
+            day,
+            seed,
+            sizes,
+            output,
+        } = cli.command
+        else {
+            panic!("expected a generate");
+        };
+        assert_eq!((day.number, seed), (14, 7));
+        assert_eq!(sizes.get::<u64>("robots").unwrap(), 50);
+        assert_eq!(output, Some(PathBuf::from("robots.txt")));
+        assert!(matches!(
+            parse(args("generate 6")).unwrap().command,
+            Command::Generate {
+                seed: 0,
+                output: None,
+                ..
+            }
+        ));
+
+        let error = |s: &str| parse(args(s)).unwrap_err().0;
+        assert!(error("generate").contains("expects a day"));
+        assert!(error("generate 6 --seed x").contains("found 'x'"));
+        assert!(error("generate 6 --size robots").contains("expects name=value"));
+    }
+
+    #[test]
+    fn test_parse_verbosity() {
+        let verbosity = |s: &str| parse(args(s)).unwrap().verbosity;
+        assert_eq!(verbosity("6 input.txt"), 0);
+        assert_eq!(verbosity("6 -v input.txt"), 1);
+        assert_eq!(verbosity("-vvv 6 --verbose input.txt"), 4);
+        assert_eq!(verbosity("6 -q -v -qq"), -2);
+        assert_eq!(verbosity("--help --quiet"), -1);
+        assert!(
+            parse(args("6 -vq"))
+                .unwrap_err()
+                .0
+                .contains("unknown option '-vq'")
+        );
+    }
+
+    #[test]
+    fn test_parse_export() {
+        let cli = parse(args("14 --export robots.gif --scale 2 --frames 10..20")).unwrap();
+        let Command::Visualize {
+            export,
+            scale,
+            frames,
+            ..
+        } = cli.command
+        else {
+            panic!("expected a visualize");
+        };
+        assert_eq!(export, Some(PathBuf::from("robots.gif")));
+        assert_eq!(scale, 2);
+        assert_eq!(
+            frames,
+            FrameRange {
+                start: 10,
+                end: Some(20)
+            }
+        );
+        assert!(matches!(
+            parse(args("6 --visualize")).unwrap().command,
+            Command::Visualize {
+                export: None,
+                scale: 4,
+                frames: FrameRange::ALL,
+                ..
+            }
+        ));
+
+        let error = |s: &str| parse(args(s)).unwrap_err().0;
+        assert!(error("12 --export regions.jpg").contains("found 'regions.jpg'"));
+        assert!(error("12 --export r.png --scale 0").contains("found '0'"));
+        assert!(error("14 --visualize --frames 5..2").contains("found '5..2'"));
+    }
+
+    #[test]
+    fn test_parse_stored_input() {
+        let cli = parse(args("6 --session-file token")).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Solve {
+                input: Input::Stored,
+                ..
+            }
+        ));
+        assert_eq!(cli.data_dir, PathBuf::from("inputs"));
+        assert_eq!(cli.base_url, inputs::DEFAULT_BASE_URL);
+        assert_eq!(cli.session_file, Some(PathBuf::from("token")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |s: &str| parse(args(s)).unwrap_err().0;
+        assert!(error("").contains("expected a day"));
+        assert!(error("fetch").contains("fetch expects a day"));
+        assert!(error("6 a b").contains("unexpected argument 'b'"));
+        assert!(error("6 a --part 3").contains("found '3'"));
+        assert!(error("6 a --part").contains("--part needs 1 or 2"));
+        assert!(error("6 a --frobnicate").contains("unknown option '--frobnicate'"));
+        assert!(error("6 a --format xml").contains("unknown format 'xml'"));
+        assert!(error("bench 6 --iterations many").contains("found 'many'"));
+        assert!(error("6 a --param blinks").contains("expects name=value"));
+        assert!(error("run-all --param blinks=6").contains("single day"));
+        assert!(error("6 a --example").contains("instead of an input"));
+        assert!(error("run-all --example").contains("single day"));
+    }
+
+    #[test]
+    fn test_usage_lists_days() {
+        let usage = usage();
+        for day in DAYS {
+            assert!(usage.contains(day.name));
+        }
+    }
+}
@@ -1,39 +1,86 @@
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+use crate::generate::{self, Rng};
+use crate::params::{Param, Params};
+use crate::parse;
+use crate::solver::{self, Answer, Answers, Example, Solver};
 use ahash::AHashMap;
-use std::env;
-use std::io;
-use std::time::Instant;
-
-pub fn day_one(path: String) -> io::Result<()> {
-    let now = Instant::now();
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 3 {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "Expected two args, got {args.len()}",
-        ));
-    }
-    let content = std::fs::read_to_string(path)?;
-    let mut v1 = Vec::new();
-    let mut v2 = Vec::new();
-    content.lines().for_each(|line| {
-        let mut parts = line.split_whitespace().map(|s| s.parse::<u32>().unwrap());
-        if let (Some(f), Some(s), None) = (parts.next(), parts.next(), parts.next()) {
+
+/// the total distance between the two lists and then how similar they are
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayOne>(input)
+}
+
+pub fn day_one(path: String) -> Result<()> {
+    let content = read_input(&path)?;
+    let answers = solve(&content).map_err(|e| e.in_file(&path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
+    Ok(())
+}
+
+pub struct DayOne;
+
+impl Solver for DayOne {
+    const DAY: u8 = 1;
+    const NAME: &'static str = "day_one";
+
+    const SIZES: &'static [Param] = &[Param {
+        name: "lines",
+        default: "1000",
+        help: "pairs of location ids",
+    }];
+
+    const EXAMPLES: &'static [Example] =
+        &[Example::new(include_str!("examples/day01.txt"), "11", "31")];
+
+    type Input = (Vec<u32>, Vec<u32>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut v1 = Vec::new();
+        let mut v2 = Vec::new();
+        for line in input.lines().filter(|l| !l.trim().is_empty()) {
+            let [f, s] = parse::numbers(input, line, "a location id")?[..] else {
+                return Err(Error::malformed(
+                    Location::of(input, line),
+                    "two location ids separated by whitespace",
+                ));
+            };
             v1.push(f);
             v2.push(s);
-        };
-    });
-    v1.sort_unstable();
-    v2.sort_unstable();
-
-    let d: u32 = v1.iter().zip(&v2).map(|(a, &b)| a.abs_diff(b)).sum();
-    println!("{d:?}");
-    let mut m = AHashMap::new();
-    v2.iter().for_each(|val| {
-        *m.entry(val).or_insert(0) += 1;
-    });
-    let d2: u32 = v1.iter().map(|val| m.get(val).unwrap_or(&0) * val).sum();
-
-    println!("{d2:?}");
-    println!("total duration {}", now.elapsed().as_micros());
-    Ok(())
+        }
+        v1.sort_unstable();
+        v2.sort_unstable();
+        Ok((v1, v2))
+    }
+
+    fn part_one((v1, v2): &Self::Input) -> Answer {
+        let d: u32 = v1.iter().zip(v2).map(|(a, &b)| a.abs_diff(b)).sum();
+        d.into()
+    }
+
+    fn part_two((v1, v2): &Self::Input) -> Answer {
+        let mut m = AHashMap::new();
+        v2.iter().for_each(|val| {
+            *m.entry(val).or_insert(0) += 1;
+        });
+        let d2: u32 = v1.iter().map(|val| m.get(val).unwrap_or(&0) * val).sum();
+        d2.into()
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let lines = generate::size(sizes, "lines", 1..=1_000_000)?;
+        let left: Vec<i64> = (0..lines).map(|_| rng.between(10_000..=99_999)).collect();
+        let mut out = String::new();
+        for id in &left {
+            // some of the right list turn up on the left too, or part two is 0
+            let right = if rng.chance(0.3) {
+                *rng.pick(&left)
+            } else {
+                rng.between(10_000..=99_999)
+            };
+            out.push_str(&format!("{id}   {right}\n"));
+        }
+        Ok(Some(out))
+    }
 }
@@ -1,203 +1,87 @@
-use std::cmp::min;
 use std::collections::HashMap;
-use std::fs::read;
-use std::io::BufRead;
-use std::time::Instant;
-
-use itertools::Itertools;
-
-/// We are given some topographical maps that represent some height [0,9]
-/// we have a concept of a hiking trail that is a route that uses
-/// left, right, up, and down moves and uses all of the posible heights
-/// For each zero (trailhead) we need to count how many hiking routes
-/// there are and sum them over the whole map
-///
-
-/// A Move represents an action on a map
-pub enum Move {
-    Left,
-    Right,
-    Up,
-    Down,
-}
 
-#[derive(Debug, PartialEq)]
-pub struct Route {
-    positions: Vec<Position>,
-}
-
-#[derive(Debug, PartialEq)]
-pub enum Error {
-    InvalidMove,
-}
-
-impl Route {
-    /// initalises with a trailhead position (height=0)
-    pub fn new(trailhead: Position) -> Self {
-        let positions = vec![trailhead];
-        Route { positions }
-    }
-
-    /// actions a move
-    pub fn action_move(&self, m: &Move, map: &Map) -> Result<Self, Error> {
-        let last_pos = self.positions.last().unwrap().to_owned();
-        let current_value = map.grid.get(&last_pos).unwrap().value();
-        let mut result = Self {
-            positions: self.positions.clone(),
-        };
-        match m {
-            Move::Left => {
-                if last_pos.x == 0 {
-                    return Err(Error::InvalidMove);
-                }
-                let new_x = last_pos.x - 1;
-                let new_y = last_pos.y;
-                let new_pos = Position::new(new_x, new_y);
-                let next_value = map.grid.get(&new_pos).unwrap().value();
-                if (next_value > current_value) && (next_value - current_value == 1) {
-                    result.positions.push(new_pos);
-                    Ok(result)
-                } else {
-                    Err(Error::InvalidMove)
-                }
-            }
-            Move::Right => {
-                if last_pos.x == map.max_x - 1 {
-                    return Err(Error::InvalidMove);
-                }
-                let new_x = last_pos.x + 1;
-                let new_y = last_pos.y;
-                let new_pos = Position::new(new_x, new_y);
-                let next_value = map.grid.get(&new_pos).unwrap().value();
-                if (next_value > current_value) && (next_value - current_value == 1) {
-                    result.positions.push(new_pos);
-                    Ok(result)
-                } else {
-                    Err(Error::InvalidMove)
-                }
-            }
-            Move::Up => {
-                if last_pos.y == 0 {
-                    return Err(Error::InvalidMove);
-                }
-                let new_x = last_pos.x;
-                let new_y = last_pos.y - 1;
-                let new_pos = Position::new(new_x, new_y);
-                let next_value = map.grid.get(&new_pos).unwrap().value();
-                if (next_value > current_value) && (next_value - current_value == 1) {
-                    result.positions.push(new_pos);
-                    Ok(result)
-                } else {
-                    Err(Error::InvalidMove)
-                }
-            }
-            Move::Down => {
-                if last_pos.y == map.max_y - 1 {
-                    return Err(Error::InvalidMove);
-                }
-                let new_x = last_pos.x;
-                let new_y = last_pos.y + 1;
-                let new_pos = Position::new(new_x, new_y);
-                let next_value = map.grid.get(&new_pos).unwrap().value();
-                if (next_value > current_value) && (next_value - current_value == 1) {
-                    result.positions.push(new_pos);
-                    Ok(result)
-                } else {
-                    Err(Error::InvalidMove)
-                }
-            }
-        }
-    }
-}
+use crate::days::read_input;
+use crate::error::{self, Location, Result};
+use crate::generate::{self, Rng};
+use crate::geom::Position;
+use crate::graph::{self, Graph};
+use crate::grid::{Grid, Point};
+use crate::params::{Param, Params};
+use crate::solver::{self, Answer, Answers, Example, Solver};
+
+// We are given some topographical maps that represent some height [0,9]
+// we have a concept of a hiking trail that is a route that uses
+// left, right, up, and down moves and uses all of the posible heights
+// For each zero (trailhead) we need to count how many hiking routes
+// there are and sum them over the whole map
 
 /// A map is a grid with heights filling each position
 #[derive(Debug, PartialEq)]
 pub struct Map {
-    grid: HashMap<Position, Height>,
+    grid: Grid<Height>,
     trailheads: Vec<Position>,
-    max_x: usize,
-    max_y: usize,
 }
 
 impl Map {
-    /// read in a map from a vec grid which is the input
-    pub fn read(vec_grid: Vec<Vec<u8>>) -> Self {
-        let mut hmap: HashMap<Position, Height> = HashMap::new();
+    /// read in a map from a grid of the heights in the input
+    pub fn read(grid: Grid<u8>) -> Result<Self> {
         let mut trailheads = Vec::new();
-
-        for (j, row) in vec_grid.iter().enumerate() {
-            for (i, element) in row.iter().enumerate() {
-                let pos = Position::new(i, j);
-                let height = Height::new(element).unwrap();
-                hmap.insert(pos, height);
-                if *element == 0 {
-                    trailheads.push(pos);
-                }
+        for (point, element) in grid.iter() {
+            let (i, j) = point;
+            Height::new(element).ok_or_else(|| error::Error::InvalidHeight {
+                location: Location::new(j + 1, i + 1),
+                found: element.to_string(),
+            })?;
+            if *element == 0 {
+                trailheads.push(Position::from(point));
             }
         }
-        Self {
-            grid: hmap,
+        Ok(Self {
+            grid: grid.map(|element| Height(*element)),
             trailheads,
-            max_y: vec_grid.len(),
-            max_x: vec_grid.first().unwrap().len(),
-        }
+        })
     }
 
-    pub fn score(&self) -> (u64, u64) {
-        let all_routes = iterate_whole_routes(self);
-        let unique_routes: Vec<&Route> = all_routes
-            .iter()
-            .unique_by(|r| (r.positions.first().unwrap(), r.positions.last().unwrap()))
-            .collect();
-
-        (unique_routes.len() as u64, all_routes.len() as u64)
+    /// the trails as a graph, each point leading to the neighbours one higher
+    pub fn trails(&self) -> impl Graph<Node = Point> {
+        graph::from_fn(move |point: Point| {
+            let height = self.grid[point].value();
+            self.grid
+                .neighbours4(point)
+                .filter(move |&next| self.grid[next].value() == height + 1)
+        })
     }
-}
 
-/// take a vector of routes and calculate the next step for each
-/// if the next step is invalid, we drop the whole route.
-/// this is done inplace
-pub fn iterate_routes(routes: Vec<Route>, map: &Map) -> Vec<Route> {
-    let mut result = vec![];
-    let steps = [Move::Left, Move::Right, Move::Up, Move::Down];
-    for route in routes.iter() {
-        for s in steps.iter() {
-            match route.action_move(s, map) {
-                Ok(r) => result.push(r),
-                Err(_) => continue,
-            }
+    /// the score and rating summed over every trailhead. The score counts the
+    /// peaks a trailhead reaches and the rating counts the trails to them
+    pub fn score(&self) -> (u64, u64) {
+        let trails = self.trails();
+        let starts = self.trailheads.iter().filter_map(|t| t.to_point());
+        let score = starts
+            .clone()
+            .map(|start| {
+                graph::bfs(&trails, start)
+                    .into_keys()
+                    .filter(|&p| self.grid[p].value() == 9)
+                    .count() as u64
+            })
+            .sum();
+
+        // every step climbs so the trails can't loop, and walking them highest
+        // first means the trails on from a point are all counted before it
+        let order = graph::toposort(&trails, starts.clone()).expect("trails only go up");
+        let mut ratings = HashMap::new();
+        for &point in order.iter().rev() {
+            let rating = if self.grid[point].value() == 9 {
+                1
+            } else {
+                trails.neighbours(point).map(|next| ratings[&next]).sum()
+            };
+            ratings.insert(point, rating);
         }
-    }
-    result
-}
+        let rating = starts.map(|start| ratings[&start]).sum();
 
-/// take a map with start points and iterate until we find all of the routes
-/// that end up at an end point
-pub fn iterate_whole_routes(map: &Map) -> Vec<Route> {
-    let mut routes = map.trailheads.iter().map(|x| Route::new(*x)).collect();
-    let mut min_length = 1;
-
-    while min_length < 10 {
-        routes = iterate_routes(routes, map);
-        min_length = routes
-            .iter()
-            .map(|r| r.positions.len())
-            .reduce(|acc, e| min(e, acc))
-            .expect("possible routes");
-    }
-    routes
-}
-
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
-pub struct Position {
-    x: usize,
-    y: usize,
-}
-
-impl Position {
-    /// Position initialisation from two usizes
-    pub fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
+        (score, rating)
     }
 }
 
@@ -221,31 +105,107 @@ impl Height {
     }
 }
 
-pub fn day_ten(path: &str) -> std::io::Result<()> {
-    let now = Instant::now();
-    let content = read(path)?;
-    let data: Vec<String> = content.lines().map(|x| x.unwrap()).collect();
-    let rows: Vec<Vec<u8>> = data
-        .iter()
-        .map(|x| {
-            let dig: Vec<u8> = x.chars().map(|c| c.to_digit(10).unwrap() as u8).collect();
-            dig
-        })
-        .collect();
-    let map = Map::read(rows);
-    let score = map.score();
-    println!(
-        "the score is {} and {} and calculated in {:?}",
-        score.0,
-        score.1,
-        now.elapsed().as_micros()
-    );
+/// the trailheads' scores summed, then their ratings
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayTen>(input)
+}
+
+pub fn day_ten(path: &str) -> Result<()> {
+    let content = read_input(path)?;
+    let answers = solve(&content).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
     Ok(())
 }
 
+pub struct DayTen;
+
+impl Solver for DayTen {
+    const DAY: u8 = 10;
+    const NAME: &'static str = "day_ten";
+
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "width",
+            default: "50",
+            help: "tiles across the map",
+        },
+        Param {
+            name: "height",
+            default: "50",
+            help: "tiles down the map",
+        },
+        Param {
+            name: "trails",
+            default: "100",
+            help: "trails laid from a 0 to a 9, some crossing the others",
+        },
+    ];
+
+    const EXAMPLES: &'static [Example] =
+        &[Example::new(include_str!("examples/day10.txt"), "36", "81")];
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let heights = Grid::try_parse(input, |c, location| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or_else(|| error::Error::InvalidHeight {
+                    location,
+                    found: c.to_string(),
+                })
+        })?;
+        Map::read(heights)
+    }
+
+    fn part_one(input: &Self::Input) -> Answer {
+        input.score().0.into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        input.score().1.into()
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let width = generate::size(sizes, "width", 1..=1000)? as usize;
+        let height = generate::size(sizes, "height", 1..=1000)? as usize;
+        let trails = generate::size(sizes, "trails", 0..=1_000_000)?;
+        // random heights hardly ever make a trail by themselves, so trails are
+        // walked onto them a step up at a time, never crossing themselves
+        let mut heights = Grid::new(width, height, 0u8);
+        for point in heights.points() {
+            heights[point] = rng.below(10) as u8;
+        }
+        for _ in 0..trails {
+            let mut trail = vec![(rng.index(width), rng.index(height))];
+            while trail.len() < 10 {
+                let steps: Vec<Point> = heights
+                    .neighbours4(trail[trail.len() - 1])
+                    .filter(|p| !trail.contains(p))
+                    .collect();
+                if steps.is_empty() {
+                    break;
+                }
+                trail.push(*rng.pick(&steps));
+            }
+            for (level, &point) in trail.iter().enumerate() {
+                heights[point] = level as u8;
+            }
+        }
+        Ok(Some(heights.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::check;
+    use std::collections::HashSet;
+
+    fn heights(rows: Vec<Vec<u8>>) -> Grid<u8> {
+        Grid::from_rows(rows).unwrap()
+    }
 
     #[test]
     fn test_new_height() {
@@ -268,190 +228,68 @@ mod tests {
         assert_eq!(expected, actual)
     }
 
-    #[test]
-    fn test_new_route() {
-        let expected = Route {
-            positions: vec![Position::new(3, 4)],
-        };
-        let actual = Route::new(Position::new(3, 4));
-        assert_eq!(expected, actual)
-    }
-
-    #[test]
-    fn test_action_move_left() {
-        let map = Map::read(vec![
-            vec![0, 1, 2, 3],
-            vec![1, 2, 3, 4],
-            vec![8, 7, 6, 5],
-            vec![9, 8, 7, 6],
-        ]);
-        let actual = Route::new(Position::new(2, 2)).action_move(&Move::Left, &map);
-        let expected_positons = vec![Position::new(2, 2), Position::new(1, 2)];
-        let expected = Route {
-            positions: expected_positons,
-        };
-        assert_eq!(expected, actual.unwrap())
-    }
-
-    #[test]
-    fn test_action_move_right() {
-        let map = Map::read(vec![
-            vec![0, 1, 2, 3],
-            vec![1, 2, 3, 4],
-            vec![8, 7, 6, 5],
-            vec![9, 8, 7, 6],
-        ]);
-        let actual = Route::new(Position::new(1, 1)).action_move(&Move::Right, &map);
-        let expected_positons = vec![Position::new(1, 1), Position::new(2, 1)];
-        let expected = Route {
-            positions: expected_positons,
-        };
-        assert_eq!(expected, actual.unwrap())
-    }
-
-    #[test]
-    fn test_action_move_down() {
-        let map = Map::read(vec![
-            vec![0, 1, 2, 3],
-            vec![1, 2, 3, 4],
-            vec![8, 7, 6, 5],
-            vec![9, 8, 7, 6],
-        ]);
-        let actual = Route::new(Position::new(1, 0)).action_move(&Move::Down, &map);
-        let expected_positons = vec![Position::new(1, 0), Position::new(1, 1)];
-        let expected = Route {
-            positions: expected_positons,
-        };
-        assert_eq!(expected, actual.unwrap())
-    }
-
-    #[test]
-    fn test_action_move_up() {
-        let map = Map::read(vec![
-            vec![0, 1, 2, 3],
-            vec![1, 2, 3, 4],
-            vec![8, 8, 6, 5],
-            vec![9, 7, 7, 6],
-        ]);
-        let actual = Route::new(Position::new(1, 3)).action_move(&Move::Up, &map);
-        let expected_positons = vec![Position::new(1, 3), Position::new(1, 2)];
-        let expected = Route {
-            positions: expected_positons,
-        };
-        assert_eq!(expected, actual.unwrap())
-    }
-
-    #[test]
-    fn test_action_move_out_of_bounds() {
-        let map = Map::read(vec![
-            vec![0, 1, 2, 3],
-            vec![1, 2, 3, 4],
-            vec![8, 7, 6, 5],
-            vec![9, 8, 7, 6],
-        ]);
-        let actual = Route::new(Position::new(3, 0)).action_move(&Move::Right, &map);
-        assert_eq!(Err(Error::InvalidMove), actual)
-    }
-
     #[test]
     fn test_map_read() {
-        let actual = Map::read(vec![
+        let actual = Map::read(heights(vec![
             vec![0, 1, 2, 3],
             vec![1, 2, 3, 4],
             vec![8, 7, 6, 5],
             vec![9, 8, 7, 6],
-        ]);
-        let mut expected_hmap = HashMap::new();
-        expected_hmap.insert(Position::new(0, 0), Height(0));
-        expected_hmap.insert(Position::new(1, 0), Height(1));
-        expected_hmap.insert(Position::new(2, 0), Height(2));
-        expected_hmap.insert(Position::new(3, 0), Height(3));
-
-        expected_hmap.insert(Position::new(0, 1), Height(1));
-        expected_hmap.insert(Position::new(1, 1), Height(2));
-        expected_hmap.insert(Position::new(2, 1), Height(3));
-        expected_hmap.insert(Position::new(3, 1), Height(4));
-
-        expected_hmap.insert(Position::new(0, 2), Height(8));
-        expected_hmap.insert(Position::new(1, 2), Height(7));
-        expected_hmap.insert(Position::new(2, 2), Height(6));
-        expected_hmap.insert(Position::new(3, 2), Height(5));
-
-        expected_hmap.insert(Position::new(0, 3), Height(9));
-        expected_hmap.insert(Position::new(1, 3), Height(8));
-        expected_hmap.insert(Position::new(2, 3), Height(7));
-        expected_hmap.insert(Position::new(3, 3), Height(6));
-
+        ]))
+        .unwrap();
         let expected = Map {
-            grid: expected_hmap,
+            grid: heights(vec![
+                vec![0, 1, 2, 3],
+                vec![1, 2, 3, 4],
+                vec![8, 7, 6, 5],
+                vec![9, 8, 7, 6],
+            ])
+            .map(|h| Height(*h)),
             trailheads: vec![Position::new(0, 0)],
-            max_x: 4,
-            max_y: 4,
         };
 
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn test_iterate_step() {
-        let map = Map::read(vec![
+    fn test_trails() {
+        let map = Map::read(heights(vec![
             vec![0, 1, 2, 3],
             vec![1, 2, 3, 4],
             vec![8, 7, 6, 5],
             vec![9, 8, 7, 6],
-        ]);
-        let routes = map.trailheads.iter().map(|x| Route::new(*x)).collect();
-        let new_routes = iterate_routes(routes, &map);
-
-        let expected = vec![
-            Route {
-                positions: vec![Position::new(0, 0), Position::new(1, 0)],
-            },
-            Route {
-                positions: vec![Position::new(0, 0), Position::new(0, 1)],
-            },
-        ];
-
-        assert_eq!(expected, new_routes)
+        ]))
+        .unwrap();
+        let trails = map.trails();
+        let next: Vec<Point> = trails.neighbours((0, 0)).collect();
+        assert_eq!(next, vec![(1, 0), (0, 1)]);
+        let next: Vec<Point> = trails.neighbours((2, 2)).collect();
+        assert_eq!(next, vec![(2, 3), (1, 2)]);
+        assert_eq!(trails.neighbours((0, 3)).count(), 0);
     }
 
     #[test]
-    fn test_iterate_all() {
-        let map = Map::read(vec![
+    fn test_score_single_trail() {
+        let map = Map::read(heights(vec![
             vec![0, 1, 2, 3],
             vec![0, 2, 0, 4],
             vec![0, 0, 0, 5],
             vec![9, 8, 7, 6],
-        ]);
-
-        let actual = iterate_whole_routes(&map);
-
-        let expected = vec![Route {
-            positions: vec![
-                Position::new(0, 0),
-                Position::new(1, 0),
-                Position::new(2, 0),
-                Position::new(3, 0),
-                Position::new(3, 1),
-                Position::new(3, 2),
-                Position::new(3, 3),
-                Position::new(2, 3),
-                Position::new(1, 3),
-                Position::new(0, 3),
-            ],
-        }];
+        ]))
+        .unwrap();
 
-        assert_eq!(expected, actual)
+        assert_eq!(map.score(), (1, 1));
     }
 
     #[test]
     fn test_score() {
-        let map = Map::read(vec![
+        let map = Map::read(heights(vec![
             vec![0, 1, 2, 3],
             vec![1, 2, 3, 4],
             vec![8, 7, 6, 5],
             vec![9, 8, 7, 6],
-        ]);
+        ]))
+        .unwrap();
         let expected = (1, 16);
         let actual = map.score();
 
@@ -460,15 +298,131 @@ mod tests {
 
     #[test]
     fn test_score_again() {
-        let map = Map::read(vec![
+        let map = Map::read(heights(vec![
             vec![0, 1, 2, 3],
             vec![1, 0, 3, 4],
             vec![8, 9, 6, 5],
             vec![9, 8, 7, 6],
-        ]);
+        ]))
+        .unwrap();
         let expected = (4, 16);
         let actual = map.score();
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = DayTen::parse("0123\n12x4").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 3)));
+        assert!(matches!(
+            error,
+            error::Error::InvalidHeight { ref found, .. } if found == "x"
+        ));
+        let error = Map::read(heights(vec![vec![0, 12]])).unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(1, 2)));
+    }
+
+    #[test]
+    fn test_no_trailheads() {
+        let map = DayTen::parse("123\n456").unwrap();
+        assert_eq!(map.score(), (0, 0));
+    }
+
+    /// walk every trail from every trailhead, counting the peaks reached and
+    /// every way of reaching them
+    fn naive_score(rows: &[Vec<u8>]) -> (u64, u64) {
+        fn walk(rows: &[Vec<u8>], x: usize, y: usize, peaks: &mut HashSet<(usize, usize)>) -> u64 {
+            let height = rows[y][x];
+            if height == 9 {
+                peaks.insert((x, y));
+                return 1;
+            }
+            let steps = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            steps
+                .into_iter()
+                .filter(|&(nx, ny)| rows.get(ny).and_then(|r| r.get(nx)) == Some(&(height + 1)))
+                .map(|(nx, ny)| walk(rows, nx, ny, peaks))
+                .sum()
+        }
+        let (mut score, mut rating) = (0, 0);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &height) in row.iter().enumerate() {
+                if height == 0 {
+                    let mut peaks = HashSet::new();
+                    rating += walk(rows, x, y, &mut peaks);
+                    score += peaks.len() as u64;
+                }
+            }
+        }
+        (score, rating)
+    }
+
+    /// small maps from the day's own generator, so there are trails on them
+    fn map(rng: &mut Rng, size: usize) -> Vec<Vec<u8>> {
+        let mut sizes = Params::new();
+        sizes.set("width", (1 + rng.index(size.min(8))).to_string());
+        sizes.set("height", (1 + rng.index(size.min(8))).to_string());
+        sizes.set("trails", rng.below(size as u64 / 2 + 1).to_string());
+        let sizes = sizes.resolve(DayTen::SIZES).unwrap();
+        let map = DayTen::generate(rng, &sizes).unwrap().unwrap();
+        map.lines()
+            .map(|l| l.bytes().map(|b| b - b'0').collect())
+            .collect()
+    }
+
+    /// the map without one of its rows or columns, then with one of its
+    /// heights lower
+    fn shrink_map(rows: &[Vec<u8>]) -> Vec<Vec<Vec<u8>>> {
+        let mut smaller = Vec::new();
+        if rows.len() > 1 {
+            for y in 0..rows.len() {
+                let mut without = rows.to_vec();
+                without.remove(y);
+                smaller.push(without);
+            }
+        }
+        if rows[0].len() > 1 {
+            for x in 0..rows[0].len() {
+                let mut without = rows.to_vec();
+                without.iter_mut().for_each(|row| {
+                    row.remove(x);
+                });
+                smaller.push(without);
+            }
+        }
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &height) in row.iter().enumerate() {
+                for lower in check::shrink_number(height as u64, 0) {
+                    let mut with = rows.to_vec();
+                    with[y][x] = lower as u8;
+                    smaller.push(with);
+                }
+            }
+        }
+        smaller
+    }
+
+    #[test]
+    fn test_score_against_naive() {
+        let example: Vec<Vec<u8>> = include_str!("examples/day10.txt")
+            .lines()
+            .map(|l| l.bytes().map(|b| b - b'0').collect())
+            .collect();
+        assert_eq!(naive_score(&example), (36, 81));
+        check::check(
+            "scoring the trails",
+            map,
+            |m| shrink_map(m),
+            |rows| {
+                let map = Map::read(heights(rows.clone())).unwrap();
+                check::equal("the score and rating", map.score(), naive_score(rows))
+            },
+        );
+    }
 }
@@ -1,76 +1,69 @@
-use std::{
-    collections::HashMap,
-    fs::{read, read_to_string},
-    time::Instant,
-};
+use crate::days::read_input;
+use crate::error::Result;
+use crate::generate::{self, Rng};
+use crate::memo::Memo;
+use crate::params::{Param, Params};
+use crate::parse;
+use crate::solver::{self, Answer, Answers, Example, Solver};
 
 /// ok so we have a setup on a line (an array of integers)
 /// on each blink (step)
 /// each integer can do 1 of 3 things
 /// - if the integer is 0 it becomes 1
 /// - if the number of digits is even then it splits into two numbers the first half of the
-///     digits followed by the second half i.e. 10 -> 1, 0.
+///   digits followed by the second half i.e. 10 -> 1, 0.
 /// - if none of the above two apply then the digit gets multiplied by 2024
 ///
 ///
 /// so initial thoughts is have a Line struct which is an array of Stones
 /// and implement a blink step which moves left to right evolving a Stone
-
+///
 /// stone represents a stone with an integer carved on it that can transform in
 /// three ways zeroToOne, Split, Multiply
 
+// a stone of 17 digits times 2024 is already too big for a u64
 #[derive(Debug, Eq, Hash, PartialEq, Clone)]
-pub struct Stone(u64);
+pub struct Stone(u128);
 
 /// A line of stones which evolves on each blink into a new Line
 #[derive(Debug, PartialEq)]
 pub struct Line {
     line: Vec<Stone>,
-    memo: HashMap<(Stone, usize), u64>,
+    memo: Memo<(Stone, usize), u128>,
 }
 
 impl Line {
     pub fn new(stones: Vec<u64>) -> Self {
-        let memo = HashMap::new();
         Self {
-            line: stones.iter().map(|n| Stone(*n)).collect(),
-            memo,
+            line: stones.iter().map(|&n| Stone(n.into())).collect(),
+            memo: Memo::new(),
         }
     }
 }
 
-pub fn blink_stones_n(
-    stones: Vec<Stone>,
-    n: usize,
-    memo: &mut HashMap<(Stone, usize), u64>,
-) -> u64 {
+/// the most blinks part two takes. The line grows about half again every
+/// blink, so counting in a u128 there's room for any line this long
+const MOST_BLINKS: u64 = 150;
+
+pub fn blink_stones_n(stones: Vec<Stone>, n: usize, memo: &mut Memo<(Stone, usize), u128>) -> u128 {
     stones.iter().map(|s| blink_n(s.clone(), n, memo)).sum()
 }
 
-pub fn blink_n(stone: Stone, n: usize, memo: &mut HashMap<(Stone, usize), u64>) -> u64 {
-    // ok so given a stone and steps i want to recursively count the
-    // number of stones it generates
-    //
+/// the number of stones `stone` turns into after `n` blinks. The same stones
+/// come up again and again so the counts are cached
+pub fn blink_n(stone: Stone, n: usize, memo: &mut Memo<(Stone, usize), u128>) -> u128 {
     if n == 0 {
         return 1;
     }
-    if let Some(cached) = memo.get(&(stone.clone(), n)) {
-        return *cached;
-    }
-    let mut result = 0;
-
-    let stones = stone.blink();
-    if stones.len() == 1 {
-        result += blink_n(stones.first().unwrap().clone(), n - 1, memo);
-    } else {
-        result += blink_n(stones.first().unwrap().clone(), n - 1, memo)
-            + blink_n(stones.get(1).unwrap().clone(), n - 1, memo);
-    }
-
-    memo.insert((stone, n), result);
-
-    result
+    memo.get_or_compute((stone.clone(), n), |memo| {
+        stone
+            .blink()
+            .into_iter()
+            .map(|s| blink_n(s, n - 1, memo))
+            .sum()
+    })
 }
+
 impl Stone {
     pub fn blink(&self) -> Vec<Self> {
         if self.0 == 0 {
@@ -87,7 +80,7 @@ impl Stone {
             return false;
         }
         let digits = self.0.ilog10() + 1;
-        if digits % 2 == 0 {
+        if digits.is_multiple_of(2) {
             return true;
         }
         false
@@ -95,7 +88,7 @@ impl Stone {
 
     fn split(&self) -> Vec<Self> {
         let digits = self.0.ilog10() + 1;
-        let base: u64 = 10;
+        let base: u128 = 10;
         let denom = base.pow(digits / 2);
 
         let first_set = self.0 % denom;
@@ -104,30 +97,98 @@ impl Stone {
     }
 }
 
-pub fn day_eleven(path: &str) -> std::io::Result<()> {
-    let now = Instant::now();
-    let content = read_to_string(path)?;
-    let digits: Vec<u64> = content
-        .split_whitespace()
-        .map(|s| s.parse::<u64>().unwrap())
-        .collect();
+/// the stones after 25 blinks, then after as many as part two blinks
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayEleven>(input)
+}
+
+pub fn day_eleven(path: &str) -> Result<()> {
+    let content = read_input(path)?;
+    let answers = solve(&content).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
+    Ok(())
+}
+
+/// The engraved stones and how many times part two blinks at them
+#[derive(Debug)]
+pub struct Stones {
+    stones: Vec<u64>,
+    blinks: usize,
+}
+
+pub struct DayEleven;
 
-    let mut line = Line::new(digits);
-    println!("the line is {:?}", line);
+impl Solver for DayEleven {
+    const DAY: u8 = 11;
+    const NAME: &'static str = "day_eleven";
+    const PARAMS: &'static [Param] = &[Param {
+        name: "blinks",
+        default: "75",
+        help: "how many times part two blinks, up to 150",
+    }];
 
-    let result = blink_stones_n(line.line, 75, &mut line.memo);
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "stones",
+            default: "8",
+            help: "stones in the line",
+        },
+        Param {
+            name: "largest",
+            default: "9999999",
+            help: "the biggest number engraved on a stone",
+        },
+    ];
 
-    println!(
-        "the score is {} and  and calculated in {:?}",
-        result,
-        now.elapsed().as_micros()
-    );
-    Ok(())
+    // the puzzle doesn't give part two for the example, this is the known answer
+    const EXAMPLES: &'static [Example] = &[Example::new(
+        include_str!("examples/day11.txt"),
+        "55312",
+        "65601038650482",
+    )];
+
+    type Input = Stones;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::new().resolve(Self::PARAMS)?)
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        let stones = parse::numbers(input, input, "a stone number")?;
+        Ok(Stones {
+            stones,
+            blinks: params.get_within("blinks", 0..=MOST_BLINKS)? as usize,
+        })
+    }
+
+    fn part_one(input: &Self::Input) -> Answer {
+        let mut line = Line::new(input.stones.clone());
+        blink_stones_n(line.line, 25, &mut line.memo).into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        let mut line = Line::new(input.stones.clone());
+        log::trace!("the line is {line:?}");
+        let stones = blink_stones_n(line.line, input.blinks, &mut line.memo);
+        log::debug!("{} blinks, {}", input.blinks, line.memo.stats());
+        stones.into()
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let stones = generate::size(sizes, "stones", 1..=100_000)?;
+        let largest = generate::size(sizes, "largest", 0..=u32::MAX as u64)?;
+        let stones: Vec<String> = (0..stones)
+            .map(|_| rng.below(largest + 1).to_string())
+            .collect();
+        Ok(Some(stones.join(" ") + "\n"))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::check;
 
     #[test]
     fn test_stone_blink() {
@@ -147,9 +208,17 @@ mod test {
         assert_eq!(expected_else, input_else.blink());
     }
 
+    #[test]
+    fn test_stone_blink_big() {
+        let stone = Stone(10_000_000_000_000_000);
+        assert_eq!(stone.blink(), vec![Stone(20_240_000_000_000_000_000)]);
+        let mut line = Line::new(vec![u64::MAX]);
+        assert_eq!(blink_stones_n(line.line, 3, &mut line.memo), 4);
+    }
+
     #[test]
     fn test_blink_n() {
-        let mut memo = HashMap::new();
+        let mut memo = Memo::new();
         let actual = blink_stones_n(vec![Stone(125), Stone(17)], 6, &mut memo);
         let expected = vec![
             Stone(2097446912),
@@ -175,7 +244,8 @@ mod test {
             Stone(3),
             Stone(2),
         ];
-        assert_eq!(expected.len() as u64, actual);
+        assert_eq!(expected.len() as u128, actual);
+        assert!(memo.stats().hits > 0);
     }
 
     #[test]
@@ -214,11 +284,93 @@ mod test {
         let input = vec![1, 2, 3, 4];
         let expected = Line {
             line: vec![Stone(1), Stone(2), Stone(3), Stone(4)],
-            memo: HashMap::new(),
+            memo: Memo::new(),
         };
 
         let actual = Line::new(input);
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_blinks_param() {
+        let mut params = Params::new();
+        params.set("blinks", "6");
+        let answers = solver::solve_with::<DayEleven>("125 17", &params).unwrap();
+        assert_eq!(answers.part_two, Answer::Number(22));
+    }
+
+    #[test]
+    fn test_blinks_param_range() {
+        let mut params = Params::new();
+        params.set("blinks", "1000");
+        let error = solver::solve_with::<DayEleven>("125 17", &params).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "parameter 'blinks' is 1000, it has to be from 0 to 150"
+        );
+        // the most blinks there can be is past what a u64 can count
+        params.set("blinks", "150");
+        let stones = format!("{} 0 9", u64::MAX);
+        let answers = solver::solve_with::<DayEleven>(&stones, &params).unwrap();
+        assert!(matches!(answers.part_two, Answer::Text(_)));
+    }
+
+    /// blink one stone at a time with the rules as the puzzle puts them, on
+    /// the digits written out
+    fn naive_blinks(stones: &[u64], blinks: usize) -> u128 {
+        let mut line: Vec<String> = stones.iter().map(|s| s.to_string()).collect();
+        for _ in 0..blinks {
+            let mut next = Vec::new();
+            for stone in line {
+                if stone == "0" {
+                    next.push("1".to_string());
+                } else if stone.len() % 2 == 0 {
+                    let (left, right) = stone.split_at(stone.len() / 2);
+                    next.push(left.to_string());
+                    // the right half loses its leading zeros
+                    let right = right.trim_start_matches('0');
+                    next.push(if right.is_empty() { "0" } else { right }.to_string());
+                } else {
+                    let times = stone.parse::<u128>().unwrap() * 2024;
+                    next.push(times.to_string());
+                }
+            }
+            line = next;
+        }
+        line.len() as u128
+    }
+
+    /// a few stones of any number of digits and blinks at them
+    fn stones(rng: &mut Rng, size: usize) -> (Vec<u64>, usize) {
+        let stones = (0..=rng.below(3))
+            .map(|_| {
+                let digits = rng.below(20) as u32;
+                rng.below(10u64.saturating_pow(digits).max(10))
+            })
+            .collect();
+        (stones, rng.index(size.min(20) + 1))
+    }
+
+    fn shrink_stones((stones, blinks): &(Vec<u64>, usize)) -> Vec<(Vec<u64>, usize)> {
+        let fewer_blinks = check::shrink_number(*blinks as u64, 0)
+            .into_iter()
+            .map(|b| (stones.clone(), b as usize));
+        let smaller_stones = check::shrink_vec(stones, |&s| check::shrink_number(s, 0))
+            .into_iter()
+            .map(|s| (s, *blinks));
+        fewer_blinks.chain(smaller_stones).collect()
+    }
+
+    #[test]
+    fn test_blinks_against_naive() {
+        check::check("blinking", stones, shrink_stones, |(stones, blinks)| {
+            let mut line = Line::new(stones.clone());
+            check::equal(
+                "the stones",
+                blink_stones_n(line.line, *blinks, &mut line.memo),
+                naive_blinks(stones, *blinks),
+            )
+        });
+    }
 }
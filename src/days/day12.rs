@@ -11,7 +11,15 @@
 // by moving left-right top-down it means by looking above and then left we
 // can check if there is a group that already exists for that plant
 
-use std::{fs::read, io::BufRead, time::Instant};
+use crate::days::read_input;
+use crate::error::Result;
+use crate::generate::{self, Rng};
+use crate::geom::Vector;
+use crate::graph;
+use crate::grid::{Grid, Point};
+use crate::params::{Param, Params};
+use crate::solver::{self, Answer, Answers, Example, Solver};
+use crate::visual::{Cell, Colour, Frame, Frames};
 
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub struct Plant {
@@ -38,108 +46,57 @@ impl Plant {
 
 #[derive(PartialEq, Debug)]
 pub struct Map {
-    plants: Vec<Vec<Plant>>,
+    plants: Grid<Plant>,
     n_groups: Option<usize>,
-    size_i: usize,
-    size_j: usize,
 }
 
 impl Map {
-    pub fn new(grid: Vec<Vec<char>>) -> Self {
-        let plants: Vec<Vec<Plant>> = grid
-            .iter()
-            .enumerate()
-            .map(|(j, row)| {
-                row.iter()
-                    .enumerate()
-                    .map(|(i, c)| Plant::new(*c, i, j))
-                    .collect()
-            })
-            .collect();
-        let size_i = plants.first().unwrap().len();
-        let size_j = plants.len();
+    pub fn new(grid: Grid<char>) -> Self {
+        let plants = Grid::from_rows(
+            grid.rows()
+                .enumerate()
+                .map(|(j, row)| {
+                    row.iter()
+                        .enumerate()
+                        .map(|(i, c)| Plant::new(*c, i, j))
+                        .collect()
+                })
+                .collect(),
+        )
+        .expect("the rows of a grid are all the same length");
         Self {
             plants,
             n_groups: None,
-            size_i,
-            size_j,
         }
     }
 
-    pub fn print(&self) {
-        for row in self.plants.iter() {
-            row.iter()
-                .for_each(|e| print!("{}:{} ", e.label, e.group.unwrap()));
-            println!();
-        }
+    /// the plants coloured by the region they're in, so neighbouring regions
+    /// of the same plant can be told apart
+    pub fn frame(&self) -> Frame {
+        let cells = self.plants.map(|p| match p.group {
+            Some(group) => Cell::new(p.label, Colour::cycle(group)),
+            None => Cell::plain(p.label),
+        });
+        let regions = self.n_groups.unwrap_or_default();
+        Frame::new(cells, format!("{regions} regions"))
     }
+
     fn count_edges(&mut self, i: usize, j: usize) {
         // so we want to take the plant at i and j and check all the corners
-        let mut is_neighbour_left = false;
-        let max_i = self.size_i - 1;
-        let max_j = self.size_j - 1;
-        let plant_grid = self.plants.clone();
-
-        if i > 0
-            && plant_grid.get(j).unwrap().get(i).unwrap().label
-                == plant_grid.get(j).unwrap().get(i - 1).unwrap().label
-        {
-            is_neighbour_left = true;
-        }
-        let mut is_neighbour_right = false;
-        if i < max_i
-            && plant_grid.get(j).unwrap().get(i).unwrap().label
-                == plant_grid.get(j).unwrap().get(i + 1).unwrap().label
-        {
-            is_neighbour_right = true;
-        }
-        let mut is_neighbour_below = false;
-        if j < max_j
-            && plant_grid.get(j).unwrap().get(i).unwrap().label
-                == plant_grid.get(j + 1).unwrap().get(i).unwrap().label
-        {
-            is_neighbour_below = true;
-        }
-        let mut is_neighbour_above = false;
-        if j > 0
-            && plant_grid.get(j).unwrap().get(i).unwrap().label
-                == plant_grid.get(j - 1).unwrap().get(i).unwrap().label
-        {
-            is_neighbour_above = true;
-        }
-
-        let mut is_neighbour_above_right_diag = false;
-        if j > 0
-            && i < max_i
-            && plant_grid.get(j).unwrap().get(i).unwrap().label
-                == plant_grid.get(j - 1).unwrap().get(i + 1).unwrap().label
-        {
-            is_neighbour_above_right_diag = true;
-        }
-        let mut is_neighbour_below_right_diag = false;
-        if j < max_j
-            && i < max_i
-            && plant_grid.get(j).unwrap().get(i).unwrap().label
-                == plant_grid.get(j + 1).unwrap().get(i + 1).unwrap().label
-        {
-            is_neighbour_below_right_diag = true;
-        }
-        let mut is_neighbour_above_left_diag = false;
-        if i > 0
-            && j > 0
-            && plant_grid.get(j).unwrap().get(i).unwrap().label
-                == plant_grid.get(j - 1).unwrap().get(i - 1).unwrap().label
-        {
-            is_neighbour_above_left_diag = true;
-        }
-        let mut is_neighbour_below_left_diag = false;
-        if i > 0
-            && j < max_j
-            && plant_grid.get(j).unwrap().get(i).unwrap().label
-                == plant_grid.get(j + 1).unwrap().get(i - 1).unwrap().label
-        {
-            is_neighbour_below_left_diag = true;
-        }
+        let label = self.plants[(i, j)].label;
+        let is_neighbour = |x, y| {
+            self.plants
+                .offset((i, j), Vector::new(x, y))
+                .is_some_and(|p| self.plants[p].label == label)
+        };
+        let is_neighbour_left = is_neighbour(-1, 0);
+        let is_neighbour_right = is_neighbour(1, 0);
+        let is_neighbour_below = is_neighbour(0, 1);
+        let is_neighbour_above = is_neighbour(0, -1);
+        let is_neighbour_above_right_diag = is_neighbour(1, -1);
+        let is_neighbour_below_right_diag = is_neighbour(1, 1);
+        let is_neighbour_above_left_diag = is_neighbour(-1, -1);
+        let is_neighbour_below_left_diag = is_neighbour(-1, 1);
 
         let mut edges = 0;
 
@@ -169,18 +126,13 @@ impl Map {
             edges += 1;
         }
 
-        let current_plant = self.plants.get_mut(j).unwrap().get_mut(i).unwrap();
-        current_plant.edges = Some(edges);
+        self.plants[(i, j)].edges = Some(edges);
     }
     // this calculates the cost of Map by area * perimeter of each group summed
     pub fn get_cost(&self) -> u64 {
         let mut cost = 0;
         for i in 0..self.n_groups.unwrap() {
-            let group = self
-                .plants
-                .iter()
-                .flatten()
-                .filter(|p| p.group.unwrap() == i);
+            let group = self.plants.cells().filter(|p| p.group.unwrap() == i);
             let perimeter: u64 = group.clone().map(|p| p.borders.unwrap() as u64).sum();
             let area: u64 = group.count() as u64;
             cost += area * perimeter;
@@ -191,171 +143,157 @@ impl Map {
     pub fn get_cost_2(&self) -> u64 {
         let mut cost = 0;
         for i in 0..self.n_groups.unwrap() {
-            let group = self
-                .plants
-                .iter()
-                .flatten()
-                .filter(|p| p.group.unwrap() == i);
+            let group = self.plants.cells().filter(|p| p.group.unwrap() == i);
             let number_external_edges: u64 = group.clone().map(|p| p.edges.unwrap() as u64).sum();
             let area: u64 = group.count() as u64;
-            println!(
-                "i:{}, number_edges {}, area {}, cost {}",
-                i,
-                number_external_edges,
-                area,
-                area * number_external_edges,
+            log::debug!(
+                "region {i}: {number_external_edges} sides, area {area}, cost {}",
+                area * number_external_edges
             );
             cost += area * number_external_edges;
         }
         cost
     }
 
+    /// split the map into its regions, numbering them in the order their
+    /// first plant is found reading left to right and top to bottom
     pub fn find_neighbours_and_borders(&mut self) {
-        // ok so im going to want to recursively look for neighbours
-        // so for each element i want to then calculate the
-        // borders and the neighbours and then
-        // call that function on the neighbours
-        // i probably want some kind of flag
-        let mut starting_index = 0;
-
-        for j in 0..self.size_j {
-            for i in 0..self.size_i {
-                let is_new = Self::search((i, j), None, None, self, Some(starting_index));
-                if !is_new {
-                    starting_index += 1;
-                }
+        let mut groups = 0;
+        for point in self.plants.points() {
+            if self.plants[point].group.is_none() {
+                self.fill(point, groups);
+                groups += 1;
             }
         }
-        self.n_groups = Some(starting_index);
+        self.n_groups = Some(groups);
     }
 
-    fn search(
-        start_plant_pos: (usize, usize),
-        parent_plant_pos: Option<(usize, usize)>,
-        parent_group: Option<usize>,
-        plant_grid: &mut Self,
-        starting_group_index: Option<usize>,
-    ) -> bool {
-        let mut positions: Vec<(usize, usize)> = vec![];
-        let (i, j) = (start_plant_pos.0, start_plant_pos.1);
-        if plant_grid
-            .plants
-            .get(j)
-            .unwrap()
-            .get(i)
-            .unwrap()
-            .group
-            .is_some()
-        {
-            return true;
-        }
-        // left
-        if i > 0 {
-            positions.push((i - 1, j));
-        }
-        // up
-        if j > 0 {
-            positions.push((i, j - 1));
+    /// put every plant in the region around `start` in `group`, working out
+    /// its borders and corners as it goes
+    fn fill(&mut self, start: Point, group: usize) {
+        let region: Vec<Point> = {
+            let plants = &self.plants;
+            let same = graph::from_fn(|p: Point| {
+                plants
+                    .neighbours4(p)
+                    .filter(move |&n| plants[n].label == plants[p].label)
+            });
+            graph::bfs(&same, start).into_keys().collect()
+        };
+        for point in region {
+            let label = self.plants[point].label;
+            let neighbours = self
+                .plants
+                .neighbours4(point)
+                .filter(|&n| self.plants[n].label == label)
+                .count();
+            self.count_edges(point.0, point.1);
+            let plant = &mut self.plants[point];
+            plant.borders = Some(4 - neighbours);
+            plant.group = Some(group);
         }
+    }
+}
 
-        // right
-        if i < plant_grid.size_i - 1 {
-            positions.push((i + 1, j));
-        }
-        // down
-        if j < plant_grid.size_j - 1 {
-            positions.push((i, j + 1));
-        }
+/// the price of fencing every region by its perimeter, then by its sides
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayTwelve>(input)
+}
 
-        let neighbours: Vec<&(usize, usize)> = positions
-            .iter()
-            .filter(|(k, l)| {
-                // neightbours with same label
-                plant_grid.plants.get(*l).unwrap().get(*k).unwrap().label
-                    == plant_grid.plants.get(j).unwrap().get(i).unwrap().label
-            })
-            .collect();
-
-        let num_neighbours = neighbours.len();
-
-        plant_grid.count_edges(i, j);
-
-        let borders = 4 - num_neighbours;
-        let mut new_parent_group = None;
-        {
-            {
-                let current_plant = plant_grid.plants.get_mut(j).unwrap().get_mut(i).unwrap();
-                current_plant.borders = Some(borders);
-            }
-            {}
-            match parent_group {
-                Some(p) => {
-                    let current_plant = plant_grid.plants.get_mut(j).unwrap().get_mut(i).unwrap();
-                    current_plant.group = Some(p);
-                    new_parent_group = Some(p)
-                }
-                None => {
-                    let current_plant = plant_grid.plants.get_mut(j).unwrap().get_mut(i).unwrap();
-                    // this is panicking as its come up as None
-                    current_plant.group = Some(starting_group_index.unwrap());
-                    new_parent_group = Some(starting_group_index.unwrap());
+pub fn day_twelve(path: &str) -> Result<()> {
+    let content = read_input(path)?;
+    let answers = solve(&content).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
+    Ok(())
+}
+
+pub struct DayTwelve;
+
+impl Solver for DayTwelve {
+    const DAY: u8 = 12;
+    const NAME: &'static str = "day_twelve";
+
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "width",
+            default: "140",
+            help: "plots across the garden",
+        },
+        Param {
+            name: "height",
+            default: "140",
+            help: "plots down the garden",
+        },
+        Param {
+            name: "plants",
+            default: "26",
+            help: "different plants, up to 26",
+        },
+    ];
+
+    const EXAMPLES: &'static [Example] = &[Example::new(
+        include_str!("examples/day12.txt"),
+        "1930",
+        "1206",
+    )];
+
+    type Input = Map;
+
+    /// the regions are found here as both parts price the same regions
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut map = Map::new(input.parse()?);
+        map.find_neighbours_and_borders();
+        Ok(map)
+    }
+
+    fn part_one(input: &Self::Input) -> Answer {
+        input.get_cost().into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        input.get_cost_2().into()
+    }
+
+    fn visualize(input: &Self::Input) -> Option<Frames> {
+        Some(Box::new(std::iter::once(input.frame())))
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let width = generate::size(sizes, "width", 1..=1000)? as usize;
+        let height = generate::size(sizes, "height", 1..=1000)? as usize;
+        let plants = generate::size(sizes, "plants", 1..=26)? as u8;
+        let labels: Vec<char> = (b'A'..b'A' + plants).map(char::from).collect();
+        // a plot mostly has the same plant as the one left of it or above it,
+        // so the plants grow in regions rather than all on their own
+        let mut garden = Grid::new(width, height, 'A');
+        for (x, y) in garden.points() {
+            let left = x.checked_sub(1).map(|x| garden[(x, y)]);
+            let above = y.checked_sub(1).map(|y| garden[(x, y)]);
+            garden[(x, y)] = match (left, above) {
+                (Some(left), Some(above)) if rng.chance(0.85) => {
+                    if rng.chance(0.5) {
+                        left
+                    } else {
+                        above
+                    }
                 }
-            }
-        }
-        let neighbours_new: Vec<&(usize, usize)> = neighbours
-            .iter()
-            .copied()
-            .filter(|n| {
-                // only keep neighbours that dont have a group already
-                plant_grid
-                    .plants
-                    .get(n.1)
-                    .unwrap()
-                    .get(n.0)
-                    .unwrap()
-                    .group
-                    .is_none()
-            })
-            .collect();
-        for n in neighbours_new {
-            Self::search(
-                *n,
-                Some(start_plant_pos),
-                parent_group,
-                plant_grid,
-                new_parent_group,
-            );
+                (Some(plant), None) | (None, Some(plant)) if rng.chance(0.85) => plant,
+                _ => *rng.pick(&labels),
+            };
         }
-        false
+        Ok(Some(garden.to_string()))
     }
 }
 
-pub fn day_twelve(path: &str) -> std::io::Result<()> {
-    let now = Instant::now();
-    let content = read(path)?;
-    let data: Vec<Vec<char>> = content
-        .lines()
-        .map(|l| l.unwrap().chars().collect())
-        .collect();
-
-    let mut map = Map::new(data);
-    map.find_neighbours_and_borders();
-    let result = map.get_cost();
-    let result_2 = map.get_cost_2();
-
-    println!(
-        "the cost of the fence is {} or with discount {} and it took {}",
-        result,
-        result_2,
-        now.elapsed().as_micros(),
-    );
-    Ok(())
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn labels(rows: Vec<Vec<char>>) -> Grid<char> {
+        Grid::from_rows(rows).unwrap()
+    }
+
     #[test]
     fn test_map_new() {
         let plant_a = Plant {
@@ -391,71 +329,69 @@ mod test {
             edges: None,
         };
         let expected = Map {
-            plants: vec![vec![plant_a, plant_b, plant_c, plant_d]],
-            size_i: 4,
-            size_j: 1,
+            plants: Grid::from_rows(vec![vec![plant_a, plant_b, plant_c, plant_d]]).unwrap(),
             n_groups: None,
         };
-        let actual = Map::new(vec![vec!['a', 'b', 'c', 'd']]);
+        let actual = Map::new(labels(vec![vec!['a', 'b', 'c', 'd']]));
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn test_map_search() {
-        let mut map = Map::new(vec![vec!['a', 'b', 'b', 'd']]);
-        let is_new = Map::search((0, 0), None, None, &mut map, Some(0));
-        let actual_borders = map.plants.get(0).unwrap().get(0).unwrap().borders;
-        let actual_group = map.plants.get(0).unwrap().get(0).unwrap().group;
-        let actual_edges = map.plants.get(0).unwrap().get(0).unwrap().edges;
+    fn test_map_fill() {
+        let mut map = Map::new(labels(vec![vec!['a', 'b', 'b', 'd']]));
+        map.fill((0, 0), 0);
+        let actual_borders = map.plants[(0, 0)].borders;
+        let actual_group = map.plants[(0, 0)].group;
+        let actual_edges = map.plants[(0, 0)].edges;
         assert_eq!(Some(4), actual_borders);
         assert_eq!(Some(0), actual_group);
         assert_eq!(Some(4), actual_edges);
-        let expected_group = map.plants.get(0).unwrap().get(1).unwrap().group;
+        let expected_group = map.plants[(1, 0)].group;
         assert_eq!(expected_group, None);
-        let expected_group = map.plants.get(0).unwrap().get(2).unwrap().group;
+        let expected_group = map.plants[(2, 0)].group;
         assert_eq!(expected_group, None);
-        let expected_group = map.plants.get(0).unwrap().get(3).unwrap().group;
+        let expected_group = map.plants[(3, 0)].group;
         assert_eq!(expected_group, None);
     }
     #[test]
-    fn test_map_search_again() {
-        let mut map = Map::new(vec![vec!['a', 'b', 'b', 'd']]);
-        Map::search((1, 0), None, None, &mut map, Some(0));
-        let actual_borders = map.plants.get(0).unwrap().get(1).unwrap().borders;
-        let actual_group = map.plants.get(0).unwrap().get(1).unwrap().group;
-        let actual_edges = map.plants.get(0).unwrap().get(1).unwrap().edges;
+    fn test_map_fill_again() {
+        let mut map = Map::new(labels(vec![vec!['a', 'b', 'b', 'd']]));
+        map.fill((1, 0), 0);
+        let actual_borders = map.plants[(1, 0)].borders;
+        let actual_group = map.plants[(1, 0)].group;
+        let actual_edges = map.plants[(1, 0)].edges;
         assert_eq!(Some(3), actual_borders);
         assert_eq!(Some(0), actual_group);
         assert_eq!(Some(2), actual_edges);
-        let actual_borders = map.plants.get(0).unwrap().get(2).unwrap().borders;
-        let actual_group = map.plants.get(0).unwrap().get(2).unwrap().group;
-        let actual_edges = map.plants.get(0).unwrap().get(2).unwrap().edges;
+        let actual_borders = map.plants[(2, 0)].borders;
+        let actual_group = map.plants[(2, 0)].group;
+        let actual_edges = map.plants[(2, 0)].edges;
         assert_eq!(Some(3), actual_borders);
         assert_eq!(Some(0), actual_group);
         assert_eq!(Some(2), actual_edges);
-        let expected_group = map.plants.get(0).unwrap().get(0).unwrap().group;
+        let expected_group = map.plants[(0, 0)].group;
         assert_eq!(expected_group, None);
-        let expected_group = map.plants.get(0).unwrap().get(3).unwrap().group;
+        let expected_group = map.plants[(3, 0)].group;
         assert_eq!(expected_group, None);
     }
     #[test]
     fn test_map_find_neighbours_and_borders() {
-        let mut map = Map::new(vec![vec!['a', 'b', 'b', 'd']]);
+        let mut map = Map::new(labels(vec![vec!['a', 'b', 'b', 'd']]));
         map.find_neighbours_and_borders();
-        let actual_borders = map.plants.get(0).unwrap().get(0).unwrap().borders;
-        let actual_group = map.plants.get(0).unwrap().get(0).unwrap().group;
+        let actual_borders = map.plants[(0, 0)].borders;
+        let actual_group = map.plants[(0, 0)].group;
         assert_eq!(Some(4), actual_borders);
         assert_eq!(Some(0), actual_group);
-        let actual_borders = map.plants.get(0).unwrap().get(1).unwrap().borders;
-        let actual_group = map.plants.get(0).unwrap().get(1).unwrap().group;
+        let actual_borders = map.plants[(1, 0)].borders;
+        let actual_group = map.plants[(1, 0)].group;
         assert_eq!(Some(3), actual_borders);
         assert_eq!(Some(1), actual_group);
-        let actual_borders = map.plants.get(0).unwrap().get(2).unwrap().borders;
-        let actual_group = map.plants.get(0).unwrap().get(2).unwrap().group;
+        let actual_borders = map.plants[(2, 0)].borders;
+        let actual_group = map.plants[(2, 0)].group;
         assert_eq!(Some(3), actual_borders);
         assert_eq!(Some(1), actual_group);
-        let actual_borders = map.plants.get(0).unwrap().get(3).unwrap().borders;
-        let actual_group = map.plants.get(0).unwrap().get(3).unwrap().group;
+        let actual_borders = map.plants[(3, 0)].borders;
+        let actual_group = map.plants[(3, 0)].group;
         assert_eq!(Some(4), actual_borders);
         assert_eq!(Some(2), actual_group);
 
@@ -465,21 +401,21 @@ mod test {
     #[test]
     fn test_map_count_edges() {
         let input = vec![vec!['a', 'b', 'c']];
-        let mut map = Map::new(input);
+        let mut map = Map::new(labels(input));
 
         let (pos_i, pos_j) = (0, 0);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(4);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (1, 0);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(4);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (2, 0);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(4);
         assert_eq!(expected, actual);
     }
@@ -492,69 +428,69 @@ mod test {
             vec!['a', 'd', 'b'],
             vec!['a', 'a', 'a'],
         ];
-        let mut map = Map::new(input);
+        let mut map = Map::new(labels(input));
 
         let (pos_i, pos_j) = (0, 0);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(2);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (1, 0);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(2);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (2, 0);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(4);
         assert_eq!(expected, actual);
 
         let (pos_i, pos_j) = (0, 1);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(0);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (1, 1);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(2);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (2, 1);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(2);
         assert_eq!(expected, actual);
 
         let (pos_i, pos_j) = (0, 2);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(0);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (1, 2);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(4);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (2, 2);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(2);
         assert_eq!(expected, actual);
 
         let (pos_i, pos_j) = (0, 3);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(2);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (1, 3);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(0);
         assert_eq!(expected, actual);
         let (pos_i, pos_j) = (2, 3);
         map.count_edges(pos_i, pos_j);
-        let actual = map.plants.get(pos_j).unwrap().get(pos_i).unwrap().edges;
+        let actual = map.plants[(pos_i, pos_j)].edges;
         let expected = Some(2);
         assert_eq!(expected, actual);
     }
@@ -564,7 +500,7 @@ mod test {
         let input = vec![vec!['a', 'b', 'c']];
         let expected = 12;
 
-        let mut map = Map::new(input);
+        let mut map = Map::new(labels(input));
         map.find_neighbours_and_borders();
         let actual = map.get_cost();
         assert_eq!(expected, actual);
@@ -579,7 +515,7 @@ mod test {
             vec!['e', 'e', 'e', 'c'],
         ];
         let expected = 140;
-        let mut map = Map::new(input);
+        let mut map = Map::new(labels(input));
         map.find_neighbours_and_borders();
         let actual = map.get_cost();
         assert_eq!(expected, actual);
@@ -593,9 +529,22 @@ mod test {
             vec!['e', 'e', 'e', 'c'],
         ];
         let expected = 80;
-        let mut map = Map::new(input);
+        let mut map = Map::new(labels(input));
         map.find_neighbours_and_borders();
         let actual = map.get_cost_2();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_map_frame() {
+        let input = vec![vec!['a', 'b', 'a'], vec!['a', 'b', 'b']];
+        let mut map = Map::new(labels(input));
+        map.find_neighbours_and_borders();
+        let frame = map.frame();
+        assert_eq!(frame.caption, "3 regions");
+        assert_eq!(frame.cells[(0, 1)], Cell::new('a', Colour::cycle(0)));
+        assert_eq!(frame.cells[(2, 1)], Cell::new('b', Colour::cycle(1)));
+        // the two regions of a are different colours
+        assert_eq!(frame.cells[(2, 0)], Cell::new('a', Colour::cycle(2)));
+    }
 }
@@ -1,6 +1,11 @@
-use std::{fs::read_to_string, time::Instant};
-
-use regex::Regex;
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+use crate::generate::{self, Rng};
+use crate::geom::Vector;
+use crate::math::{self, MathError};
+use crate::params::{Param, Params};
+use crate::parse;
+use crate::solver::{self, Answer, Answers, Example, Solver};
 
 /// ok so we need to solve a linear problem
 /// i have the cost function J(alpha,beta) = 3 * alpha + beta
@@ -15,136 +20,238 @@ use regex::Regex;
 ///
 /// ok so i essentially need a Game object with
 /// a, b and t defined  and then go from there
-#[derive(Debug, PartialEq, Clone, Copy)]
-struct Vector {
-    x: i64,
-    y: i64,
-}
-
 #[derive(Debug, PartialEq)]
-struct Game {
+pub struct Game {
     a: Vector,
     b: Vector,
     t: Vector,
     det: i64,
 }
 
-impl Vector {
-    fn new(x: i64, y: i64) -> Self {
-        Self { x, y }
+impl Game {
+    /// the game, unless the buttons are too big for the determinant to fit
+    fn new(a: Vector, b: Vector, t: Vector) -> std::result::Result<Self, MathError> {
+        let det = math::determinant(&Self::matrix(a, b))?;
+        Ok(Self { a, b, t, det })
     }
-}
 
-impl Game {
-    fn new(a: Vector, b: Vector, t: Vector) -> Self {
-        let det = (a.x * b.y) - (b.x * a.y);
-        Self { a, b, t, det }
+    /// the buttons as the columns of a matrix, so it times the presses is where
+    /// the claw ends up
+    fn matrix(a: Vector, b: Vector) -> math::Matrix<2> {
+        [[a.x, b.x], [a.y, b.y]]
     }
 
-    /// this is w.r.t. to integer solutions
-    fn is_invertible(&self) -> bool {
-        if self.det == 0 {
-            return false;
-        };
-        let alpha = self.a.x * self.t.y - self.a.y * self.t.x;
-        let beta = self.b.y * self.t.x - self.b.x * self.t.y;
+    /// the presses of A and B that reach the prize when there's only one way
+    /// to, as long as they're whole and not negative
+    fn presses(&self) -> Option<(i64, i64)> {
+        let [alpha, beta] =
+            math::solve(&Self::matrix(self.a, self.b), &[self.t.x, self.t.y]).ok()?;
+        let (alpha, beta) = (alpha.to_integer()?, beta.to_integer()?);
+        (alpha >= 0 && beta >= 0).then_some((alpha, beta))
+    }
 
-        if alpha % (self.det.abs()) != 0 {
-            return false;
-        }
-        if (beta) % (self.det.abs()) != 0 {
-            return false;
+    fn is_colinear(&self) -> bool {
+        self.det == 0
+    }
+
+    /// when the buttons push the same way there is a whole family of ways to
+    /// the prize, a * alpha + b * beta = t along the line. extended euclid gives
+    /// one, the rest step alpha up by b / g as beta steps down by a / g, and the
+    /// cost changes the same amount every step so the cheapest is at an end
+    fn colinear_cost(&self) -> Option<u64> {
+        if self.a.cross(self.t) != 0 || self.b.cross(self.t) != 0 {
+            return None;
         }
-        if (alpha) / (self.det) < 0 {
-            return false;
+        let (a, b, t) = if self.a.x != 0 || self.b.x != 0 {
+            (self.a.x, self.b.x, self.t.x)
+        } else {
+            (self.a.y, self.b.y, self.t.y)
+        };
+        if a == 0 && b == 0 {
+            // neither button moves the claw
+            return (self.t == Vector::default()).then_some(0);
         }
-        if (beta) / (self.det) < 0 {
-            return false;
+        let (g, x, y) = math::extended_gcd(a, b);
+        if t % g != 0 {
+            return None;
         }
+        let (alpha, beta) = (x.checked_mul(t / g)?, y.checked_mul(t / g)?);
+        let (p, q) = (b / g, a / g);
 
-        true
-    }
-
-    fn is_colinear(&self) -> bool {
-        let ratio_x = (self.a.x as f64) / (self.b.x as f64);
-        let ratio_y = (self.a.y as f64) / (self.b.y as f64);
-        ratio_x.abs() == ratio_y.abs()
+        // the steps k that keep alpha + k * p and beta - k * q both at least
+        // zero, each a bound of the shape k * c >= d
+        let mut low: Option<i64> = None;
+        let mut high: Option<i64> = None;
+        for (c, d) in [(p, -alpha), (-q, -beta)] {
+            match c.signum() {
+                1 => low = Some(low.map_or(i64::MIN, |l| l).max(math::div_ceil(d, c))),
+                -1 => high = Some(high.map_or(i64::MAX, |h| h).min(math::div_floor(d, c))),
+                _ if d > 0 => return None,
+                _ => {}
+            }
+        }
+        if let (Some(low), Some(high)) = (low, high)
+            && low > high
+        {
+            return None;
+        }
+        let k = match (3 * p - q).signum() {
+            1 => low?,
+            -1 => high?,
+            _ => low.or(high).unwrap_or(0),
+        };
+        let cost =
+            3 * (alpha as i128 + k as i128 * p as i128) + (beta as i128 - k as i128 * q as i128);
+        cost.try_into().ok()
     }
 
     fn get_opt_cost(&self) -> u64 {
-        if !self.is_invertible() {
-            return 0;
+        if let Some((alpha, beta)) = self.presses() {
+            return 3 * alpha as u64 + beta as u64;
         }
         if self.is_colinear() {
-            let ratio_b_x = (self.t.x) / (self.b.x);
-            let ratio_a_x = (self.t.x) / (self.a.x);
-            if 3 * ratio_a_x < ratio_b_x {
-                return 3 * ratio_a_x as u64;
-            }
-            return ratio_b_x as u64;
+            return self.colinear_cost().unwrap_or(0);
         }
-        let beta = (self.a.x * self.t.y - self.a.y * self.t.x) / self.det;
-        let alpha = (self.b.y * self.t.x - self.b.x * self.t.y) / self.det;
-
-        3 * (alpha as u64) + (beta as u64)
+        0
     }
 }
 
-pub fn day_thirteen(path: &str) -> std::io::Result<()> {
-    let now = Instant::now();
-    let content = read_to_string(path)?;
-    let groups: Vec<&str> = content.split("\n\n").collect();
-
-    let cost: u64 = groups
-        .iter()
-        .map(|g| {
-            let rows: Vec<&str> = g.split("\n").collect();
-            let a = rows.first().unwrap();
-            let b = rows.get(1).unwrap();
-            let t = rows.get(2).unwrap();
-
-            let a: Vec<i64> = Regex::new(r"[+]?\d+")
-                .unwrap()
-                .find_iter(a)
-                .map(|m| m.as_str().parse().unwrap())
-                .collect();
-            let b: Vec<i64> = Regex::new(r"[+]?\d+")
-                .unwrap()
-                .find_iter(b)
-                .map(|m| m.as_str().parse().unwrap())
-                .take(2)
-                .collect();
-            let t: Vec<i64> = Regex::new(r"=(\d+)")
-                .unwrap()
-                .find_iter(t)
-                .map(|m| m.as_str()[1..].parse().unwrap())
-                .take(2)
-                .collect();
-
-            let a = Vector::new(*a.first().unwrap(), *a.get(1).unwrap());
-            let b = Vector::new(*b.first().unwrap(), *b.get(1).unwrap());
-            let t = Vector::new(
-                *t.first().unwrap() + 10000000000000,
-                *t.get(1).unwrap() + 10000000000000,
-            );
+/// the fewest tokens to win every prize there is, then with the prizes moved
+/// out by the offset
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayThirteen>(input)
+}
+
+pub fn day_thirteen(path: &str) -> Result<()> {
+    let content = read_input(path)?;
+    let answers = solve(&content).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
+    Ok(())
+}
+
+/// The claw machines and how far part two moves every prize
+#[derive(Debug)]
+pub struct Arcade {
+    games: Vec<Game>,
+    offset: i64,
+}
+
+pub struct DayThirteen;
+
+impl Solver for DayThirteen {
+    const DAY: u8 = 13;
+    const NAME: &'static str = "day_thirteen";
+    const PARAMS: &'static [Param] = &[Param {
+        name: "offset",
+        default: "10000000000000",
+        help: "added to both coordinates of every prize in part two",
+    }];
+
+    const SIZES: &'static [Param] = &[Param {
+        name: "machines",
+        default: "320",
+        help: "claw machines",
+    }];
+
+    // the puzzle doesn't give part two for the example, this is the known answer
+    const EXAMPLES: &'static [Example] = &[Example::new(
+        include_str!("examples/day13.txt"),
+        "480",
+        "875318608908",
+    )];
 
-            let h = Game::new(a, b, t);
+    type Input = Arcade;
 
-            h.get_opt_cost()
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::new().resolve(Self::PARAMS)?)
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        // pull the x and y out of a line like `Button A: X+94, Y+34`
+        let vector = |line: &str, expected: &str| -> Result<Vector> {
+            let [x, y] = parse::integers(input, line)?[..] else {
+                return Err(Error::malformed(Location::of(input, line), expected));
+            };
+            Ok(Vector::new(x, y))
+        };
+        let games = parse::sections(input)
+            .into_iter()
+            .map(|g| {
+                let mut rows = g.lines();
+                let mut row = |section| {
+                    rows.next().ok_or(Error::MissingSection {
+                        location: Location::of(input, &g[g.len()..]),
+                        section,
+                    })
+                };
+                let a = row("button A line")?;
+                let b = row("button B line")?;
+                let t = row("prize line")?;
+                Game::new(
+                    vector(a, "a button like Button A: X+94, Y+34")?,
+                    vector(b, "a button like Button B: X+22, Y+67")?,
+                    vector(t, "a prize like Prize: X=8400, Y=5400")?,
+                )
+                .map_err(|_| {
+                    Error::malformed(
+                        Location::of(input, a),
+                        "buttons small enough to work out the presses for",
+                    )
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Arcade {
+            games,
+            offset: params.get("offset")?,
         })
-        .sum();
+    }
 
-    println!(
-        "the cost of the tokens is {}  and it took {}",
-        cost,
-        now.elapsed().as_micros(),
-    );
-    Ok(())
+    fn part_one(input: &Self::Input) -> Answer {
+        let cost: u64 = input.games.iter().map(|g| g.get_opt_cost()).sum();
+        cost.into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        let cost: u64 = input
+            .games
+            .iter()
+            .map(|g| {
+                // moving the prize leaves the determinant as it was
+                let t = g.t + Vector::new(input.offset, input.offset);
+                Game { t, ..*g }.get_opt_cost()
+            })
+            .sum();
+        cost.into()
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let machines = generate::size(sizes, "machines", 1..=100_000)?;
+        let mut out = Vec::new();
+        for _ in 0..machines {
+            let mut button = || (rng.between(10..=99), rng.between(10..=99));
+            let (a, b) = (button(), button());
+            // about half can be won in a hundred presses of each button, the
+            // others are somewhere random
+            let prize = if rng.chance(0.5) {
+                let (pa, pb) = (rng.between(0..=100), rng.between(0..=100));
+                (pa * a.0 + pb * b.0, pa * a.1 + pb * b.1)
+            } else {
+                (rng.between(1000..=20_000), rng.between(1000..=20_000))
+            };
+            out.push(format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+                a.0, a.1, b.0, b.1, prize.0, prize.1
+            ));
+        }
+        Ok(Some(out.join("\n")))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::check;
 
     #[test]
     fn test_new_vector() {
@@ -158,7 +265,7 @@ mod test {
         let b = Vector::new(3, 4);
         let t = Vector::new(5, 6);
         let det = -2;
-        let actual = Game::new(a, b, t);
+        let actual = Game::new(a, b, t).unwrap();
         let expected = Game { a, b, t, det };
         assert_eq!(actual, expected);
     }
@@ -167,7 +274,7 @@ mod test {
         let a = Vector::new(1, 2);
         let b = Vector::new(3, 4);
         let t = Vector::new(5, 6);
-        let g = Game::new(a, b, t);
+        let g = Game::new(a, b, t).unwrap();
         let actual = g.is_colinear();
         let expected = false;
         assert_eq!(actual, expected);
@@ -175,28 +282,28 @@ mod test {
         let a = Vector::new(1, 2);
         let b = Vector::new(2, 4);
         let t = Vector::new(5, 6);
-        let g = Game::new(a, b, t);
+        let g = Game::new(a, b, t).unwrap();
         let actual = g.is_colinear();
         let expected = true;
         assert_eq!(actual, expected);
     }
 
     #[test]
-    fn test_is_invertible() {
+    fn test_presses() {
         let a = Vector::new(1, 2);
         let b = Vector::new(3, 4);
         let t = Vector::new(5, 6);
-        let g = Game::new(a, b, t);
-        let actual = g.is_invertible();
-        let expected = false;
+        let g = Game::new(a, b, t).unwrap();
+        let actual = g.presses();
+        let expected = None;
         assert_eq!(actual, expected);
 
         let a = Vector::new(1, 2);
         let b = Vector::new(3, 4);
         let t = Vector::new(4, 6);
-        let g = Game::new(a, b, t);
-        let actual = g.is_invertible();
-        let expected = true;
+        let g = Game::new(a, b, t).unwrap();
+        let actual = g.presses();
+        let expected = Some((1, 1));
         assert_eq!(actual, expected);
     }
     #[test]
@@ -204,7 +311,7 @@ mod test {
         let a = Vector::new(94, 34);
         let b = Vector::new(22, 67);
         let t = Vector::new(8400, 5400);
-        let g = Game::new(a, b, t);
+        let g = Game::new(a, b, t).unwrap();
         let actual = g.get_opt_cost();
         let expected = 280;
         assert_eq!(actual, expected);
@@ -212,9 +319,122 @@ mod test {
         let a = Vector::new(26, 66);
         let b = Vector::new(67, 21);
         let t = Vector::new(12748, 12176);
-        let g = Game::new(a, b, t);
+        let g = Game::new(a, b, t).unwrap();
         let actual = g.get_opt_cost();
         let expected = 0;
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_colinear_cost() {
+        let cost = |a: (i64, i64), b: (i64, i64), t: (i64, i64)| {
+            let v = |(x, y)| Vector::new(x, y);
+            Game::new(v(a), v(b), v(t)).unwrap().get_opt_cost()
+        };
+        assert_eq!(cost((1, 1), (2, 2), (10, 10)), 5);
+        assert_eq!(cost((1, 1), (5, 5), (7, 7)), 7);
+        assert_eq!(cost((6, 6), (1, 1), (13, 13)), 7);
+        assert_eq!(cost((0, 3), (0, 2), (0, 7)), 5);
+        assert_eq!(cost((2, 2), (4, 4), (5, 5)), 0);
+        assert_eq!(cost((1, 1), (2, 2), (3, 4)), 0);
+        assert_eq!(cost((0, 0), (0, 0), (0, 0)), 0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let input = "Button A: X+94, Y+34\nButton B: X+22\nPrize: X=8400, Y=5400\n";
+        let error = DayThirteen::parse(input).unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 1)));
+        let error = DayThirteen::parse("Button A: X+94, Y+34\n").unwrap_err();
+        assert!(matches!(error, Error::MissingSection { .. }));
+
+        // the determinant of buttons this big doesn't fit in an i64
+        let input = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n\n\
+                     Button A: X+9999999999, Y+-9999999999\n\
+                     Button B: X+9999999999, Y+9999999999\n\
+                     Prize: X=1, Y=1\n";
+        let error = DayThirteen::parse(input).unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(5, 1)));
+    }
+
+    #[test]
+    fn test_parse_crlf() {
+        let input = include_str!("examples/day13.txt").replace('\n', "\r\n");
+        let answers = solve(&input).unwrap();
+        assert_eq!(answers.part_one, Answer::Number(480));
+        let arcade = DayThirteen::parse(&input).unwrap();
+        assert_eq!(arcade.games.len(), 4);
+    }
+
+    #[test]
+    fn test_offset_param() {
+        let input = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n";
+        let mut params = Params::new();
+        params.set("offset", "0");
+        let answers = solver::solve_with::<DayThirteen>(input, &params).unwrap();
+        assert_eq!(answers.part_one, Answer::Number(280));
+        assert_eq!(answers.part_two, Answer::Number(280));
+    }
+
+    /// the cheapest of every way of pressing the buttons up to as many times
+    /// as the prize is far, which is as many as could ever be needed
+    fn naive_cost(a: Vector, b: Vector, t: Vector) -> u64 {
+        let most = t.x.max(t.y);
+        (0..=most)
+            .flat_map(|alpha| (0..=most).map(move |beta| (alpha, beta)))
+            .filter(|&(alpha, beta)| {
+                alpha * a.x + beta * b.x == t.x && alpha * a.y + beta * b.y == t.y
+            })
+            .map(|(alpha, beta)| (3 * alpha + beta) as u64)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// a machine as A's x and y, B's then the prize's. A third of them have
+    /// the buttons pushing the same way, and half the prizes can be won
+    fn machine(rng: &mut Rng, _size: usize) -> [i64; 6] {
+        let (a, b) = if rng.chance(1.0 / 3.0) {
+            let way = (rng.between(0..=4), rng.between(0..=4));
+            let (m, n) = (rng.between(0..=3), rng.between(0..=3));
+            ((way.0 * m, way.1 * m), (way.0 * n, way.1 * n))
+        } else {
+            let mut button = || (rng.between(0..=9), rng.between(0..=9));
+            (button(), button())
+        };
+        let t = if rng.chance(0.5) {
+            let (alpha, beta) = (rng.between(0..=10), rng.between(0..=10));
+            (alpha * a.0 + beta * b.0, alpha * a.1 + beta * b.1)
+        } else {
+            (rng.between(0..=60), rng.between(0..=60))
+        };
+        [a.0, a.1, b.0, b.1, t.0, t.1]
+    }
+
+    fn shrink_machine(machine: &[i64; 6]) -> Vec<[i64; 6]> {
+        let mut smaller = Vec::new();
+        for (i, &n) in machine.iter().enumerate() {
+            for s in check::shrink_number(n as u64, 0) {
+                let mut with = *machine;
+                with[i] = s as i64;
+                smaller.push(with);
+            }
+        }
+        smaller
+    }
+
+    #[test]
+    fn test_cost_against_naive() {
+        check::check("the cheapest win", machine, shrink_machine, |&m| {
+            let (a, b, t) = (
+                Vector::new(m[0], m[1]),
+                Vector::new(m[2], m[3]),
+                Vector::new(m[4], m[5]),
+            );
+            check::equal(
+                "the cost",
+                Game::new(a, b, t).unwrap().get_opt_cost(),
+                naive_cost(a, b, t),
+            )
+        });
+    }
 }
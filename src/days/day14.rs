@@ -1,11 +1,13 @@
-use core::f64;
-use std::{
-    f64::consts::{self, PI},
-    fs::read_to_string,
-    time::Instant,
-};
-
-use regex::Regex;
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+use crate::generate::{self, Rng};
+use crate::geom::{Position, Vector};
+use crate::grid;
+use crate::math;
+use crate::params::{Param, Params};
+use crate::parse;
+use crate::solver::{self, Answer, Answers, Example, Solver};
+use crate::visual::{Cell, Colour, Frame, Frames};
 
 /// ok so this seems pretty straight forward
 /// we have some sort of grid that multiple robots traverse
@@ -14,22 +16,10 @@ use regex::Regex;
 /// the four quadrants and multiply those numbers together  
 ///
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub struct Position {
-    x: usize,
-    y: usize,
-}
-
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub struct Velocity {
-    x: i16,
-    y: i16,
-}
-
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Robot {
     pos: Position,
-    velocity: Velocity,
+    velocity: Vector,
 }
 
 #[derive(Debug, PartialEq)]
@@ -39,35 +29,18 @@ pub struct Grid {
     robots: Vec<Robot>,
 }
 
-impl Position {
-    pub fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-}
-impl Velocity {
-    pub fn new(x: i16, y: i16) -> Self {
-        Self { x, y }
-    }
-}
-
 impl Robot {
-    pub fn new(pos: Position, velocity: Velocity) -> Self {
+    pub fn new(pos: Position, velocity: Vector) -> Self {
         Self { pos, velocity }
     }
 
     pub fn step(&mut self, n: &usize, size_x: &usize, size_y: &usize) {
         // a robot just steps at velocity * n wrapping around the grid
-        let mut new_x = ((self.pos.x as i16) + ((*n as i16) * self.velocity.x)) % (*size_x as i16);
-        let mut new_y = ((self.pos.y as i16) + ((*n as i16) * self.velocity.y)) % (*size_y as i16);
-
-        if new_x < 0 {
-            new_x += *size_x as i16;
-        }
-        if new_y < 0 {
-            new_y += *size_y as i16;
-        }
-
-        self.pos = Position::new(new_x as usize, new_y as usize);
+        let moved = self.pos + self.velocity * (*n as i64);
+        self.pos = Position::new(
+            moved.x.rem_euclid(*size_x as i64),
+            moved.y.rem_euclid(*size_y as i64),
+        );
     }
 }
 impl Grid {
@@ -79,14 +52,15 @@ impl Grid {
         }
     }
 
-    pub fn print(&self) {
-        let mut print_grid = vec![vec!['.'; self.size_x]; self.size_y];
+    /// the room with a robot drawn wherever at least one stands
+    pub fn frame(&self, caption: impl Into<String>) -> Frame {
+        let mut cells = grid::Grid::new(self.size_x, self.size_y, Cell::new('.', Colour::Grey));
         for r in &self.robots {
-            print_grid[r.pos.y][r.pos.x] = 'X';
-        }
-        for row in print_grid.iter() {
-            println!("{}", row.iter().collect::<String>());
+            if let Some(point) = r.pos.to_point() {
+                cells.set(point, Cell::new('#', Colour::Green));
+            }
         }
+        Frame::new(cells, caption)
     }
 
     pub fn simulate(&mut self, steps: usize) {
@@ -95,23 +69,19 @@ impl Grid {
             .for_each(|r| r.step(&steps, &self.size_x, &self.size_y));
     }
 
-    pub fn calculate_kde(&self, robot: &Robot, h: f64) -> f64 {
-        let mut result = 0.0;
-        for r in &self.robots {
-            result += (((robot.pos.x - r.pos.x) * (robot.pos.x - r.pos.x)
-                + (robot.pos.y - r.pos.y) * (robot.pos.y - r.pos.y)) as f64)
-                / (2.0 * h);
-        }
-        result / (2.0 * PI)
-    }
-
-    pub fn calculate_entropy(&self, h: f64) -> f64 {
-        let mut result = 0.0;
-        for r in &self.robots {
-            let p = self.calculate_kde(r, h);
-            result -= p * p.log2();
-        }
-        result
+    /// how spread out the robots are across and down the room. Each is the
+    /// variance times the number of robots squared, which keeps it whole
+    pub fn calculate_spread(&self) -> (i128, i128) {
+        let spread = |values: Vec<i128>| {
+            let n = values.len() as i128;
+            let sum: i128 = values.iter().sum();
+            let squares: i128 = values.iter().map(|v| v * v).sum();
+            n * squares - sum * sum
+        };
+        (
+            spread(self.robots.iter().map(|r| r.pos.x as i128).collect()),
+            spread(self.robots.iter().map(|r| r.pos.y as i128).collect()),
+        )
     }
 
     pub fn calculate_quad_score(&self) -> usize {
@@ -136,10 +106,11 @@ impl Grid {
 
         for r in &self.robots {
             // take each robot figure out if its in a quad then add it to the count
-            let is_lower = r.pos.y >= quad_bounds_y.1;
-            let is_upper = r.pos.y <= quad_bounds_y.0;
-            let is_left = r.pos.x <= quad_bounds_x.0;
-            let is_right = r.pos.x >= quad_bounds_x.1;
+            let (x, y) = (r.pos.x as usize, r.pos.y as usize);
+            let is_lower = y >= quad_bounds_y.1;
+            let is_upper = y <= quad_bounds_y.0;
+            let is_left = x <= quad_bounds_x.0;
+            let is_right = x >= quad_bounds_x.1;
 
             if is_lower && is_left {
                 lower_left_count += 1;
@@ -158,46 +129,219 @@ impl Grid {
     }
 }
 
-pub fn day_fourteen(path: &str) -> std::io::Result<()> {
-    let now = Instant::now();
-    let content = read_to_string(path)?;
-    let groups: Vec<&str> = content.lines().collect();
+/// the safety factor once the robots have moved for the seconds given, then
+/// the seconds until they make the tree
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayFourteen>(input)
+}
+
+pub fn day_fourteen(path: &str) -> Result<()> {
+    let content = read_input(path)?;
+    let answers = solve(&content).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
+    Ok(())
+}
 
-    let re = Regex::new(r"=([+-]?\d+),([+-]?\d+)").unwrap();
-    let robots: Vec<Robot> = groups
-        .iter()
-        .map(|g| {
-            let mut capt = re.captures_iter(g).map(|c| c.extract::<2>()).map(|c| {
-                (
-                    c.1.first().unwrap().parse().unwrap(),
-                    c.1.get(1).unwrap().parse().unwrap(),
-                )
-            });
+/// the width and height of the room from `params`. Part two only sees every
+/// second before the robots come round again when they share no factor
+fn room(params: &Params) -> Result<(usize, usize)> {
+    // the quadrants need a middle row and column with tiles either side
+    let width = params.get_within("width", 3..=1000)?;
+    let height = params.get_within("height", 3..=1000)?;
+    if math::gcd(width as i64, height as i64) != 1 {
+        return Err(Error::InvalidParam {
+            name: "height".into(),
+            value: height.to_string(),
+            expected: "a number sharing no factor with the width",
+        });
+    }
+    Ok((width as usize, height as usize))
+}
 
-            let pos: (i64, i64) = capt.next().unwrap();
-            let vel: (i64, i64) = capt.next().unwrap();
+/// The robots and the shape of the room they patrol
+#[derive(Debug)]
+pub struct Lobby {
+    robots: Vec<Robot>,
+    width: usize,
+    height: usize,
+    seconds: usize,
+}
 
-            let r = Robot::new(
-                Position::new(pos.0 as usize, pos.1 as usize),
-                Velocity::new(vel.0 as i16, vel.1 as i16),
-            );
-            r
+impl Lobby {
+    /// the robots walking a second at a time, until they're all back where
+    /// they started
+    pub fn frames(&self) -> impl Iterator<Item = Frame> + use<> {
+        let mut grid = Grid::new(self.width, self.height, self.robots.clone());
+        let cycle = self.width * self.height;
+        (0..cycle).map(move |second| {
+            if second > 0 {
+                grid.simulate(1);
+            }
+            grid.frame(format!("second {second} of {cycle}"))
         })
-        .collect();
+    }
+}
+
+pub struct DayFourteen;
+
+impl Solver for DayFourteen {
+    const DAY: u8 = 14;
+    const NAME: &'static str = "day_fourteen";
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "width",
+            default: "101",
+            help: "tiles across the room, 3-1000, the example uses 11",
+        },
+        Param {
+            name: "height",
+            default: "103",
+            help: "tiles down the room, 3-1000 sharing no factor with the width, the example uses 7",
+        },
+        Param {
+            name: "seconds",
+            default: "100",
+            help: "how long part one lets the robots walk",
+        },
+    ];
+
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "robots",
+            default: "500",
+            help: "robots in the room",
+        },
+        Param {
+            name: "width",
+            default: "101",
+            help: "tiles across the room, solve it with the same --param width",
+        },
+        Param {
+            name: "height",
+            default: "103",
+            help: "tiles down the room, solve it with the same --param height",
+        },
+    ];
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: include_str!("examples/day14.txt"),
+        params: &[("width", "11"), ("height", "7")],
+        part_one: Some("12"),
+        // there's no tree in the example room
+        part_two: None,
+    }];
+
+    type Input = Lobby;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::new().resolve(Self::PARAMS)?)
+    }
 
-    let mut grid = Grid::new(101, 103, robots);
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        let robots = input
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|g| {
+                let robot =
+                    || Error::malformed(Location::of(input, g), "a robot like p=0,4 v=3,-3");
+                if !g.starts_with("p=") || !g.contains(" v=") {
+                    return Err(robot());
+                }
+                let [px, py, vx, vy] = parse::integers(input, g)?[..] else {
+                    return Err(robot());
+                };
+                Ok(Robot::new(Position::new(px, py), Vector::new(vx, vy)))
+            })
+            .collect::<Result<_>>()?;
+        let (width, height) = room(params)?;
+        Ok(Lobby {
+            robots,
+            width,
+            height,
+            seconds: params.get("seconds")?,
+        })
+    }
 
-    let mut entropies = vec![];
+    fn part_one(input: &Self::Input) -> Answer {
+        let mut grid = Grid::new(input.width, input.height, input.robots.clone());
+        grid.simulate(input.seconds);
+        grid.calculate_quad_score().into()
+    }
 
-    for i in 1..10000 {
-        grid.simulate(1);
-        entropies.push((i, grid.calculate_entropy(2.0)));
+    /// the easter egg is the frame where the robots bunch up into a picture.
+    /// Across the room the robots are back where they started every `width`
+    /// seconds and down it every `height`, so the tightest second for each is
+    /// found on its own and the chinese remainder theorem puts them together
+    fn part_two(input: &Self::Input) -> Answer {
+        let mut grid = Grid::new(input.width, input.height, input.robots.clone());
+        let mut across = (0, i128::MAX);
+        let mut down = (0, i128::MAX);
+        for second in 0..input.width.max(input.height) as i64 {
+            let (x, y) = grid.calculate_spread();
+            log::trace!("second {second}: spread {x} across and {y} down");
+            if second < input.width as i64 && x < across.1 {
+                across = (second, x);
+            }
+            if second < input.height as i64 && y < down.1 {
+                down = (second, y);
+            }
+            grid.simulate(1);
+        }
+        log::debug!(
+            "tightest across at second {}, down at second {}",
+            across.0,
+            down.0
+        );
+        let congruences = [
+            (across.0, input.width as i64),
+            (down.0, input.height as i64),
+        ];
+        math::crt(&congruences)
+            .map_or(0, |(second, _)| second as u64)
+            .into()
     }
 
-    entropies.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-    println!("entropies {:?}", entropies);
+    fn visualize(input: &Self::Input) -> Option<Frames> {
+        Some(Box::new(input.frames()))
+    }
 
-    Ok(())
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let robots = generate::size(sizes, "robots", 1..=100_000)?;
+        let (width, height) = room(sizes)?;
+        let (width, height) = (width as i64, height as i64);
+        // the easter egg, two thirds of the robots making a tree at one second
+        let second = rng.below((width * height) as u64) as i64;
+        log::debug!("the robots make a tree after {second} seconds");
+        let rows = (width.min(height) / 4).max(1);
+        // in the middle of the room, or the robots left over can make it look
+        // no more bunched up than the others some seconds
+        let (middle, top) = (width / 2, (height - rows) / 2);
+        let mut out = String::new();
+        for robot in 0..robots {
+            let velocity = (
+                rng.between(1 - width..=width - 1),
+                rng.between(1 - height..=height - 1),
+            );
+            let position = if robot % 3 < 2 {
+                // somewhere in the tree at that second, worked back to where
+                // the robot has to start
+                let row = rng.between(0..=rows - 1);
+                let (x, y) = (middle + rng.between(-row..=row), top + row);
+                (
+                    (x - velocity.0 * second).rem_euclid(width),
+                    (y - velocity.1 * second).rem_euclid(height),
+                )
+            } else {
+                (rng.between(0..=width - 1), rng.between(0..=height - 1))
+            };
+            out.push_str(&format!(
+                "p={},{} v={},{}\n",
+                position.0, position.1, velocity.0, velocity.1
+            ));
+        }
+        Ok(Some(out))
+    }
 }
 
 #[cfg(test)]
@@ -217,14 +361,14 @@ mod test {
     fn test_velocity_new() {
         let x = 1;
         let y = -2;
-        let actual = Velocity::new(x, y);
-        let expected = Velocity { x, y };
+        let actual = Vector::new(x, y);
+        let expected = Vector { x, y };
         assert_eq!(actual, expected);
     }
     #[test]
     fn test_robot_new() {
         let pos = Position::new(0, 0);
-        let velocity = Velocity::new(-1, 1);
+        let velocity = Vector::new(-1, 1);
         let actual = Robot::new(pos, velocity);
         let expected = Robot { pos, velocity };
         assert_eq!(actual, expected);
@@ -233,8 +377,8 @@ mod test {
     fn test_grid_new() {
         let size_x = 10;
         let size_y = 20;
-        let robots = vec![Robot::new(Position::new(1, 2), Velocity::new(-1, 1))];
-        let robots_copy = vec![Robot::new(Position::new(1, 2), Velocity::new(-1, 1))];
+        let robots = vec![Robot::new(Position::new(1, 2), Vector::new(-1, 1))];
+        let robots_copy = vec![Robot::new(Position::new(1, 2), Vector::new(-1, 1))];
         let actual = Grid::new(size_x, size_y, robots);
         let expected = Grid {
             size_x,
@@ -244,19 +388,35 @@ mod test {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_frames() {
+        let lobby = Lobby {
+            robots: vec![Robot::new(Position::new(0, 0), Vector::new(1, 1))],
+            width: 3,
+            height: 2,
+            seconds: 100,
+        };
+        let frames: Vec<Frame> = lobby.frames().collect();
+        assert_eq!(frames.len(), 6);
+        assert_eq!(frames[0].cells[(0, 0)], Cell::new('#', Colour::Green));
+        assert_eq!(frames[1].cells[(0, 0)], Cell::new('.', Colour::Grey));
+        assert_eq!(frames[1].cells[(1, 1)], Cell::new('#', Colour::Green));
+        assert_eq!(frames[5].caption, "second 5 of 6");
+    }
+
     #[test]
     fn test_robot_step() {
-        let mut robot = Robot::new(Position::new(0, 0), Velocity::new(1, 2));
+        let mut robot = Robot::new(Position::new(0, 0), Vector::new(1, 2));
         robot.step(&1, &10, &10);
 
         assert_eq!(robot.pos, Position::new(1, 2));
 
-        let mut robot = Robot::new(Position::new(0, 0), Velocity::new(1, 2));
+        let mut robot = Robot::new(Position::new(0, 0), Vector::new(1, 2));
         robot.step(&5, &10, &10);
 
         assert_eq!(robot.pos, Position::new(5, 0));
 
-        let mut robot = Robot::new(Position::new(0, 0), Velocity::new(-1, 2));
+        let mut robot = Robot::new(Position::new(0, 0), Vector::new(-1, 2));
         robot.step(&5, &10, &10);
 
         assert_eq!(robot.pos, Position::new(5, 0));
@@ -265,18 +425,18 @@ mod test {
     #[test]
     fn test_grid_calc() {
         let robots = vec![
-            Robot::new(Position::new(0, 4), Velocity::new(3, -3)),
-            Robot::new(Position::new(6, 3), Velocity::new(-1, -3)),
-            Robot::new(Position::new(10, 3), Velocity::new(-1, 2)),
-            Robot::new(Position::new(2, 0), Velocity::new(2, -1)),
-            Robot::new(Position::new(0, 0), Velocity::new(1, 3)),
-            Robot::new(Position::new(3, 0), Velocity::new(-2, -2)),
-            Robot::new(Position::new(7, 6), Velocity::new(-1, -3)),
-            Robot::new(Position::new(3, 0), Velocity::new(-1, -2)),
-            Robot::new(Position::new(9, 3), Velocity::new(2, 3)),
-            Robot::new(Position::new(7, 3), Velocity::new(-1, 2)),
-            Robot::new(Position::new(2, 4), Velocity::new(2, -3)),
-            Robot::new(Position::new(9, 5), Velocity::new(-3, -3)),
+            Robot::new(Position::new(0, 4), Vector::new(3, -3)),
+            Robot::new(Position::new(6, 3), Vector::new(-1, -3)),
+            Robot::new(Position::new(10, 3), Vector::new(-1, 2)),
+            Robot::new(Position::new(2, 0), Vector::new(2, -1)),
+            Robot::new(Position::new(0, 0), Vector::new(1, 3)),
+            Robot::new(Position::new(3, 0), Vector::new(-2, -2)),
+            Robot::new(Position::new(7, 6), Vector::new(-1, -3)),
+            Robot::new(Position::new(3, 0), Vector::new(-1, -2)),
+            Robot::new(Position::new(9, 3), Vector::new(2, 3)),
+            Robot::new(Position::new(7, 3), Vector::new(-1, 2)),
+            Robot::new(Position::new(2, 4), Vector::new(2, -3)),
+            Robot::new(Position::new(9, 5), Vector::new(-3, -3)),
         ];
 
         let mut grid = Grid::new(11, 7, robots);
@@ -285,4 +445,87 @@ mod test {
         let expected = 12;
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_part_two_finds_the_bunching() {
+        // every robot is at 5,3 after 30 seconds and never all together before
+        let (width, height, second) = (11, 7, 30);
+        let robots = (1..=5)
+            .map(|v| {
+                let velocity = Vector::new(v, -v);
+                let start = Position::new(5, 3) - velocity * second;
+                let start = Position::new(start.x.rem_euclid(width), start.y.rem_euclid(height));
+                Robot::new(start, velocity)
+            })
+            .collect();
+        let lobby = Lobby {
+            robots,
+            width: width as usize,
+            height: height as usize,
+            seconds: 100,
+        };
+        assert_eq!(DayFourteen::part_two(&lobby), Answer::Number(30));
+    }
+
+    #[test]
+    fn test_generate_has_an_easter_egg() {
+        let mut sizes = Params::new();
+        for (name, value) in [("robots", "150"), ("width", "31"), ("height", "29")] {
+            sizes.set(name, value);
+        }
+        let mut params = Params::new();
+        params.set("width", "31");
+        params.set("height", "29");
+        for seed in 0..5 {
+            let input = solver::generate_with::<DayFourteen>(seed, &sizes)
+                .unwrap()
+                .unwrap();
+            // the tree's second is the first thing drawn
+            let second = Rng::new(seed).below(31 * 29);
+            let answers = solver::solve_with::<DayFourteen>(&input, &params).unwrap();
+            assert_eq!(answers.part_two, Answer::Number(second));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = DayFourteen::parse("p=0,4 v=3,-3\np=6,3 v=-1,x\n").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 1)));
+    }
+
+    #[test]
+    fn test_example_grid_params() {
+        let input = "p=0,4 v=3,-3\np=6,3 v=-1,-3\np=10,3 v=-1,2\np=2,0 v=2,-1\np=0,0 v=1,3\np=3,0 v=-2,-2\np=7,6 v=-1,-3\np=3,0 v=-1,-2\np=9,3 v=2,3\np=7,3 v=-1,2\np=2,4 v=2,-3\np=9,5 v=-3,-3";
+        let mut params = Params::new();
+        params.set("width", "11");
+        params.set("height", "7");
+        let answers = solver::solve_with::<DayFourteen>(input, &params).unwrap();
+        assert_eq!(answers.part_one, Answer::Number(12));
+    }
+
+    #[test]
+    fn test_room_size_params() {
+        for (name, value) in [("width", "0"), ("height", "1"), ("width", "1001")] {
+            let mut params = Params::new();
+            params.set(name, value);
+            let error = solver::solve_with::<DayFourteen>("p=0,4 v=3,-3\n", &params).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("parameter '{name}' is {value}, it has to be from 3 to 1000")
+            );
+        }
+        // a room 100 square would have the robots round again before part two
+        // has seen every second
+        let mut params = Params::new();
+        params.set("width", "100");
+        params.set("height", "100");
+        let error = solver::solve_with::<DayFourteen>("p=0,4 v=3,-3\n", &params).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid value '100' for parameter 'height', expected a number sharing no factor \
+             with the width"
+        );
+        let sizes = params.resolve(DayFourteen::SIZES).unwrap();
+        assert!(DayFourteen::generate(&mut Rng::new(1), &sizes).is_err());
+    }
 }
@@ -1,5 +1,11 @@
-use std::io;
-use std::time::Instant;
+use itertools::Itertools;
+
+use crate::days::read_input;
+use crate::error::Result;
+use crate::generate::{self, Rng};
+use crate::params::{Param, Params};
+use crate::parse;
+use crate::solver::{self, Answer, Answers, Example, Solver};
 
 fn check_safety(input: Vec<u8>) -> bool {
     // need to check that the vector is either strictly increasing or or decreasing
@@ -8,7 +14,7 @@ fn check_safety(input: Vec<u8>) -> bool {
     let mut prev_delta = 0;
     let l = input.len();
     for i in 1..l {
-        delta = (input[i] as i8) - (input[i - 1] as i8);
+        delta = input[i] as i16 - input[i - 1] as i16;
         if delta.abs() > 3 || delta.abs() < 1 {
             return false;
         }
@@ -20,102 +26,145 @@ fn check_safety(input: Vec<u8>) -> bool {
     true
 }
 
-/// Returns -1 if the input is safe and otherwise returns the pos of the first offending number
-fn check_safety_with_pos(input: &[u8]) -> i8 {
+/// Returns the pos of the first offending number, or `None` if the input is safe
+fn check_safety_with_pos(input: &[u8]) -> Option<usize> {
     // need to check that the vector is either strictly increasing or or decreasing
     // each step can only have a delta of 1,2,3
     let mut delta;
     let mut prev_delta = 0;
     let l = input.len();
     for i in 1..l {
-        delta = (input[i] as i8) - (input[i - 1] as i8);
+        delta = input[i] as i16 - input[i - 1] as i16;
         if delta.abs() > 3 || delta.abs() < 1 {
-            return i as i8;
+            return Some(i);
         }
         if i > 1 && delta * prev_delta < 0 {
-            return i as i8;
+            return Some(i);
         }
         prev_delta = delta;
     }
-    -1
+    None
 }
 
 fn check_safety_dampner(input: &[u8]) -> bool {
-    // ok this time we are allowed one mistake so what we will do
-    // we can use check with pos to try twice with removing the offending pos and then retrying
-    // so we have an edge case where in the first two postiion if it looks like its decreasing but
-    // then increases it could fail eg. 12, 10, 13, 16, 19, 2
-    // thats because it deletes the first value that looks wrong which currently would be 13, when
-    // we would actually want to delete 10
-    // so we need to account for the first three characters
-    let mut first_try = check_safety_with_pos(input);
-    if first_try == -1 {
-        return true;
-    }
-    let mut second_input = input.to_vec();
-    second_input.remove(first_try as usize);
-    let second_try = check_safety_with_pos(&second_input);
-    if second_try == -1 {
+    // ok this time we are allowed one mistake so when there is an offending
+    // pos we try again without it or one of the two before it. the level
+    // before can be the one that jumped too far, and the one two before can
+    // have set the wrong direction, like 10 in 12, 10, 13, 16, 19. further
+    // back than that everything went the same way fine so removing it can't help
+    let Some(first_try) = check_safety_with_pos(input) else {
         return true;
-    }
-    if first_try < 3 {
-        while first_try > 0 {
-            let last_try = first_try - 1;
-            let mut last_input = input.to_vec();
-            last_input.remove(last_try as usize);
-            let last_try = check_safety_with_pos(&last_input);
-            if last_try == -1 {
-                return true;
-            }
-            first_try -= 1;
-        }
-    }
-    false
+    };
+    (first_try.saturating_sub(2)..=first_try).any(|pos| {
+        let mut next_input = input.to_vec();
+        next_input.remove(pos);
+        check_safety_with_pos(&next_input).is_none()
+    })
 }
 
-fn parse_to_check_safety(input: &str) -> bool {
-    let v: Vec<u8> = input
-        .split_whitespace()
-        .map(|s| s.parse::<u8>().unwrap())
-        .collect();
-    check_safety(v)
+/// how many reports are safe, then how many are with the dampener
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayTwo>(input)
 }
-fn parse_to_check_safety_with_dampner(input: &str) -> bool {
-    let v: Vec<u8> = input
-        .split_whitespace()
-        .map(|s| s.parse::<u8>().unwrap())
-        .collect();
-    check_safety_dampner(&v)
+
+pub fn day_two(path: &str) -> Result<()> {
+    let contents = read_input(path)?;
+    let answers = solve(&contents).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
+    Ok(())
 }
 
-pub fn day_two(path: &str) -> io::Result<()> {
-    let now = Instant::now();
-    let contents = std::fs::read_to_string(path)?;
-    let mut count = 0;
-    let mut count_damp = 0;
-    let mut count_unsafe = 0;
-    for line in contents.lines() {
-        if parse_to_check_safety(line) {
-            count += 1;
-            count_damp += 1;
-        } else if parse_to_check_safety_with_dampner(line) {
-            count_damp += 1;
-        } else {
-            println!("Unsafe!: {line}");
-            count_unsafe += 1;
-        }
+pub struct DayTwo;
+
+impl Solver for DayTwo {
+    const DAY: u8 = 2;
+    const NAME: &'static str = "day_two";
+
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "reports",
+            default: "1000",
+            help: "reports, a line each",
+        },
+        Param {
+            name: "levels",
+            default: "8",
+            help: "the most levels in a report, 2-20",
+        },
+    ];
+
+    const EXAMPLES: &'static [Example] =
+        &[Example::new(include_str!("examples/day02.txt"), "2", "4")];
+
+    type Input = Vec<Vec<u8>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input
+            .lines()
+            .map(|line| parse::numbers(input, line, "a level 0-255"))
+            .collect()
     }
-    println!(
-        "the count of safe rows is {count}, and with dampner its {count_damp}, leaving {count_unsafe} lines and it took {} us",
-        now.elapsed().as_micros()
-    );
 
-    Ok(())
+    fn part_one(input: &Self::Input) -> Answer {
+        let count = input.iter().filter(|r| check_safety(r.to_vec())).count();
+        count.into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        let count = input
+            .iter()
+            .filter(|r| {
+                let safe = check_safety_dampner(r);
+                if !safe {
+                    log::debug!("unsafe even with the dampener: {}", r.iter().join(" "));
+                }
+                safe
+            })
+            .count();
+        count.into()
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let reports = generate::size(sizes, "reports", 1..=1_000_000)?;
+        let most = generate::size(sizes, "levels", 2..=20)? as i64;
+        let mut out = String::new();
+        for _ in 0..reports {
+            let length = rng.between((most - 3).max(2)..=most) as usize;
+            // a safe report to start with, the levels going from near one end
+            // of 1-99 towards the other like the real ones do
+            let (direction, mut level) = if rng.chance(0.5) {
+                (1, rng.between(1..=30))
+            } else {
+                (-1, rng.between(70..=99))
+            };
+            let mut report = Vec::with_capacity(length);
+            for _ in 0..length {
+                report.push(level);
+                level += direction * rng.between(1..=3);
+            }
+            // then about half get a level that jumps too far, repeats or goes
+            // back the other way
+            if rng.chance(0.5) {
+                let i = rng.index(length);
+                report[i] = match rng.below(3) {
+                    0 => report[i] + direction * rng.between(4..=10),
+                    1 => report[(i + 1) % length],
+                    _ => report[i] - direction * rng.between(1..=3),
+                };
+            }
+            out.push_str(&report.iter().map(|level| (*level).clamp(1, 99)).join(" "));
+            out.push('\n');
+        }
+        Ok(Some(out))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::check;
+    use crate::error::Location;
     #[test]
     fn test_check_safety() {
         assert!(check_safety(vec![7, 6, 4, 2, 1]));
@@ -138,12 +187,117 @@ mod tests {
         assert!(!check_safety_dampner(&[16, 17, 18, 2, 1, 24, 21]));
         assert!(check_safety_dampner(&[12, 10, 13, 16, 19, 21, 22]));
         assert!(check_safety_dampner(&[88, 90, 88, 86, 84, 82, 80]));
+        assert!(check_safety_dampner(&[5, 6, 7, 10, 8, 9]));
+        assert!(check_safety_dampner(&[3, 2, 0, 1, 0]));
+    }
+
+    #[test]
+    fn test_check_safety_high_levels() {
+        assert!(check_safety(vec![126, 127, 128, 130]));
+        assert!(!check_safety(vec![1, 200]));
+        assert_eq!(check_safety_with_pos(&[250, 252, 2]), Some(2));
+    }
+
+    #[test]
+    fn test_long_reports() {
+        // past 127 levels the position of the bad one has to be a usize
+        let mut levels: Vec<u8> = (0..200).map(|l| l as u8).collect();
+        assert_eq!(check_safety_with_pos(&levels), None);
+        levels[150] = 255;
+        assert_eq!(check_safety_with_pos(&levels), Some(150));
+        assert!(check_safety_dampner(&levels));
+    }
+
+    /// a report straight from the puzzle text, nothing clever
+    fn naive_safe(levels: &[u8]) -> bool {
+        let deltas: Vec<i16> = levels
+            .windows(2)
+            .map(|w| w[1] as i16 - w[0] as i16)
+            .collect();
+        deltas.iter().all(|d| (1..=3).contains(d)) || deltas.iter().all(|d| (-3..=-1).contains(d))
+    }
+
+    /// safe with any one level taken out, trying every one
+    fn naive_safe_dampened(levels: &[u8]) -> bool {
+        naive_safe(levels)
+            || (0..levels.len()).any(|i| {
+                let mut without = levels.to_vec();
+                without.remove(i);
+                naive_safe(&without)
+            })
+    }
+
+    /// reports that mostly go one way in safe steps, so they're often safe
+    /// but for a level or two, at any height up to 255
+    fn report(rng: &mut Rng, size: usize) -> Vec<u8> {
+        let direction = if rng.chance(0.5) { 1 } else { -1 };
+        let mut level = rng.below(256) as i64;
+        (0..rng.below(size.min(12) as u64 + 1))
+            .map(|_| {
+                level += if rng.chance(0.8) {
+                    direction * rng.between(1..=3)
+                } else {
+                    rng.between(-3..=3)
+                };
+                level.clamp(0, 255) as u8
+            })
+            .collect()
+    }
+
+    fn shrink_report(levels: &[u8]) -> Vec<Vec<u8>> {
+        check::shrink_vec(levels, |&l| {
+            check::shrink_number(l as u64, 0)
+                .into_iter()
+                .map(|l| l as u8)
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_safety_against_naive() {
+        check::check(
+            "safety",
+            report,
+            |r| shrink_report(r),
+            |levels| {
+                let expected = naive_safe(levels);
+                check::equal("check_safety", check_safety(levels.clone()), expected)?;
+                check::equal(
+                    "check_safety_with_pos is None",
+                    check_safety_with_pos(levels).is_none(),
+                    expected,
+                )
+            },
+        );
+    }
+
+    #[test]
+    fn test_dampener_against_naive() {
+        check::check(
+            "safety with the dampener",
+            report,
+            |r| shrink_report(r),
+            |levels| {
+                check::equal(
+                    "check_safety_dampner",
+                    check_safety_dampner(levels),
+                    naive_safe_dampened(levels),
+                )
+            },
+        );
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let error = DayTwo::parse("1 2 3\n4 x 6").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 3)));
     }
 
     #[test]
     fn test_parse_safety() {
-        assert!(parse_to_check_safety("1 2 3 4 5 6 7 8"));
-        assert!(parse_to_check_safety("7 6 4 2 1"));
-        assert!(!parse_to_check_safety("9 7 6 2 1"));
+        let reports = DayTwo::parse("1 2 3 4 5 6 7 8\n7 6 4 2 1\n9 7 6 2 1").unwrap();
+        assert!(check_safety(reports[0].clone()));
+        assert!(check_safety(reports[1].clone()));
+        assert!(!check_safety(reports[2].clone()));
     }
 }
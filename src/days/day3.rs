@@ -1,42 +1,41 @@
-use regex::Regex;
-use std::fs;
-use std::io::Result;
-use std::time::Instant;
-
-// ok so we need to be able to take in an input like the other days
-// cant recall if we take an iterable or not but can check
-// but then we can take each line and then use regex to match
-// mult(x,y) no spaces and that. use regex101 to test the pattern
-// ok so have a func that takes in per line and then read in the file
-// and run that func over it
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+use crate::generate::{self, Rng};
+use crate::params::{Param, Params};
+use crate::solver::{self, Answer, Answers, Example, Solver};
+use regex::{Captures, Regex};
+
+/// the sum of every mul, then of only the ones a do() left enabled
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayThree>(input)
+}
+
 pub fn day_three(path: &str) -> Result<()> {
-    let now = Instant::now();
-    let contents = fs::read_to_string(path)?;
-    let mut total = 0;
-    for line in contents.lines() {
-        total += sum_line(line);
-    }
-    let t = now.elapsed().as_micros();
-    println!("total sum is {} in {}us ", total, t);
-    let input: String = contents.lines().collect();
-    let s = sum_file(&input);
-    let t = now.elapsed().as_micros();
-    println!("total sum of two is {} in {}us ", s, t);
+    let contents = read_input(path)?;
+    let answers = solve(&contents).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
     Ok(())
 }
 
-fn sum_line(input: &str) -> u64 {
-    let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-    let matches: Vec<u64> = re
-        .find_iter(input)
-        .map(|m| re.captures(m.as_str()).unwrap())
-        .map(|c| {
-            let first = c.get(1).unwrap().as_str().parse::<u64>().unwrap();
-            let second = c.get(2).unwrap().as_str().parse::<u64>().unwrap();
-            first * second
-        })
-        .collect();
-    matches.iter().sum()
+// use regex to match mult(x,y) no spaces and that. use regex101 to test the
+// pattern
+fn mul_regex() -> Regex {
+    Regex::new(r"mul\((\d+),(\d+)\)").unwrap()
+}
+
+/// the numbers of a mul times each other, `None` when they're too big
+fn product(mul: &Captures) -> Option<u64> {
+    let number = |i: usize| mul[i].parse::<u64>().ok();
+    number(1)?.checked_mul(number(2)?)
+}
+
+// a lot of big muls can add up to more than a u64 holds
+fn sum_line(input: &str) -> u128 {
+    mul_regex()
+        .captures_iter(input)
+        .map(|c| product(&c).expect("parsing checked every mul fits") as u128)
+        .sum()
 }
 
 /// this time need to ensure to only take strings inbetween do() and don't()
@@ -44,7 +43,7 @@ fn sum_line(input: &str) -> u64 {
 /// ok so the program starts with a do() - so i could just append that on the front
 /// and add dont() onto the end but that has to be done on the input level -> just feed the whole
 /// string in not line by line then i can do it on this level
-fn sum_file(input: &str) -> u64 {
+fn sum_file(input: &str) -> u128 {
     let input_corrected = format!("do() {} don't()", input);
     let filter_re = Regex::new(r"do\(\)(.+?)don\'t\(\)").unwrap();
     let input_corrected: String = filter_re
@@ -54,17 +53,116 @@ fn sum_file(input: &str) -> u64 {
             capt
         })
         .collect();
-    let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-    let matches: Vec<u64> = re
-        .find_iter(&input_corrected)
-        .map(|m| re.captures(m.as_str()).unwrap())
-        .map(|c| {
-            let first = c.get(1).unwrap().as_str().parse::<u64>().unwrap();
-            let second = c.get(2).unwrap().as_str().parse::<u64>().unwrap();
-            first * second
-        })
-        .collect();
-    matches.iter().sum()
+    sum_line(&input_corrected)
+}
+
+pub struct DayThree;
+
+impl Solver for DayThree {
+    const DAY: u8 = 3;
+    const NAME: &'static str = "day_three";
+
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "lines",
+            default: "6",
+            help: "lines of corrupted memory",
+        },
+        Param {
+            name: "length",
+            default: "3000",
+            help: "about how many characters each line has",
+        },
+    ];
+
+    const EXAMPLES: &'static [Example] = &[Example::new(
+        include_str!("examples/day03.txt"),
+        "161",
+        "48",
+    )];
+
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        if let Some(mul) = mul_regex()
+            .captures_iter(input)
+            .find(|c| product(c).is_none())
+        {
+            return Err(Error::malformed(
+                Location::of(input, &mul[0]),
+                "a mul of numbers small enough to multiply",
+            ));
+        }
+        Ok(input.to_string())
+    }
+
+    fn part_one(input: &Self::Input) -> Answer {
+        let total: u128 = input.lines().map(sum_line).sum();
+        total.into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        let input: String = input.lines().collect();
+        sum_file(&input).into()
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let lines = generate::size(sizes, "lines", 1..=10_000)?;
+        let length = generate::size(sizes, "length", 1..=1_000_000)? as usize;
+        // things that look like instructions but aren't, and plain noise
+        const JUNK: &[&str] = &[
+            "mul[3,7]",
+            "mul(4*",
+            "mul ( 2 , 4 )",
+            "mul(32,64]",
+            "?(12,34)",
+            "don't",
+            "do",
+            "what()",
+            "select()",
+            "from()",
+            "why()",
+            "how(",
+            "%",
+            "&",
+            "+",
+            "[",
+            "]",
+            "<",
+            ">",
+            "'",
+            " ",
+            ")",
+            "(",
+            "@",
+            "#",
+            "~",
+            "^",
+            "/",
+            ":",
+            ",",
+            "!",
+            "*",
+        ];
+        let mut out = String::new();
+        for _ in 0..lines {
+            let start = out.len();
+            while out.len() - start < length {
+                match rng.below(10) {
+                    0..=2 => out.push_str(&format!(
+                        "mul({},{})",
+                        rng.between(1..=999),
+                        rng.between(1..=999)
+                    )),
+                    3 => out.push_str("do()"),
+                    4 => out.push_str("don't()"),
+                    _ => out.push_str(rng.pick::<&str>(JUNK)),
+                }
+            }
+            out.push('\n');
+        }
+        Ok(Some(out))
+    }
 }
 
 #[cfg(test)]
@@ -76,6 +174,25 @@ mod tests {
         assert_eq!(sum_line("mul(100,100),,, mul( 5,    1)mul(3,8)"), 10024);
     }
 
+    #[test]
+    fn test_big_muls() {
+        // the puzzle's numbers are never more than three digits but nothing
+        // says they can't be
+        let answers = solve("mul(1234,5)mul(4294967296,4294967295)").unwrap();
+        let sum = 6170 + 4294967296u128 * 4294967295;
+        assert_eq!(answers.part_one, Answer::Number(sum as u64));
+        let answers = solve(&"mul(4294967296,4294967295)".repeat(5)).unwrap();
+        assert_eq!(
+            answers.part_one,
+            Answer::Text((4294967296u128 * 4294967295 * 5).to_string())
+        );
+
+        let error = solve("mul(2,2)\nxmul(4294967296,4294967296)").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 2)));
+        let error = solve("mul(99999999999999999999,1)").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(1, 1)));
+    }
+
     #[test]
     fn test_sum_file() {
         assert_eq!(
@@ -1,6 +1,14 @@
-use std::{collections::HashMap, fs::read_to_string, io::Result, time::Instant};
+use std::collections::HashMap;
 
-// Ok so the file is formated with two sections seperated by a newline
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+use crate::generate::{self, Rng};
+use crate::graph::{self, Cycle};
+use crate::params::{Param, Params};
+use crate::parse;
+use crate::solver::{self, Answer, Answers, Example, Solver};
+
+// Ok so the input is formated with two sections seperated by a newline
 // the first section contains X|Y pairs that are numbers indicating some pages
 // page ordering rules - 47|53 means that if an update contains both then 47 must be before 53 (not
 // immedialty)
@@ -9,34 +17,22 @@ use std::{collections::HashMap, fs::read_to_string, io::Result, time::Instant};
 //
 // so i think store the rules and the check each one is satisfied. Can probably then
 // first calculate for redundant rules
-pub fn day_five(path: &str) -> Result<()> {
-    let now = Instant::now();
-
-    let contents = read_to_string(path)?;
-
-    // TODO: Check that \n\n actually splits as intended
-    let (first, second) = contents.split_once("\n\n").unwrap();
-
-    let rules: Vec<Rule> = first.lines().map(Rule::from_str).collect();
-    let mut updates: Vec<Update> = second
-        .lines()
-        .map(|line| Update::new(line.into()))
-        .collect();
 
-    let updates_slice: &mut [Update] = &mut updates;
-
-    // apply solver to all the lines and count the bools
-    let (answer, fixed_answer) = solver(updates_slice, rules);
+/// the middle pages of the updates already in order, then of the ones that
+/// had to be put in order
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayFive>(input)
+}
 
-    println!(
-        "the answer is {} with {} and the time elapsed is {}",
-        answer,
-        fixed_answer,
-        now.elapsed().as_micros()
-    );
+pub fn day_five(path: &str) -> Result<()> {
+    let contents = read_input(path)?;
+    let answers = solve(&contents).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
     Ok(())
 }
 
+#[derive(Clone)]
 struct Update {
     line: String,
     vec: Vec<u64>,
@@ -45,24 +41,26 @@ struct Update {
 }
 
 impl Update {
-    fn new(line: String) -> Self {
-        let line_iter = line.split(",").map(|val| val.parse::<u64>().unwrap());
+    fn new(line: String) -> Result<Self> {
         let mut len: usize = 0;
 
         let mut map = HashMap::new();
         let mut vec = Vec::new();
-        for (i, page) in line_iter.enumerate() {
+        for (i, val) in line.split(",").enumerate() {
+            let page = val
+                .parse::<u64>()
+                .map_err(|_| Error::malformed(Location::of(&line, val), "a page number"))?;
             map.insert(page, i);
             vec.push(page);
             len = i;
         }
 
-        Update {
+        Ok(Update {
             line,
             vec,
             map,
             middle: len / 2,
-        }
+        })
     }
 
     fn check(&self, rule: &Rule) -> bool {
@@ -75,37 +73,6 @@ impl Update {
         pos_x < pos_y
     }
 
-    fn fix(&mut self, rule: &Rule) -> bool {
-        // this should take the update and check against the rule
-        // if the rule fails then change the underlying values
-        // of self so that it is fixed
-        // for now that means swapping the two positions
-        // need to change:
-        // vec -> easy with swap
-        // line -> need to write a function that converts a vector to a &str csv
-        // map -> should be easy to just overwrite the values of the two
-
-        let map_x = &self.map.get(&rule.x).cloned();
-        let map_y = &self.map.get(&rule.y).cloned();
-
-        let Some(pos_y) = map_y else {
-            return true;
-        };
-        let Some(pos_x) = map_x else {
-            return true;
-        };
-        if pos_x < pos_y {
-            return true;
-        }
-        self.vec.swap(*pos_x, *pos_y);
-        self.line = Self::vec_to_csv(&self.vec);
-
-        self.map.insert(rule.x, *pos_y);
-        self.map.insert(rule.y, *pos_x);
-
-        false
-    }
-
     fn vec_to_csv(vec_vals: &[u64]) -> String {
         let vec_strings: Vec<String> = vec_vals.iter().map(|v| v.to_string()).collect();
         vec_strings.join(",")
@@ -121,13 +88,25 @@ impl Update {
         true
     }
 
-    fn fix_all(&mut self, rules: &[Rule]) {
+    /// the rules that apply to this update as edges from each page to the
+    /// pages that have to come after it
+    fn rule_graph(&self, rules: &[Rule]) -> HashMap<u64, Vec<u64>> {
+        let mut graph: HashMap<u64, Vec<u64>> = HashMap::new();
         for rule in rules {
-            let valid = self.fix(rule);
-            if !valid {
-                return self.fix_all(rules);
+            if self.map.contains_key(&rule.x) && self.map.contains_key(&rule.y) {
+                graph.entry(rule.x).or_default().push(rule.y);
             }
         }
+        graph
+    }
+
+    /// put the pages in an order that satisfies every rule, which is a
+    /// topological sort of the rule graph. Rules that loop can't be satisfied
+    fn fix_all(&mut self, rules: &[Rule]) -> std::result::Result<(), Cycle<u64>> {
+        self.vec = graph::toposort(&self.rule_graph(rules), self.vec.iter().copied())?;
+        self.line = Self::vec_to_csv(&self.vec);
+        self.map = self.vec.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+        Ok(())
     }
 }
 
@@ -138,33 +117,170 @@ struct Rule {
 }
 
 impl Rule {
-    fn from_str(line: &str) -> Self {
+    fn from_str(line: &str) -> Result<Self> {
         // read a line of x|y and return the corresponding Rule
 
-        let (x, y) = line.split_once("|").unwrap();
-        Rule {
-            x: x.parse::<u64>().unwrap(),
-            y: y.parse::<u64>().unwrap(),
-        }
+        let (x, y) = line
+            .split_once("|")
+            .ok_or_else(|| Error::malformed(Location::new(1, 1), "a rule like 47|53"))?;
+        let page = |p: &str| {
+            p.parse::<u64>()
+                .map_err(|_| Error::malformed(Location::of(line, p), "a page number"))
+        };
+        Ok(Rule {
+            x: page(x)?,
+            y: page(y)?,
+        })
     }
 }
 
-fn solver(updates: &mut [Update], rules: Vec<Rule>) -> (u64, u64) {
-    // iteratre through all lines and check each rule is satisfied and return if follows rules
-    let mut sum: u64 = 0;
-    let mut fixed_sum: u64 = 0;
-    for update in updates {
-        let valid = update.check_all(&rules);
-        if valid {
-            let middle_val = update.vec[update.middle];
-            sum += middle_val;
-        } else {
-            update.fix_all(&rules);
-            let middle_val = update.vec[update.middle];
-            fixed_sum += middle_val;
+pub struct DayFive;
+
+pub struct Manual {
+    rules: Vec<Rule>,
+    updates: Vec<Update>,
+}
+
+impl Solver for DayFive {
+    const DAY: u8 = 5;
+    const NAME: &'static str = "day_five";
+
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "pages",
+            default: "49",
+            help: "different page numbers, from 2 to 90",
+        },
+        Param {
+            name: "updates",
+            default: "200",
+            help: "updates after the rules",
+        },
+        Param {
+            name: "longest",
+            default: "23",
+            help: "the most pages in an update, up to pages",
+        },
+    ];
+
+    const EXAMPLES: &'static [Example] = &[Example::new(
+        include_str!("examples/day05.txt"),
+        "143",
+        "123",
+    )];
+
+    type Input = Manual;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let sections = parse::sections(input);
+        let [first, rest @ ..] = &sections[..] else {
+            return Err(Error::MissingSection {
+                location: Location::of(input, &input[input.len()..]),
+                section: "rules",
+            });
+        };
+        if rest.is_empty() {
+            return Err(Error::MissingSection {
+                location: Location::of(input, &input[input.len()..]),
+                section: "blank line between the rules and the updates",
+            });
+        }
+        let on_line = |line: &str| {
+            let number = Location::of(input, line).line;
+            move |e: Error| e.on_line(number)
+        };
+        let rules: Vec<Rule> = first
+            .lines()
+            .map(|line| Rule::from_str(line).map_err(on_line(line)))
+            .collect::<Result<_>>()?;
+        let lines: Vec<&str> = rest.iter().flat_map(|section| section.lines()).collect();
+        let updates: Vec<Update> = lines
+            .iter()
+            .map(|line| Update::new((*line).into()).map_err(on_line(line)))
+            .collect::<Result<_>>()?;
+
+        // part two reorders the pages, which the rules have to allow. When
+        // they don't loop at all that's the case for every update at once
+        let mut all: HashMap<u64, Vec<u64>> = HashMap::new();
+        for rule in &rules {
+            all.entry(rule.x).or_default().push(rule.y);
         }
+        if graph::toposort(&all, all.keys().copied()).is_err() {
+            // the puzzle's own rules do go round, just never through only the
+            // pages of one update
+            for (line, update) in lines.iter().zip(&updates) {
+                let order = graph::toposort(&update.rule_graph(&rules), update.vec.iter().copied());
+                if let Err(Cycle(pages)) = order {
+                    let pages: Vec<String> = pages.iter().map(u64::to_string).collect();
+                    return Err(Error::malformed(
+                        Location::of(input, line),
+                        format!("rules that don't loop, {} go round", pages.join("|")),
+                    ));
+                }
+            }
+        }
+        Ok(Manual { rules, updates })
+    }
+
+    fn part_one(input: &Self::Input) -> Answer {
+        let sum: u64 = input
+            .updates
+            .iter()
+            .filter(|u| u.check_all(&input.rules))
+            .map(|u| u.vec[u.middle])
+            .sum();
+        sum.into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        let sum: u64 = input
+            .updates
+            .iter()
+            .filter(|u| !u.check_all(&input.rules))
+            .map(|u| {
+                let mut fixed = u.clone();
+                fixed
+                    .fix_all(&input.rules)
+                    .expect("parsing checked the rules don't loop");
+                fixed.vec[fixed.middle]
+            })
+            .sum();
+        sum.into()
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        // one page on its own has nothing to come before or after, so no rules
+        let pages = generate::size(sizes, "pages", 2..=90)?;
+        let updates = generate::size(sizes, "updates", 1..=100_000)?;
+        let longest = generate::size(sizes, "longest", 1..=pages)?;
+        // the order the pages go in, with a rule for every pair of them so the
+        // pages of any update can only be put in order one way
+        let mut order: Vec<i64> = (10..10 + pages as i64).collect();
+        rng.shuffle(&mut order);
+        let mut rules = Vec::new();
+        for (i, before) in order.iter().enumerate() {
+            for after in &order[i + 1..] {
+                rules.push(format!("{before}|{after}"));
+            }
+        }
+        rng.shuffle(&mut rules);
+        let mut out = rules.join("\n") + "\n\n";
+        for _ in 0..updates {
+            // always an odd number of pages so there's one in the middle
+            let length = rng.between(0..=(longest as i64 - 1) / 2) as usize * 2 + 1;
+            let mut update: Vec<usize> = (0..pages as usize).collect();
+            rng.shuffle(&mut update);
+            update.truncate(length);
+            // about half are in the right order already
+            if rng.chance(0.5) {
+                update.sort_unstable();
+            }
+            let update: Vec<String> = update.iter().map(|&i| order[i].to_string()).collect();
+            out.push_str(&update.join(","));
+            out.push('\n');
+        }
+        Ok(Some(out))
     }
-    (sum, fixed_sum)
 }
 
 #[cfg(test)]
@@ -177,7 +293,7 @@ mod tests {
         //input is a &str like 1,2,3,4,5 and it stores a middle of 2, and hashmap with values
         //of 1,2,3,4,5 and their posiitions 0,1,2,3,4
         let input = "1,2,3,4,5";
-        let up = Update::new(input.into());
+        let up = Update::new(input.into()).unwrap();
         let mut expected_map = HashMap::new();
         expected_map.insert(1, 0);
         expected_map.insert(2, 1);
@@ -198,21 +314,21 @@ mod tests {
         // checks the update complies with a given rule i.e. 1,2,3,4,5 and 4|5 is true but 5|4 is
         // false
         //
-        let update = Update::new("1,2,3,4,5".into());
-        let rule = Rule::from_str("4|5");
-        let rule_to_fail = Rule::from_str("5|4");
+        let update = Update::new("1,2,3,4,5".into()).unwrap();
+        let rule = Rule::from_str("4|5").unwrap();
+        let rule_to_fail = Rule::from_str("5|4").unwrap();
         assert!(update.check(&rule));
         assert!(!update.check(&rule_to_fail));
     }
 
     #[test]
     fn test_day_update_check_all() {
-        let update = Update::new("1,2,3,4,5".into());
-        let rule = Rule::from_str("4|5");
-        let rule_to_fail = Rule::from_str("5|4");
-        let rule2 = Rule::from_str("1|2");
-        let rule3 = Rule::from_str("3|5");
-        let rule4 = Rule::from_str("1|5");
+        let update = Update::new("1,2,3,4,5".into()).unwrap();
+        let rule = Rule::from_str("4|5").unwrap();
+        let rule_to_fail = Rule::from_str("5|4").unwrap();
+        let rule2 = Rule::from_str("1|2").unwrap();
+        let rule3 = Rule::from_str("3|5").unwrap();
+        let rule4 = Rule::from_str("1|5").unwrap();
         let rules = vec![rule, rule2];
         let failed_rules = vec![rule3, rule4, rule_to_fail];
 
@@ -221,20 +337,25 @@ mod tests {
     }
 
     #[test]
-    fn test_day_update_fix() {
-        let mut update = Update::new("1,2,3,4,5".into());
-        let rule_to_fail = Rule::from_str("5|4");
-        let expected_vec = vec![1, 2, 3, 5, 4];
-        let expected_line = "1,2,3,5,4";
+    fn test_day_update_fix_all() {
+        let mut update = Update::new("1,2,3,4,5".into()).unwrap();
+        let rules = [
+            Rule::from_str("5|4").unwrap(),
+            Rule::from_str("3|2").unwrap(),
+            Rule::from_str("5|1").unwrap(),
+        ];
+        let expected_vec = vec![5, 4, 3, 2, 1];
+        let expected_line = "5,4,3,2,1";
         let mut expected_map = HashMap::new();
-        expected_map.insert(1, 0);
-        expected_map.insert(2, 1);
+        expected_map.insert(5, 0);
+        expected_map.insert(4, 1);
         expected_map.insert(3, 2);
-        expected_map.insert(5, 3);
-        expected_map.insert(4, 4);
+        expected_map.insert(2, 3);
+        expected_map.insert(1, 4);
         let expected_middle = 2;
 
-        update.fix(&rule_to_fail);
+        update.fix_all(&rules).unwrap();
+        assert!(update.check_all(&rules));
         assert!(update.vec == expected_vec);
         assert!(update.line == expected_line);
         assert!(update.map == expected_map);
@@ -242,26 +363,36 @@ mod tests {
     }
 
     #[test]
-    fn test_day_update_fix_all() {
-        let mut update = Update::new("1,2,3,4,5".into());
-        let rule_1 = Rule::from_str("5|4");
-        let rule_2 = Rule::from_str("3|2");
-        let rule_3 = Rule::from_str("5|1");
-        let expected_vec = vec![5, 3, 2, 1, 4];
-        let expected_line = "5,3,2,1,4";
-        let mut expected_map = HashMap::new();
-        expected_map.insert(5, 0);
-        expected_map.insert(3, 1);
-        expected_map.insert(2, 2);
-        expected_map.insert(1, 3);
-        expected_map.insert(4, 4);
-        let expected_middle = 2;
+    fn test_day_update_fix_all_cycle() {
+        let mut update = Update::new("1,2,3".into()).unwrap();
+        let rules = [
+            Rule::from_str("1|2").unwrap(),
+            Rule::from_str("2|3").unwrap(),
+            Rule::from_str("3|1").unwrap(),
+        ];
+        assert_eq!(update.fix_all(&rules), Err(Cycle(vec![1, 2, 3])));
+        let error = DayFive::parse("1|2\n2|3\n3|1\n\n4,5\n1,2,3\n")
+            .err()
+            .unwrap();
+        assert_eq!(error.location(), Some(&Location::new(6, 1)));
+        assert!(error.to_string().contains("1|2|3 go round"));
+
+        // a loop no update has all of is fine
+        let manual = DayFive::parse("1|2\n2|3\n3|1\n\n1,2\n3,2\n").unwrap();
+        assert_eq!(DayFive::part_one(&manual), Answer::Number(1));
+        assert_eq!(DayFive::part_two(&manual), Answer::Number(2));
+    }
 
-        update.fix_all(&[rule_1, rule_2, rule_3]);
-        assert!(update.vec == expected_vec);
-        assert!(update.line == expected_line);
-        assert!(update.map == expected_map);
-        assert!(update.middle == expected_middle);
+    #[test]
+    fn test_parse_errors() {
+        let error = DayFive::parse("47|53\n97-13\n\n75,47").err().unwrap();
+        assert_eq!(error.location(), Some(&Location::new(2, 1)));
+
+        let error = DayFive::parse("47|53\n\n75,47\n61,x,29").err().unwrap();
+        assert_eq!(error.location(), Some(&Location::new(4, 4)));
+
+        let error = DayFive::parse("47|53\n75,47\n").err().unwrap();
+        assert!(matches!(error, Error::MissingSection { .. }));
     }
 
     #[test]
@@ -269,7 +400,7 @@ mod tests {
         //takes &str and reutrn the x and y in a rule
         //
         let input = "5|4";
-        let rule = Rule::from_str(input);
+        let rule = Rule::from_str(input).unwrap();
         assert_eq!(rule.x, 5);
         assert_eq!(rule.y, 4);
     }
@@ -278,19 +409,40 @@ mod tests {
     fn test_day_solver() {
         //takes a vec of updates and rules and returns the sum of the middle vals of those i
         // in correct order can
-        let update1 = Update::new("1,2,3,4,5".into());
-        let update2 = Update::new("10,20,30,40,50".into());
-        let update3 = Update::new("100,200,201,202,203".into());
-
-        let rule2 = Rule::from_str("1|2");
-        let rule3 = Rule::from_str("3|5");
-        let rule4 = Rule::from_str("1|5");
-        let rule5 = Rule::from_str("203|202");
+        let update1 = Update::new("1,2,3,4,5".into()).unwrap();
+        let update2 = Update::new("10,20,30,40,50".into()).unwrap();
+        let update3 = Update::new("100,200,201,202,203".into()).unwrap();
+
+        let rule2 = Rule::from_str("1|2").unwrap();
+        let rule3 = Rule::from_str("3|5").unwrap();
+        let rule4 = Rule::from_str("1|5").unwrap();
+        let rule5 = Rule::from_str("203|202").unwrap();
+
+        let manual = Manual {
+            rules: vec![rule2, rule3, rule4, rule5],
+            updates: vec![update1, update2, update3],
+        };
 
-        let mut updates = vec![update1, update2, update3];
-        let rules = vec![rule2, rule3, rule4, rule5];
-        let updates_slice: &mut [Update] = &mut updates;
+        assert_eq!(DayFive::part_one(&manual), Answer::Number(33));
+        assert_eq!(DayFive::part_two(&manual), Answer::Number(201));
+    }
 
-        assert_eq!(solver(updates_slice, rules), (33, 201));
+    #[test]
+    fn test_generate_pages() {
+        let mut sizes = Params::new();
+        sizes.set("pages", "1");
+        let one = sizes.resolve(DayFive::SIZES).unwrap();
+        let error = DayFive::generate(&mut Rng::new(1), &one).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "parameter 'pages' is 1, it has to be from 2 to 90"
+        );
+        sizes.set("pages", "2");
+        sizes.set("longest", "2");
+        let sizes = sizes.resolve(DayFive::SIZES).unwrap();
+        let input = DayFive::generate(&mut Rng::new(1), &sizes)
+            .unwrap()
+            .unwrap();
+        assert_eq!(DayFive::parse(&input).unwrap().rules.len(), 1);
     }
 }
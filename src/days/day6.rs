@@ -1,8 +1,15 @@
 use std::cmp::PartialEq;
 use std::collections::HashSet;
-use std::fs::read;
-use std::io::BufRead;
-use std::time::Instant;
+use std::fmt;
+
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+use crate::generate::{self, Rng};
+use crate::geom::{Direction, Position};
+use crate::grid::Grid;
+use crate::params::{Param, Params};
+use crate::solver::{self, Answer, Answers, Example, Solver};
+use crate::visual::{Cell, Colour, Frame, Frames};
 
 #[derive(PartialEq, Clone, Debug)]
 enum Tile {
@@ -22,41 +29,17 @@ enum Move {
     Finish,
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn rotate(&mut self) {
-        *self = match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
-}
-
-#[derive(Debug)]
-enum TileError {
-    InvalidTile,
-}
-
 impl Tile {
-    fn from_char(tile_char: &char) -> std::result::Result<Self, TileError> {
+    fn from_char(tile_char: char) -> Option<Self> {
         match tile_char {
-            '.' => Ok(Tile::Open),
-            '#' => Ok(Tile::Obstacle),
-            'X' => Ok(Tile::Visited),
-            '^' => Ok(Tile::Current(Direction::Up)),
-            'v' => Ok(Tile::Current(Direction::Down)),
-            '<' => Ok(Tile::Current(Direction::Left)),
-            '>' => Ok(Tile::Current(Direction::Right)),
-            _ => Err(TileError::InvalidTile),
+            '.' => Some(Tile::Open),
+            '#' => Some(Tile::Obstacle),
+            'X' => Some(Tile::Visited),
+            '^' => Some(Tile::Current(Direction::Up)),
+            'v' => Some(Tile::Current(Direction::Down)),
+            '<' => Some(Tile::Current(Direction::Left)),
+            '>' => Some(Tile::Current(Direction::Right)),
+            _ => None,
         }
     }
 
@@ -73,88 +56,84 @@ impl Tile {
     }
 }
 
-#[derive(Debug)]
-enum MapError {
-    NoGuard,
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
 }
 
 #[derive(Debug, Clone)]
-struct Map {
-    grid: Vec<Vec<Tile>>,
+pub struct Map {
+    grid: Grid<Tile>,
     completed: bool,
     guard: Guard,
     positions_visited: u16,
 }
 
 impl Map {
-    fn new(char_grid: Vec<Vec<char>>) -> Self {
-        // we will want to construct this from the input string and find the guard to initialise
-        // a guard on the map
+    fn new(grid: Grid<Tile>) -> Result<Self> {
+        // find the guard on the tiles to initialise a guard on the map
+        let guard = Self::find_guard(&grid)?;
 
-        let grid = Self::from_char_grid(char_grid);
-
-        let guard = Self::find_guard(grid.clone()).unwrap();
-
-        Map {
+        Ok(Map {
             grid,
             completed: false,
             guard,
             positions_visited: 1,
-        }
+        })
     }
 
-    fn from_char_grid(char_grid: Vec<Vec<char>>) -> Vec<Vec<Tile>> {
-        let mut result = Vec::new();
-        for row in char_grid {
-            let row_tiles: Vec<Tile> = row.iter().map(|c| Tile::from_char(c).unwrap()).collect();
-            result.push(row_tiles);
-        }
-        result
+    fn from_chars(input: &str) -> Result<Grid<Tile>> {
+        Grid::parse(input, Tile::from_char)
     }
 
-    fn find_guard(grid: Vec<Vec<Tile>>) -> std::result::Result<Guard, MapError> {
-        for (i, row) in grid.iter().enumerate() {
-            for (j, tile) in row.iter().enumerate() {
-                match tile {
-                    Tile::Current(direction) => {
-                        let direction = direction.clone();
-                        return Ok(Guard::new(j as u16, i as u16, direction));
-                    }
-                    _ => continue,
-                }
-            }
-        }
-        Err(MapError::NoGuard)
+    fn find_guard(grid: &Grid<Tile>) -> Result<Guard> {
+        let guard = grid
+            .position(|tile| matches!(tile, Tile::Current(_)))
+            .ok_or(Error::MissingSection {
+                location: Location::new(1, 1),
+                section: "guard, one of '^', 'v', '<' or '>'",
+            })?;
+        let Tile::Current(direction) = &grid[guard] else {
+            unreachable!("the guard was found by its tile");
+        };
+        let start = Position::from(guard);
+        Ok(Guard::new(start.x, start.y, *direction))
     }
 
     fn step(&mut self) {
         // here we will update the map with the new iteration
         let next_move = self.calculate_next_step();
-        let current_x = self.guard.position.x;
-        let current_y = self.guard.position.y;
-        let current_dir = self.guard.position.direction.clone();
+        let current = self
+            .grid
+            .point(self.guard.position)
+            .expect("the guard is on the map");
+        let current_dir = self.guard.direction;
 
         match next_move {
             Move::Rotate => {
                 self.guard.rotate();
 
-                let new_dir = self.guard.position.direction.clone();
-                self.grid[current_y as usize][current_x as usize] = Tile::Current(new_dir);
+                let new_dir = self.guard.direction;
+                self.grid[current] = Tile::Current(new_dir);
             }
             Move::Finish => {
-                self.guard.history.insert(self.guard.position.clone());
-                self.grid[current_y as usize][current_x as usize] = Tile::Visited;
+                self.guard.history.insert(self.guard.pose());
+                self.grid[current] = Tile::Visited;
                 self.completed = true;
             }
             _ => {
                 // the guard walks and the tiles of the map are updated and the postiitons is
                 // updated if they havenet visited the new spot
-                let (new_x, new_y) = self.guard.walk();
-                if self.grid[new_y as usize][new_x as usize] == Tile::Open {
+                let new = self
+                    .grid
+                    .point(self.guard.walk())
+                    .expect("the guard only walks on the map");
+                if self.grid[new] == Tile::Open {
                     self.positions_visited += 1;
                 }
-                self.grid[current_y as usize][current_x as usize] = Tile::Visited;
-                self.grid[new_y as usize][new_x as usize] = Tile::Current(current_dir);
+                self.grid[current] = Tile::Visited;
+                self.grid[new] = Tile::Current(current_dir);
             }
         }
     }
@@ -163,69 +142,50 @@ impl Map {
         // take the guards current direction and check if there is a
         // obstacle in that direction if not move in that dierecti0on
         // account for off map
-
-        match self.guard.position.direction {
-            Direction::Up => {
-                if self.guard.position.y > 0 {
-                    if self.grid[(self.guard.position.y - 1) as usize]
-                        [self.guard.position.x as usize]
-                        == Tile::Obstacle
-                    {
-                        return Move::Rotate;
-                    }
-                    return Move::Up;
-                }
-                Move::Finish
-            }
-
-            Direction::Down => {
-                if (self.guard.position.y as usize) < self.grid.len() - 1 {
-                    if self.grid[(self.guard.position.y + 1) as usize]
-                        [self.guard.position.x as usize]
-                        == Tile::Obstacle
-                    {
-                        return Move::Rotate;
-                    }
-                    return Move::Down;
-                }
-                Move::Finish
-            }
-
-            Direction::Left => {
-                if self.guard.position.x > 0 {
-                    if self.grid[self.guard.position.y as usize]
-                        [(self.guard.position.x - 1) as usize]
-                        == Tile::Obstacle
-                    {
-                        return Move::Rotate;
-                    }
-                    return Move::Left;
-                }
-                Move::Finish
-            }
-            Direction::Right => {
-                if (self.guard.position.x as usize) < self.grid.first().unwrap().len() - 1 {
-                    if self.grid[self.guard.position.y as usize]
-                        [(self.guard.position.x + 1) as usize]
-                        == Tile::Obstacle
-                    {
-                        return Move::Rotate;
-                    }
-                    return Move::Right;
-                }
-                Move::Finish
-            }
+        let ahead = self
+            .grid
+            .point(self.guard.position + self.guard.direction.step());
+        match ahead {
+            None => Move::Finish,
+            Some(ahead) if self.grid[ahead] == Tile::Obstacle => Move::Rotate,
+            Some(_) => match self.guard.direction {
+                Direction::Up => Move::Up,
+                Direction::Down => Move::Down,
+                Direction::Left => Move::Left,
+                Direction::Right => Move::Right,
+            },
         }
     }
 
-    fn print_map(&self) {
-        // will just print out the current tiles as a map
-        println!("-----------------STEP-----------------------");
-        for row in &self.grid {
-            let row_chars: String = row.iter().map(|tile| tile.to_char().to_string()).collect();
-            println!("{row_chars}");
-        }
-        println!("--------------------------------------------");
+    /// the map as it stands, the guard in red on the trail they've left
+    fn frame(&self, steps: usize) -> Frame {
+        let cells = self.grid.map(|tile| match tile {
+            Tile::Open => Cell::new('.', Colour::Grey),
+            Tile::Obstacle => Cell::new('#', Colour::White),
+            Tile::Visited => Cell::new('X', Colour::Yellow),
+            Tile::Current(_) => Cell::new(tile.to_char(), Colour::Red),
+        });
+        Frame::new(
+            cells,
+            format!("step {steps}, {} positions visited", self.positions_visited),
+        )
+    }
+
+    /// the guard's walk a step at a time, ending once they leave the map
+    pub fn frames(&self) -> impl Iterator<Item = Frame> + use<> {
+        let mut map = self.clone();
+        let mut steps = 0;
+        std::iter::once(map.frame(0)).chain(std::iter::from_fn(move || {
+            if map.completed {
+                return None;
+            }
+            map.step();
+            if map.guard.looped {
+                map.completed = true;
+            }
+            steps += 1;
+            Some(map.frame(steps))
+        }))
     }
 
     fn solve(&mut self) -> u16 {
@@ -252,14 +212,12 @@ impl Map {
         // return the number of configs for which that results in a loop
 
         let mut result = 0;
-        for (i, row) in self.grid.iter().enumerate() {
-            for (j, tile) in row.iter().enumerate() {
-                if tile == &Tile::Open {
-                    let mut map_clone = self.clone();
-                    map_clone.grid[i][j] = Tile::Obstacle;
-                    if map_clone.check_looped() {
-                        result += 1;
-                    }
+        for (point, tile) in self.grid.iter() {
+            if tile == &Tile::Open {
+                let mut map_clone = self.clone();
+                map_clone.grid[point] = Tile::Obstacle;
+                if map_clone.check_looped() {
+                    result += 1;
                 }
             }
         }
@@ -267,88 +225,160 @@ impl Map {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Position {
-    x: u16,
-    y: u16,
-    direction: Direction,
-}
+/// Where the guard stands and which way they face. Being in the same pose
+/// twice means the guard is walking in a loop
+type Pose = (Position, Direction);
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Guard {
     position: Position,
-    history: HashSet<Position>,
+    direction: Direction,
+    history: HashSet<Pose>,
     looped: bool,
 }
 
 impl Guard {
-    fn new(x: u16, y: u16, direction: Direction) -> Self {
-        let history: HashSet<Position> = HashSet::new();
-        let position = Position { x, y, direction };
+    fn new(x: i64, y: i64, direction: Direction) -> Self {
         Guard {
-            position,
-            history,
+            position: Position::new(x, y),
+            direction,
+            history: HashSet::new(),
             looped: false,
         }
     }
 
-    fn walk(&mut self) -> (u16, u16) {
-        self.history.insert(self.position.clone());
-        match self.position.direction {
-            Direction::Up => self.position.y -= 1,
-            Direction::Down => self.position.y += 1,
-            Direction::Left => self.position.x -= 1,
-            Direction::Right => self.position.x += 1,
-        };
-        match self.history.get(&self.position) {
-            Some(_) => self.looped = true,
-            None => self.looped = false,
-        };
-        (self.position.x, self.position.y)
+    fn pose(&self) -> Pose {
+        (self.position, self.direction)
+    }
+
+    fn walk(&mut self) -> Position {
+        self.history.insert(self.pose());
+        self.position += self.direction.step();
+        self.looped = self.history.contains(&self.pose());
+        self.position
     }
 
     fn rotate(&mut self) {
-        self.history.insert(self.position.clone());
-        self.position.direction.rotate();
-        match self.history.get(&self.position) {
-            Some(_) => self.looped = true,
-            None => self.looped = false,
-        };
+        self.history.insert(self.pose());
+        self.direction = self.direction.turn_right();
+        self.looped = self.history.contains(&self.pose());
     }
 }
 
-pub fn day_six(path: &str) -> std::io::Result<()> {
-    let now = Instant::now();
-    let contents = read(path)?;
-    let grid: Vec<Vec<char>> = contents
-        .lines()
-        .map(|l| l.unwrap().chars().collect())
-        .collect();
-    let mut map = Map::new(grid);
-    let map_clone = map.clone();
-    let unique_pos = map.solve();
-    let num_pos = map_clone.find_loop_obstacle_pos();
-    println!(
-        "unique postions {} and num_obstacles {} in {}us",
-        unique_pos,
-        num_pos,
-        now.elapsed().as_micros(),
-    );
-    map.print_map();
+/// the tiles the guard walks over, then the spots an obstacle would loop them
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DaySix>(input)
+}
+
+pub fn day_six(path: &str) -> Result<()> {
+    let contents = read_input(path)?;
+    let answers = solve(&contents).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
     Ok(())
 }
 
+pub struct DaySix;
+
+impl Solver for DaySix {
+    const DAY: u8 = 6;
+    const NAME: &'static str = "day_six";
+
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "width",
+            default: "130",
+            help: "tiles across the lab",
+        },
+        Param {
+            name: "height",
+            default: "130",
+            help: "tiles down the lab",
+        },
+        Param {
+            name: "obstacles",
+            default: "2",
+            help: "the percentage of tiles with an obstacle on, up to 30",
+        },
+    ];
+
+    const EXAMPLES: &'static [Example] =
+        &[Example::new(include_str!("examples/day06.txt"), "41", "6")];
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Map::new(Map::from_chars(input)?)
+    }
+
+    fn part_one(input: &Self::Input) -> Answer {
+        input.clone().solve().into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        input.find_loop_obstacle_pos().into()
+    }
+
+    fn visualize(input: &Self::Input) -> Option<Frames> {
+        Some(Box::new(input.frames()))
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let width = generate::size(sizes, "width", 1..=1000)?;
+        let height = generate::size(sizes, "height", 1..=1000)?;
+        let obstacles = generate::size(sizes, "obstacles", 0..=30)? as f64 / 100.0;
+        // the guard always walks off the map in the end, so keep making maps
+        // until there's one where they do
+        loop {
+            let guard = (rng.below(width), rng.below(height));
+            let map = generate::grid(width, height, |x, y| {
+                if (x, y) == guard {
+                    '^'
+                } else if rng.chance(obstacles) {
+                    '#'
+                } else {
+                    '.'
+                }
+            });
+            if !Self::parse(&map)?.check_looped() {
+                return Ok(Some(map));
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_generate_lets_the_guard_out() {
+        let mut sizes = Params::new();
+        for (name, value) in [("width", "12"), ("height", "9"), ("obstacles", "30")] {
+            sizes.set(name, value);
+        }
+        for seed in 0..20 {
+            let input = solver::generate_with::<DaySix>(seed, &sizes)
+                .unwrap()
+                .unwrap();
+            assert_eq!(input.matches('^').count(), 1);
+            assert!(!DaySix::parse(&input).unwrap().check_looped());
+        }
+    }
+
+    fn tiles(rows: Vec<Vec<char>>) -> Grid<Tile> {
+        Grid::from_rows(rows)
+            .unwrap()
+            .map(|c| Tile::from_char(*c).unwrap())
+    }
+
     #[test]
     fn test_guard_new() {
         let guard = Guard::new(10, 20, Direction::Up);
-        let expected_history: HashSet<Position> = HashSet::new();
+        let expected_history: HashSet<Pose> = HashSet::new();
         assert_eq!(10, guard.position.x);
         assert_eq!(20, guard.position.y);
-        assert_eq!(Direction::Up, guard.position.direction);
+        assert_eq!(Direction::Up, guard.direction);
         assert_eq!(expected_history, guard.history);
         assert!(!guard.looped);
     }
@@ -358,12 +388,8 @@ mod test {
         let mut guard = Guard::new(10, 20, Direction::Up);
 
         guard.rotate();
-        assert_eq!(Direction::Right, guard.position.direction);
-        let expected_position = Position {
-            x: 10,
-            y: 20,
-            direction: Direction::Up,
-        };
+        assert_eq!(Direction::Right, guard.direction);
+        let expected_position = (Position::new(10, 20), Direction::Up);
         assert_eq!(
             guard.history.get(&expected_position),
             Some(&expected_position)
@@ -372,12 +398,8 @@ mod test {
         assert!(!guard.looped);
 
         guard.rotate();
-        assert_eq!(Direction::Down, guard.position.direction);
-        let expected_position = Position {
-            x: 10,
-            y: 20,
-            direction: Direction::Right,
-        };
+        assert_eq!(Direction::Down, guard.direction);
+        let expected_position = (Position::new(10, 20), Direction::Right);
         assert_eq!(
             guard.history.get(&expected_position),
             Some(&expected_position)
@@ -386,12 +408,8 @@ mod test {
         assert!(!guard.looped);
 
         guard.rotate();
-        assert_eq!(Direction::Left, guard.position.direction);
-        let expected_position = Position {
-            x: 10,
-            y: 20,
-            direction: Direction::Down,
-        };
+        assert_eq!(Direction::Left, guard.direction);
+        let expected_position = (Position::new(10, 20), Direction::Down);
         assert_eq!(
             guard.history.get(&expected_position),
             Some(&expected_position)
@@ -400,12 +418,8 @@ mod test {
         assert!(!guard.looped);
 
         guard.rotate();
-        assert_eq!(Direction::Up, guard.position.direction);
-        let expected_position = Position {
-            x: 10,
-            y: 20,
-            direction: Direction::Left,
-        };
+        assert_eq!(Direction::Up, guard.direction);
+        let expected_position = (Position::new(10, 20), Direction::Left);
         assert_eq!(
             guard.history.get(&expected_position),
             Some(&expected_position)
@@ -422,11 +436,7 @@ mod test {
         guard.walk();
 
         let mut expected_history = HashSet::new();
-        let prev_pos = Position {
-            x: 2,
-            y: 2,
-            direction: Direction::Up,
-        };
+        let prev_pos = (Position::new(2, 2), Direction::Up);
         expected_history.insert(prev_pos);
         assert_eq!(expected_history, guard.history);
         assert_eq!(2, guard.position.x);
@@ -435,41 +445,25 @@ mod test {
         // rotate to right 2,1
         guard.rotate();
 
-        expected_history.insert(Position {
-            x: 2,
-            y: 1,
-            direction: Direction::Up,
-        });
+        expected_history.insert((Position::new(2, 1), Direction::Up));
 
         // rotate to down 2,1
         guard.rotate();
 
-        expected_history.insert(Position {
-            x: 2,
-            y: 1,
-            direction: Direction::Right,
-        });
+        expected_history.insert((Position::new(2, 1), Direction::Right));
 
         // rotate to left 2, 1
         guard.rotate();
 
-        expected_history.insert(Position {
-            x: 2,
-            y: 1,
-            direction: Direction::Down,
-        });
+        expected_history.insert((Position::new(2, 1), Direction::Down));
 
         // walk left to 1,1
         guard.walk();
-        expected_history.insert(Position {
-            x: 2,
-            y: 1,
-            direction: Direction::Left,
-        });
+        expected_history.insert((Position::new(2, 1), Direction::Left));
 
         assert_eq!(1, guard.position.x);
         assert_eq!(1, guard.position.y);
-        assert_eq!(Direction::Left, guard.position.direction);
+        assert_eq!(Direction::Left, guard.direction);
         assert_eq!(expected_history, guard.history);
     }
 
@@ -480,7 +474,7 @@ mod test {
             vec!['.', '^', '#'],
             vec!['.', '.', '.'],
         ];
-        let map = Map::new(grid);
+        let map = Map::new(tiles(grid)).unwrap();
 
         let expected_grid = vec![
             vec![Tile::Open, Tile::Obstacle, Tile::Open],
@@ -491,7 +485,7 @@ mod test {
         let expected_completed = false;
         let expected_positions_visited = 1;
 
-        assert_eq!(expected_grid, map.grid);
+        assert_eq!(Grid::from_rows(expected_grid).unwrap(), map.grid);
         assert_eq!(expected_guard, map.guard);
         assert_eq!(expected_completed, map.completed);
         assert_eq!(expected_positions_visited, map.positions_visited);
@@ -499,19 +493,24 @@ mod test {
 
     #[test]
     fn test_map_from_char() {
-        let grid = vec![
-            vec!['.', '#', '.'],
-            vec!['.', '^', '#'],
-            vec!['.', '.', '.'],
-        ];
-        let tile_grid = Map::from_char_grid(grid);
+        let tile_grid = Map::from_chars(".#.\n.^#\n...").unwrap();
 
         let expected_grid = vec![
             vec![Tile::Open, Tile::Obstacle, Tile::Open],
             vec![Tile::Open, Tile::Current(Direction::Up), Tile::Obstacle],
             vec![Tile::Open, Tile::Open, Tile::Open],
         ];
-        assert_eq!(expected_grid, tile_grid);
+        assert_eq!(Grid::from_rows(expected_grid).unwrap(), tile_grid);
+    }
+
+    #[test]
+    fn test_map_new_errors() {
+        let error = DaySix::parse(".#\n.?").unwrap_err();
+        assert!(matches!(error, Error::InvalidTile { found: '?', .. }));
+        assert_eq!(error.location(), Some(&Location::new(2, 2)));
+
+        let error = DaySix::parse(".#\n..").unwrap_err();
+        assert!(matches!(error, Error::MissingSection { .. }));
     }
 
     #[test]
@@ -521,7 +520,7 @@ mod test {
             vec![Tile::Open, Tile::Current(Direction::Up), Tile::Obstacle],
             vec![Tile::Open, Tile::Open, Tile::Open],
         ];
-        let guard = Map::find_guard(tile_grid).unwrap();
+        let guard = Map::find_guard(&Grid::from_rows(tile_grid).unwrap()).unwrap();
 
         let expected_guard = Guard::new(1, 1, Direction::Up);
         assert_eq!(expected_guard, guard);
@@ -535,19 +534,15 @@ mod test {
             vec!['.', '^', '#'],
             vec!['.', '.', '.'],
         ];
-        let mut map = Map::new(start_grid);
+        let mut map = Map::new(tiles(start_grid)).unwrap();
 
-        let expected_grid = Map::from_char_grid(vec![
+        let expected_grid = tiles(vec![
             vec!['.', '#', '.'],
             vec!['.', '>', '#'],
             vec!['.', '.', '.'],
         ]);
         let mut expected_guard = Guard::new(1, 1, Direction::Right);
-        let prev_pos = Position {
-            x: 1,
-            y: 1,
-            direction: Direction::Up,
-        };
+        let prev_pos = (Position::new(1, 1), Direction::Up);
         let mut clean_history = HashSet::new();
         clean_history.insert(prev_pos);
         expected_guard.history = clean_history.clone();
@@ -569,19 +564,15 @@ mod test {
             vec!['.', 'v', '#'],
             vec!['.', '.', '.'],
         ];
-        let mut map = Map::new(start_grid);
+        let mut map = Map::new(tiles(start_grid)).unwrap();
 
-        let expected_grid = Map::from_char_grid(vec![
+        let expected_grid = tiles(vec![
             vec!['.', '#', '.'],
             vec!['.', 'X', '#'],
             vec!['.', 'v', '.'],
         ]);
         let mut expected_guard = Guard::new(1, 2, Direction::Down);
-        let prev_pos = Position {
-            x: 1,
-            y: 1,
-            direction: Direction::Down,
-        };
+        let prev_pos = (Position::new(1, 1), Direction::Down);
         let mut clean_history = HashSet::new();
         clean_history.insert(prev_pos);
         expected_guard.history = clean_history.clone();
@@ -603,28 +594,12 @@ mod test {
             vec!['>', '.', '#'],
             vec!['.', '.', '.'],
         ];
-        let mut map = Map::new(start_grid);
+        let mut map = Map::new(tiles(start_grid)).unwrap();
 
-        let first_pos = Position {
-            x: 0,
-            y: 1,
-            direction: Direction::Right,
-        };
-        let second_pos = Position {
-            x: 1,
-            y: 1,
-            direction: Direction::Right,
-        };
-        let third_pos = Position {
-            x: 1,
-            y: 1,
-            direction: Direction::Down,
-        };
-        let fourth_pos = Position {
-            x: 1,
-            y: 2,
-            direction: Direction::Down,
-        };
+        let first_pos = (Position::new(0, 1), Direction::Right);
+        let second_pos = (Position::new(1, 1), Direction::Right);
+        let third_pos = (Position::new(1, 1), Direction::Down);
+        let fourth_pos = (Position::new(1, 2), Direction::Down);
         let mut clean_history = HashSet::new();
         clean_history.insert(first_pos);
         clean_history.insert(second_pos);
@@ -646,19 +621,15 @@ mod test {
             vec!['.', '.', '#'],
             vec!['.', 'v', '.'],
         ];
-        let mut map = Map::new(start_grid);
+        let mut map = Map::new(tiles(start_grid)).unwrap();
 
-        let expected_grid = Map::from_char_grid(vec![
+        let expected_grid = tiles(vec![
             vec!['.', '#', '.'],
             vec!['.', '.', '#'],
             vec!['.', 'X', '.'],
         ]);
         let mut expected_guard = Guard::new(1, 2, Direction::Down);
-        let prev_pos = Position {
-            x: 1,
-            y: 2,
-            direction: Direction::Down,
-        };
+        let prev_pos = (Position::new(1, 2), Direction::Down);
         let mut clean_history = HashSet::new();
         clean_history.insert(prev_pos);
         expected_guard.history = clean_history.clone();
@@ -680,7 +651,7 @@ mod test {
             vec!['#', '^', '#'],
             vec!['.', '#', '.'],
         ];
-        let mut map = Map::new(start_grid);
+        let mut map = Map::new(tiles(start_grid)).unwrap();
 
         map.step();
         assert!(!map.guard.looped);
@@ -700,7 +671,7 @@ mod test {
             vec!['.', '^', '#'],
             vec!['.', '.', '.'],
         ];
-        let map = Map::new(grid);
+        let map = Map::new(tiles(grid)).unwrap();
         let m = map.calculate_next_step();
 
         let expected_m = Move::Rotate;
@@ -713,7 +684,7 @@ mod test {
             vec!['.', '.', '.'],
         ];
 
-        let map = Map::new(grid);
+        let map = Map::new(tiles(grid)).unwrap();
         let m = map.calculate_next_step();
 
         let expected_m = Move::Down;
@@ -726,7 +697,7 @@ mod test {
             vec!['.', 'v', '.'],
         ];
 
-        let map = Map::new(grid);
+        let map = Map::new(tiles(grid)).unwrap();
         let m = map.calculate_next_step();
 
         let expected_m = Move::Finish;
@@ -742,7 +713,7 @@ mod test {
             vec!['.', '.', '.'],
         ];
 
-        let mut map = Map::new(grid);
+        let mut map = Map::new(tiles(grid)).unwrap();
         let expected_solved = 2;
         assert_eq!(expected_solved, map.solve());
         assert!(!map.guard.looped);
@@ -754,7 +725,7 @@ mod test {
             vec!['#', '.', '<'],
         ];
 
-        let mut map = Map::new(grid);
+        let mut map = Map::new(tiles(grid)).unwrap();
         let expected_solved = 4;
         assert_eq!(expected_solved, map.solve());
         assert!(!map.guard.looped);
@@ -769,13 +740,31 @@ mod test {
             vec!['.', '#', '.'],
         ];
 
-        let mut map = Map::new(grid);
+        let mut map = Map::new(tiles(grid)).unwrap();
         let expected_solved = 1;
         assert_eq!(expected_solved, map.solve());
         assert!(map.guard.looped);
         assert!(map.completed);
     }
 
+    #[test]
+    fn test_map_frames() {
+        let grid = vec![
+            vec!['.', '#', '.'],
+            vec!['.', '^', '#'],
+            vec!['.', '.', '.'],
+        ];
+        let map = Map::new(tiles(grid)).unwrap();
+        let frames: Vec<Frame> = map.frames().collect();
+        // two turns, a step down and the step off the map
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0].cells[(1, 1)], Cell::new('^', Colour::Red));
+        assert_eq!(frames[0].caption, "step 0, 1 positions visited");
+        assert_eq!(frames[3].cells[(1, 2)], Cell::new('v', Colour::Red));
+        assert_eq!(frames[4].cells[(1, 2)], Cell::new('X', Colour::Yellow));
+        assert_eq!(frames[4].caption, "step 4, 2 positions visited");
+    }
+
     #[test]
     fn test_map_check_looped() {
         let grid = vec![
@@ -789,11 +778,11 @@ mod test {
             vec!['.', '#', '.'],
         ];
 
-        let map = Map::new(grid);
+        let map = Map::new(tiles(grid)).unwrap();
         let is_looped = map.check_looped();
         assert!(is_looped);
 
-        let map = Map::new(grid_not);
+        let map = Map::new(tiles(grid_not)).unwrap();
         let is_looped = map.check_looped();
         assert!(!is_looped);
     }
@@ -806,7 +795,7 @@ mod test {
             vec!['.', '#', '.'],
         ];
 
-        let map = Map::new(grid);
+        let map = Map::new(tiles(grid)).unwrap();
         let pos = map.find_loop_obstacle_pos();
         assert_eq!(1, pos);
     }
@@ -826,7 +815,7 @@ mod test {
             vec!['.', '.', '.', '.', '.', '.', '.', '.', '#', '.', '.', '.'],
         ];
 
-        let map = Map::new(grid);
+        let map = Map::new(tiles(grid)).unwrap();
         let pos = map.find_loop_obstacle_pos();
         assert_eq!(6, pos);
     }
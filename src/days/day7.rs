@@ -19,10 +19,15 @@
 // finally we just sum all of the valid equation values
 //
 
-use std::{fs::read, io::BufRead, time::Instant};
+use crate::days::read_input;
+use crate::error::{Error, Result};
+use crate::generate::{self, Rng};
+use crate::params::{Param, Params};
+use crate::parse;
+use crate::solver::{self, Answer, Answers, Example, Solver};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Equation {
+pub struct Equation {
     value: u64,
     numbers: Vec<u64>,
     operators: Vec<Operators>,
@@ -30,14 +35,14 @@ struct Equation {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum Operators {
+pub enum Operators {
     Addition,
     Multiplication,
     Concatination,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Validity {
+pub enum Validity {
     True,
     False,
     Unchecked,
@@ -53,6 +58,19 @@ impl Operators {
     }
 }
 
+impl std::str::FromStr for Operators {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim() {
+            "+" => Ok(Operators::Addition),
+            "*" => Ok(Operators::Multiplication),
+            "||" => Ok(Operators::Concatination),
+            _ => Err(()),
+        }
+    }
+}
+
 impl Equation {
     pub fn new(value: u64, numbers: Vec<u64>, operators: Vec<Operators>) -> Self {
         let valid = Validity::Unchecked;
@@ -80,19 +98,19 @@ impl Equation {
         let mut num_iter = self.numbers.iter();
         num_iter.next();
         for (num, op) in num_iter.zip(self.operators.clone()) {
-            match op {
-                Operators::Addition => {
-                    result += *num;
-                }
-                Operators::Multiplication => {
-                    result *= *num;
-                }
+            // anything that overflows is far past the value so can't be valid
+            let next = match op {
+                Operators::Addition => result.checked_add(*num),
+                Operators::Multiplication => result.checked_mul(*num),
                 Operators::Concatination => {
                     let new_str = result.to_string() + &num.to_string();
-                    let new = new_str.parse::<u64>().unwrap();
-                    result = new;
+                    new_str.parse::<u64>().ok()
                 }
-            }
+            };
+            let Some(next) = next else {
+                return false;
+            };
+            result = next;
         }
         result == self.value
     }
@@ -100,15 +118,21 @@ impl Equation {
 
 fn get_all_equations(value: u64, numbers: Vec<u64>, all_operators: &[Operators]) -> Vec<Equation> {
     let l = numbers.len();
+    // a lone number is an equation without any operators and no numbers at
+    // all can't make anything
+    match l {
+        0 => return vec![],
+        1 => return vec![Equation::new(value, numbers, vec![])],
+        _ => {}
+    }
     let mut operator_combos: Vec<Vec<Operators>> = Vec::new();
     for _ in 0..l - 1 {
         operator_combos = get_operator_combos(operator_combos, all_operators);
     }
-    let equations = operator_combos
+    operator_combos
         .iter()
         .map(|combo| Equation::new(value, numbers.clone(), combo.clone()))
-        .collect();
-    equations
+        .collect()
 }
 
 fn get_operator_combos(
@@ -146,12 +170,13 @@ fn check_equation_set(equation_set: Vec<Equation>) -> bool {
     false
 }
 
-fn get_total_sum_of_valid_equations(equation_sets: Vec<Vec<Equation>>) -> u64 {
+// a few values near the top of a u64 already add up to more than one holds
+fn get_total_sum_of_valid_equations(equation_sets: Vec<Vec<Equation>>) -> u128 {
     equation_sets
         .iter()
         .map(|e| {
             if check_equation_set(e.to_vec()) {
-                e.first().unwrap().value
+                e.first().unwrap().value as u128
             } else {
                 0
             }
@@ -159,40 +184,152 @@ fn get_total_sum_of_valid_equations(equation_sets: Vec<Vec<Equation>>) -> u64 {
         .sum()
 }
 
-pub fn day_seven(path: &str) -> std::io::Result<()> {
-    let now = Instant::now();
-    let content = read(path)?;
-    let all_ops = vec![
-        Operators::Addition,
-        Operators::Multiplication,
-        Operators::Concatination,
+/// the calibration total with + and *, then with the operators part two may
+/// use
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DaySeven>(input)
+}
+
+pub fn day_seven(path: &str) -> Result<()> {
+    let content = read_input(path)?;
+    let answers = solve(&content).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
+    Ok(())
+}
+
+fn sum_valid_with(equations: &[(u64, Vec<u64>)], all_ops: &[Operators]) -> u128 {
+    let equation_sets: Vec<Vec<Equation>> = equations
+        .iter()
+        .map(|(value, numbers)| get_all_equations(*value, numbers.clone(), all_ops))
+        .collect();
+    get_total_sum_of_valid_equations(equation_sets)
+}
+
+/// The equations along with the operators part two may use
+#[derive(Debug)]
+pub struct Calibration {
+    equations: Vec<(u64, Vec<u64>)>,
+    operators: Vec<Operators>,
+}
+
+/// the biggest value a generated equation has, about as big as the puzzle's
+/// own get
+const LARGEST_VALUE: u64 = 999_999_999_999_999;
+
+pub struct DaySeven;
+
+impl Solver for DaySeven {
+    const DAY: u8 = 7;
+    const NAME: &'static str = "day_seven";
+    const PARAMS: &'static [Param] = &[Param {
+        name: "operators",
+        default: "+,*,||",
+        help: "the operators part two may place between numbers",
+    }];
+
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "equations",
+            default: "850",
+            help: "equations, a line each",
+        },
+        Param {
+            name: "numbers",
+            default: "12",
+            help: "the most numbers in an equation, 2-12",
+        },
     ];
-    let equation_sets: Vec<Vec<Equation>> = content
-        .lines()
-        .map(|l| {
-            let binding = l.unwrap();
-            let (value_str, eq_str) = binding.split_once(": ").unwrap();
-            let value: u64 = value_str.parse::<u64>().unwrap();
-            let eq: Vec<u64> = eq_str
-                .trim_end()
-                .split(" ")
-                .map(|s| s.parse().unwrap())
-                .collect();
-            get_all_equations(value, eq, &all_ops)
+
+    const EXAMPLES: &'static [Example] = &[Example::new(
+        include_str!("examples/day07.txt"),
+        "3749",
+        "11387",
+    )];
+
+    type Input = Calibration;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::new().resolve(Self::PARAMS)?)
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        let equations = input
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| parse::key_values(input, l, "an equation like 190: 10 19"))
+            .collect::<Result<_>>()?;
+        let operators: String = params.get("operators")?;
+        let operators = operators
+            .split(',')
+            .map(str::parse)
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|_| Error::InvalidParam {
+                name: "operators".into(),
+                value: operators.clone(),
+                expected: "a list of +, * and || separated by commas",
+            })?;
+        Ok(Calibration {
+            equations,
+            operators,
         })
-        .collect();
-    let total_sum = get_total_sum_of_valid_equations(equation_sets);
-    println!(
-        "the total sum from the data is {} and it was calculated in {}",
-        total_sum,
-        now.elapsed().as_micros()
-    );
-    Ok(())
+    }
+
+    fn part_one(input: &Self::Input) -> Answer {
+        sum_valid_with(
+            &input.equations,
+            &[Operators::Addition, Operators::Multiplication],
+        )
+        .into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        sum_valid_with(&input.equations, &input.operators).into()
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let equations = generate::size(sizes, "equations", 1..=100_000)?;
+        let most = generate::size(sizes, "numbers", 2..=12)? as i64;
+        let mut out = String::new();
+        for _ in 0..equations {
+            let numbers: Vec<u64> = (0..rng.between(2..=most))
+                .map(|_| {
+                    if rng.chance(0.8) {
+                        rng.between(1..=99) as u64
+                    } else {
+                        rng.between(100..=999) as u64
+                    }
+                })
+                .collect();
+            // put random operators between the numbers for a value that can be
+            // made, adding instead where another would go past the largest,
+            // then knock about half of them off so they can't
+            let mut value = numbers[0];
+            for &number in &numbers[1..] {
+                let next = match rng.below(3) {
+                    0 => value.checked_add(number),
+                    1 => value.checked_mul(number),
+                    _ => format!("{value}{number}").parse().ok(),
+                };
+                value = next
+                    .filter(|&next| next <= LARGEST_VALUE)
+                    .unwrap_or(value + number);
+            }
+            if rng.chance(0.5) {
+                value += rng.between(1..=1000) as u64;
+            }
+            let numbers: Vec<String> = numbers.iter().map(u64::to_string).collect();
+            out.push_str(&format!("{value}: {}\n", numbers.join(" ")));
+        }
+        Ok(Some(out))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::check;
+    use crate::error::Location;
 
     #[test]
     fn test_equation_check_valid() {
@@ -217,6 +354,46 @@ mod test {
         assert!(valid_equation.check_valid());
     }
 
+    #[test]
+    fn test_equation_check_valid_overflow() {
+        let operators = vec![Operators::Multiplication, Operators::Concatination];
+        let equation = Equation::new(100, vec![u64::MAX, 2, 1], operators);
+        assert!(!equation.check_valid());
+    }
+
+    #[test]
+    fn test_sum_past_u64() {
+        let input = format!("{0}: {0} 1\n{0}: {0} 1", u64::MAX);
+        let answers = solver::solve::<DaySeven>(&input).unwrap();
+        let sum = (u64::MAX as u128 * 2).to_string();
+        assert_eq!(answers.part_one, Answer::Text(sum.clone()));
+        assert_eq!(answers.part_two, Answer::Text(sum));
+    }
+
+    #[test]
+    fn test_generate_values() {
+        let sizes = Params::new().resolve(DaySeven::SIZES).unwrap();
+        for seed in 1..=8 {
+            let input = DaySeven::generate(&mut Rng::new(seed), &sizes)
+                .unwrap()
+                .unwrap();
+            let calibration = DaySeven::parse(&input).unwrap();
+            // the adding in place of what went too far and the knocking off
+            // take it a little past the largest at most
+            let most = LARGEST_VALUE + 11 * 999 + 1000;
+            assert!(calibration.equations.iter().all(|(v, _)| *v <= most));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = DaySeven::parse("190: 10 19\n3267 81 40 27").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 1)));
+
+        let error = DaySeven::parse("190: 10 19\n3267: 81 4o 27").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 10)));
+    }
+
     #[test]
     fn test_get_all_equations() {
         let all_ops = vec![Operators::Addition, Operators::Multiplication];
@@ -249,6 +426,15 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_get_all_equations_short() {
+        let ops = [Operators::Addition, Operators::Multiplication];
+        assert!(get_all_equations(11, vec![], &ops).is_empty());
+        let lone = get_all_equations(11, vec![11], &ops);
+        assert_eq!(lone, vec![Equation::new(11, vec![11], vec![])]);
+        assert!(check_equation_set(lone));
+    }
+
     #[test]
     fn test_check_equation_set_zero() {
         let all_ops = vec![Operators::Addition, Operators::Multiplication];
@@ -326,4 +512,94 @@ mod test {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_operators_param() {
+        let input = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n161011: 16 10 13\n192: 17 8 14\n21037: 9 7 18 13\n292: 11 6 16 20";
+        let mut params = Params::new();
+        params.set("operators", "+,*");
+        let answers = solver::solve_with::<DaySeven>(input, &params).unwrap();
+        assert_eq!(answers.part_two, Answer::Number(3749));
+        params.set("operators", "+,-");
+        assert!(solver::solve_with::<DaySeven>(input, &params).is_err());
+    }
+
+    /// every value the numbers make with every way of putting the operators
+    /// between them, worked out left to right
+    fn naive_valid(value: u64, numbers: &[u64], operators: &[Operators]) -> bool {
+        let Some((&first, rest)) = numbers.split_first() else {
+            return false;
+        };
+        let mut made = vec![first];
+        for &number in rest {
+            made = made
+                .iter()
+                .flat_map(|&m| {
+                    operators.iter().map(move |op| match op {
+                        Operators::Addition => m + number,
+                        Operators::Multiplication => m * number,
+                        Operators::Concatination => format!("{m}{number}").parse().unwrap(),
+                    })
+                })
+                .collect();
+        }
+        made.contains(&value)
+    }
+
+    /// equations small enough that nothing overflows, about half of them
+    /// made to be valid
+    fn equation(rng: &mut Rng, size: usize) -> (u64, Vec<u64>) {
+        let numbers: Vec<u64> = (0..rng.below(size.min(6) as u64 + 1))
+            .map(|_| rng.below(100))
+            .collect();
+        let value = match numbers.split_first() {
+            Some((&first, rest)) if rng.chance(0.5) => {
+                rest.iter().fold(first, |v, &n| match rng.below(3) {
+                    0 => v + n,
+                    1 => v * n,
+                    _ => format!("{v}{n}").parse().unwrap(),
+                })
+            }
+            _ => rng.below(1000),
+        };
+        (value, numbers)
+    }
+
+    fn shrink_equation((value, numbers): &(u64, Vec<u64>)) -> Vec<(u64, Vec<u64>)> {
+        let smaller_value = check::shrink_number(*value, 0)
+            .into_iter()
+            .map(|v| (v, numbers.clone()));
+        let smaller_numbers = check::shrink_vec(numbers, |&n| check::shrink_number(n, 0))
+            .into_iter()
+            .map(|n| (*value, n));
+        smaller_value.chain(smaller_numbers).collect()
+    }
+
+    #[test]
+    fn test_sum_valid_against_naive() {
+        use Operators::*;
+        for operators in [
+            &[Addition, Multiplication][..],
+            &[Addition, Multiplication, Concatination],
+            &[Concatination],
+        ] {
+            check::check(
+                "summing the valid equations",
+                equation,
+                shrink_equation,
+                |(value, numbers)| {
+                    let expected = if naive_valid(*value, numbers, operators) {
+                        *value as u128
+                    } else {
+                        0
+                    };
+                    check::equal(
+                        &format!("the sum with {operators:?}"),
+                        sum_valid_with(&[(*value, numbers.clone())], operators),
+                        expected,
+                    )
+                },
+            );
+        }
+    }
 }
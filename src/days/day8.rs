@@ -6,26 +6,20 @@
 // direction. They exist as long as they are on the map
 // WE just need to calculate how many exist on the map
 
-use std::{collections::HashMap, fs::read, io::BufRead, time::Instant};
-
-use itertools::Itertools;
-
-#[derive(Debug, PartialEq)]
-struct Map {
-    grid: HashMap<Position, Tiles>,
-    size: GridSize,
-}
-
-#[derive(Debug, PartialEq)]
-struct GridSize {
-    width: usize,
-    height: usize,
-}
-
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-struct Position {
-    x: usize,
-    y: usize,
+use std::collections::HashMap;
+
+use crate::days::read_input;
+use crate::error::Result;
+use crate::generate::{self, Rng};
+use crate::geom::Position;
+use crate::grid::{Grid, Point};
+use crate::params::{Param, Params};
+use crate::solver::{self, Answer, Answers, Example, Solver};
+use crate::visual::{Cell, Colour, Frame, Frames};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Map {
+    grid: Grid<Tiles>,
 }
 
 #[derive(PartialEq, Debug, Eq, Hash, Clone, Copy)]
@@ -47,171 +41,200 @@ impl Tiles {
 }
 
 impl Map {
-    fn new(grid: Vec<Vec<char>>) -> Self {
-        let mut hmap: HashMap<Position, Tiles> = HashMap::new();
-        let mut height = 0;
-        let mut width = 0;
-        for (row, v) in grid.iter().enumerate() {
-            for (col, c) in v.iter().enumerate() {
-                let pos = Position { x: col, y: row };
-                let tile = Tiles::from_char(*c);
-                hmap.insert(pos, tile);
-                width = col;
-            }
-            height = row;
-        }
+    fn new(grid: Grid<char>) -> Self {
         Map {
-            grid: hmap,
-            size: GridSize {
-                width: width + 1,
-                height: height + 1,
-            },
+            grid: grid.map(|c| Tiles::from_char(*c)),
         }
     }
 
     fn count_antinodes(&self) -> usize {
         self.grid
-            .values()
+            .cells()
             .filter(|t| matches!(t, Tiles::Overlapping(_) | Tiles::Antinode))
             .count()
     }
 
-    fn find_antinodes(&mut self) {
+    /// the map with its antinodes so far, antennas that are antinodes too
+    /// standing out from the rest
+    fn frame(&self, caption: &str) -> Frame {
+        let cells = self.grid.map(|tile| match tile {
+            Tiles::Empty => Cell::new('.', Colour::Grey),
+            Tiles::Antinode => Cell::new('#', Colour::Magenta),
+            Tiles::Antenna(c) => Cell::new(*c, Colour::Cyan),
+            Tiles::Overlapping(c) => Cell::new(*c, Colour::Yellow),
+        });
+        let caption = format!("{caption}, {} antinodes", self.count_antinodes());
+        Frame::new(cells, caption)
+    }
+
+    fn antenna_groups(&self) -> HashMap<Tiles, Vec<Position>> {
         // here we need to essentially group ANs into same types
-        // pairwise calculate the antinode positions
-        let cloned_grid = self.grid.clone();
         let mut antennas_grouped: HashMap<Tiles, Vec<Position>> = HashMap::new();
-        for (key, chunk) in &cloned_grid.into_iter().chunk_by(|elt| {
-            let (_, tile) = elt;
-            *tile
-        }) {
-            if matches!(key, Tiles::Antenna(_)) {
-                match antennas_grouped.get_mut(&key) {
-                    None => {
-                        antennas_grouped.insert(
-                            key,
-                            chunk
-                                .map(|c| {
-                                    let (pos, _) = c;
-                                    pos
-                                })
-                                .collect(),
-                        );
-                    }
-                    Some(a) => {
-                        for (pos, _) in chunk {
-                            a.push(pos);
-                        }
-                    }
+        for (pos, tile) in self.grid.iter() {
+            if matches!(tile, Tiles::Antenna(_)) {
+                antennas_grouped
+                    .entry(*tile)
+                    .or_default()
+                    .push(Position::from(pos));
+            }
+        }
+        antennas_grouped
+    }
+
+    fn mark_antinodes(&mut self, antinode_positions: &[Point]) {
+        for pos in antinode_positions.iter() {
+            let updated_tile = match self.grid[*pos] {
+                Tiles::Empty => Tiles::Antinode,
+                Tiles::Antinode => Tiles::Antinode,
+                Tiles::Overlapping(t) => Tiles::Overlapping(t),
+                Tiles::Antenna(t) => Tiles::Overlapping(t),
+            };
+            self.grid[*pos] = updated_tile;
+        }
+    }
+
+    /// the antinodes without resonant harmonics, just the one either side of each
+    /// pair of antennas at the same distance as the pair are from each other
+    fn find_direct_antinodes(&mut self) {
+        for (_, positions) in self.antenna_groups() {
+            for (i, a) in positions.iter().enumerate() {
+                for other in &positions[i + 1..] {
+                    let delta = *a - *other;
+
+                    let antinode_positions: Vec<Point> = [*a + delta, *other - delta]
+                        .into_iter()
+                        .filter_map(|pos| self.grid.point(pos))
+                        .collect();
+                    self.mark_antinodes(&antinode_positions);
                 }
             }
         }
-        for (_, positions) in antennas_grouped.into_iter().filter(|a| {
-            let (t, _) = a;
-            matches!(t, Tiles::Antenna(_))
-        }) {
-            let mut rest = positions.clone();
-            for a in positions {
-                rest.remove(0);
-                for other in &rest {
+    }
+
+    fn find_antinodes(&mut self) {
+        // pairwise calculate the antinode positions
+        for (_, positions) in self.antenna_groups() {
+            for (i, pos_a) in positions.iter().enumerate() {
+                for pos_other in &positions[i + 1..] {
                     // for each pair of antennas a, and other
-                    // we want to calculate the two antinode positions
-                    // to do that we need to calculate the delta x, delta y
-                    // just check theyre in bounds
-                    // Figure out why i get 298 and they get 299
-
-                    let (pos_a, pos_other) = (a, other);
-
-                    let delta_x = pos_a.x as i32 - pos_other.x as i32;
-                    let delta_y = pos_a.y as i32 - pos_other.y as i32;
-
-                    // i now have a delta vector which points from  other to a
-                    // so now i can minus that from other and add two to other
-                    //
-                    let mut antinode_positions: Vec<Position> = vec![];
-                    let mut oob_check = false;
-                    let mut i = 0;
-
-                    while !oob_check {
-                        let (x, y) = (
-                            pos_other.x as i32 + (delta_x * i),
-                            pos_other.y as i32 + (delta_y * i),
-                        );
-                        if (x < self.size.width.try_into().unwrap())
-                            && (x >= 0)
-                            && (y < self.size.height.try_into().unwrap())
-                            && (y >= 0)
-                        {
-                            antinode_positions.push(Position {
-                                x: x as usize,
-                                y: y as usize,
-                            });
-                            i += 1;
-                        } else {
-                            oob_check = true;
-                        }
-                    }
-                    oob_check = false;
-                    i = -1;
-                    while !oob_check {
-                        let (x, y) = (
-                            pos_other.x as i32 + (delta_x * i),
-                            pos_other.y as i32 + (delta_y * i),
-                        );
-                        if (x < self.size.width.try_into().unwrap())
-                            && (x >= 0)
-                            && (y < self.size.height.try_into().unwrap())
-                            && (y >= 0)
-                        {
-                            antinode_positions.push(Position {
-                                x: x as usize,
-                                y: y as usize,
-                            });
-                            i -= 1;
-                        } else {
-                            oob_check = true;
-                        }
-                    }
-
-                    for pos in antinode_positions.iter() {
-                        let updated_tile = match self.grid.get(pos) {
-                            Some(Tiles::Empty) => Tiles::Antinode,
-                            Some(Tiles::Antinode) => Tiles::Antinode,
-                            Some(Tiles::Overlapping(t)) => Tiles::Overlapping(*t),
-                            Some(Tiles::Antenna(t)) => Tiles::Overlapping(*t),
-                            None => panic!("Unexpected position"),
-                        };
-                        self.grid.insert(*pos, updated_tile);
-                    }
+                    // we want to calculate the delta x, delta y
+                    // which is a vector pointing from other to a
+                    // so every multiple of it from other that is still on
+                    // the map is an antinode
+                    let delta = *pos_a - *pos_other;
+                    let start = self
+                        .grid
+                        .point(*pos_other)
+                        .expect("antennas are on the map");
+
+                    let mut antinode_positions: Vec<Point> =
+                        self.grid.ray(start, delta).map(|(pos, _)| pos).collect();
+                    antinode_positions
+                        .extend(self.grid.ray(start, -delta).skip(1).map(|(pos, _)| pos));
+
+                    self.mark_antinodes(&antinode_positions);
                 }
             }
         }
     }
 }
 
-pub fn day_eight(path: &str) -> std::io::Result<()> {
-    let now = Instant::now();
-    let content = read(path)?;
-    let char_grid: Vec<Vec<char>> = content
-        .lines()
-        .map(|l| {
-            let binding = l.unwrap();
-            let chars: Vec<char> = binding.chars().collect();
-            chars
-        })
-        .collect();
-    let mut map = Map::new(char_grid);
-
-    map.find_antinodes();
-    let answer = map.count_antinodes();
-    println!(
-        "the total unique antinodes are {} and was calculated in {} us",
-        answer,
-        now.elapsed().as_micros()
-    );
+/// the antinodes on the map, then the antinodes with resonant harmonics
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayEight>(input)
+}
+
+pub fn day_eight(path: &str) -> Result<()> {
+    let content = read_input(path)?;
+    let answers = solve(&content).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
     Ok(())
 }
 
+pub struct DayEight;
+
+impl Solver for DayEight {
+    const DAY: u8 = 8;
+    const NAME: &'static str = "day_eight";
+
+    const SIZES: &'static [Param] = &[
+        Param {
+            name: "width",
+            default: "50",
+            help: "tiles across the map",
+        },
+        Param {
+            name: "height",
+            default: "50",
+            help: "tiles down the map",
+        },
+        Param {
+            name: "frequencies",
+            default: "40",
+            help: "different frequencies, up to 62",
+        },
+        Param {
+            name: "antennas",
+            default: "4",
+            help: "antennas of each frequency",
+        },
+    ];
+
+    const EXAMPLES: &'static [Example] =
+        &[Example::new(include_str!("examples/day08.txt"), "14", "34")];
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Map::new(input.parse()?))
+    }
+
+    fn part_one(input: &Self::Input) -> Answer {
+        let mut map = input.clone();
+        map.find_direct_antinodes();
+        map.count_antinodes().into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        let mut map = input.clone();
+        map.find_antinodes();
+        map.count_antinodes().into()
+    }
+
+    fn visualize(input: &Self::Input) -> Option<Frames> {
+        let mut direct = input.clone();
+        direct.find_direct_antinodes();
+        let mut harmonics = input.clone();
+        harmonics.find_antinodes();
+        Some(Box::new(
+            [
+                input.frame("antennas"),
+                direct.frame("direct"),
+                harmonics.frame("with harmonics"),
+            ]
+            .into_iter(),
+        ))
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let width = generate::size(sizes, "width", 1..=1000)?;
+        let height = generate::size(sizes, "height", 1..=1000)?;
+        let frequencies = generate::size(sizes, "frequencies", 1..=62)? as usize;
+        let antennas =
+            generate::size(sizes, "antennas", 1..=width * height / frequencies as u64)? as usize;
+        let labels: Vec<char> = ('0'..='9').chain('a'..='z').chain('A'..='Z').collect();
+        // every antenna on a tile of its own
+        let mut map = Grid::new(width as usize, height as usize, '.');
+        let mut tiles: Vec<Point> = map.points().collect();
+        rng.shuffle(&mut tiles);
+        for (i, &tile) in tiles[..frequencies * antennas].iter().enumerate() {
+            map[tile] = labels[i / antennas];
+        }
+        Ok(Some(map.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -230,7 +253,7 @@ mod test {
             vec!['.', '.', '.'],
         ];
 
-        let mut map = Map::new(char_grid);
+        let mut map = Map::new(Grid::from_rows(char_grid).unwrap());
         map.find_antinodes();
         let actual = map.count_antinodes();
         let expected = 4;
@@ -247,7 +270,7 @@ mod test {
             vec!['.', '.', 'e'],
         ];
 
-        let mut map = Map::new(char_grid);
+        let mut map = Map::new(Grid::from_rows(char_grid).unwrap());
         map.find_antinodes();
         let actual = map.count_antinodes();
         let expected = 6;
@@ -264,7 +287,7 @@ mod test {
             vec!['.', '.', '.'],
         ];
 
-        let mut map = Map::new(char_grid);
+        let mut map = Map::new(Grid::from_rows(char_grid).unwrap());
         map.find_antinodes();
         let actual = map.count_antinodes();
         let expected = 7;
@@ -272,6 +295,39 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_count_direct_antinodes() {
+        let char_grid = vec![
+            vec!['.', '.', '.', '.', '.'],
+            vec!['.', 'a', 'a', '.', '.'],
+            vec!['.', '.', '.', 'b', '.'],
+            vec!['.', '.', '.', '.', 'b'],
+        ];
+
+        let mut map = Map::new(Grid::from_rows(char_grid).unwrap());
+        map.find_direct_antinodes();
+        let actual = map.count_antinodes();
+        let expected = 3;
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_frames() {
+        let char_grid = vec![vec!['.', 'a', 'a', '.'], vec!['.', '.', '.', '.']];
+        let map = Map::new(Grid::from_rows(char_grid).unwrap());
+        let frames: Vec<Frame> = DayEight::visualize(&map).unwrap().collect();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].caption, "antennas, 0 antinodes");
+        assert_eq!(frames[0].cells[(1, 0)], Cell::new('a', Colour::Cyan));
+        assert_eq!(frames[1].caption, "direct, 2 antinodes");
+        assert_eq!(frames[1].cells[(0, 0)], Cell::new('#', Colour::Magenta));
+        // every antenna in line is an antinode once there are harmonics
+        assert_eq!(frames[2].caption, "with harmonics, 4 antinodes");
+        assert_eq!(frames[2].cells[(2, 0)], Cell::new('a', Colour::Yellow));
+        assert_eq!(frames[2].cells[(0, 1)], Cell::new('.', Colour::Grey));
+    }
+
     #[test]
     fn test_map_new() {
         let char_grid = vec![
@@ -279,25 +335,20 @@ mod test {
             vec!['c', 'd', 'e'],
             vec!['.', '.', '.'],
         ];
-        let mut expected_map = HashMap::new();
-        expected_map.insert(Position { x: 0, y: 0 }, Tiles::Empty);
-        expected_map.insert(Position { x: 1, y: 0 }, Tiles::Empty);
-        expected_map.insert(Position { x: 2, y: 0 }, Tiles::Empty);
-        expected_map.insert(Position { x: 0, y: 1 }, Tiles::Antenna('c'));
-        expected_map.insert(Position { x: 1, y: 1 }, Tiles::Antenna('d'));
-        expected_map.insert(Position { x: 2, y: 1 }, Tiles::Antenna('e'));
-        expected_map.insert(Position { x: 0, y: 2 }, Tiles::Empty);
-        expected_map.insert(Position { x: 1, y: 2 }, Tiles::Empty);
-        expected_map.insert(Position { x: 2, y: 2 }, Tiles::Empty);
         let expected = Map {
-            grid: expected_map,
-            size: GridSize {
-                height: 3,
-                width: 3,
-            },
+            grid: Grid::from_rows(vec![
+                vec![Tiles::Empty, Tiles::Empty, Tiles::Empty],
+                vec![
+                    Tiles::Antenna('c'),
+                    Tiles::Antenna('d'),
+                    Tiles::Antenna('e'),
+                ],
+                vec![Tiles::Empty, Tiles::Empty, Tiles::Empty],
+            ])
+            .unwrap(),
         };
 
-        let actual = Map::new(char_grid);
+        let actual = Map::new(Grid::from_rows(char_grid).unwrap());
 
         assert_eq!(expected, actual);
     }
@@ -1,4 +1,8 @@
-use std::{fs::read, io::BufRead, iter::repeat, time::Instant};
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+use crate::generate::{self, Rng};
+use crate::params::{Param, Params};
+use crate::solver::{self, Answer, Answers, Example, Solver};
 
 // so we are given some kind of compressed data
 // that represent larger data
@@ -97,7 +101,8 @@ impl Data {
                 pos_counter += self.compressed[j + 1] as usize;
             }
         }
-        self.empty_blocks = empty_blocks;
+        // files can be empty, leaving the gaps either side of them as one
+        self.empty_blocks = merge_adjacent_blocks(empty_blocks);
         self.value_blocks = value_blocks;
     }
 
@@ -141,8 +146,9 @@ impl Data {
                 Bits::Value(x) => x,
                 _ => panic!(),
             };
-            let add = (v.start_pos as u64 - 1) * (value as u64) * (v.length as u64)
-                + (v.length as u64 + 1) * (v.length as u64) * (value as u64) / 2;
+            // sum of value * pos over start_pos..start_pos + length
+            let (start, length) = (v.start_pos as u64, v.length as u64);
+            let add = (value as u64) * (start * length + length * length.saturating_sub(1) / 2);
             sum += add
         }
         sum
@@ -151,28 +157,18 @@ impl Data {
     pub fn reduce_raw(&mut self) {
         // this will take the raw value and reduce it so that there are no
         // empty gaps between values
-        // basic plan is to iterate through from left to right until non_empty_count
-        // and swap the right most non_empty point with the left most empty
-        let mut left = 0;
-        let mut right = self.raw.len() - 1;
+        // basic plan is to walk in from both ends and swap the left most empty
+        // point with the right most non empty one until they meet
         let mut reduced = self.raw.clone();
-        while left < self.non_empty_count {
-            let mut v = &self.raw[left];
-            while v != &Bits::Empty {
-                left += 1;
-                v = &reduced[left];
-            }
-
-            let mut w = &reduced[right];
-            while w == &Bits::Empty {
-                right -= 1;
-                w = &reduced[right];
-            }
-            if left < self.non_empty_count {
-                // swap left and right
-                reduced.swap(left, right);
+        let mut left = 0;
+        let mut right = reduced.len();
+        while left < right {
+            if reduced[left] != Bits::Empty {
                 left += 1;
+            } else if reduced[right - 1] == Bits::Empty {
                 right -= 1;
+            } else {
+                reduced.swap(left, right - 1);
             }
         }
         self.raw_reduced = reduced;
@@ -220,42 +216,91 @@ fn merge_adjacent_blocks(blocks: Vec<Block>) -> Vec<Block> {
     merged
 }
 
-pub fn day_nine(path: &str) -> std::io::Result<()> {
-    let now = Instant::now();
-    let content = std::fs::read_to_string(path).unwrap();
-    let digits: Vec<u16> = content
-        .chars()
-        .filter_map(|s| s.to_digit(10))
-        .map(|d| d as u16)
-        .collect();
-
-    let mut x = Data::new(digits);
-
-    x.expand();
-    x.reduce_raw();
-    let checksum = x.get_checksum();
-
-    println!(
-        "the checksum is {} and was calculated in {} us",
-        checksum,
-        now.elapsed().as_micros()
-    );
-
-    x.get_blocks();
-    x.move_blocks();
-    let checksum = x.block_checksum();
-
-    println!(
-        "the checksum for p2 is {} and was calculated in {} us",
-        checksum,
-        now.elapsed().as_micros()
-    );
+/// the checksum after compacting a block at a time, then a whole file at a
+/// time
+pub fn solve(input: &str) -> Result<Answers> {
+    solver::solve::<DayNine>(input)
+}
+
+pub fn day_nine(path: &str) -> Result<()> {
+    let content = read_input(path)?;
+    let answers = solve(&content).map_err(|e| e.in_file(path))?;
+    println!("{}", answers.part_one);
+    println!("{}", answers.part_two);
     Ok(())
 }
 
+pub struct DayNine;
+
+impl Solver for DayNine {
+    const DAY: u8 = 9;
+    const NAME: &'static str = "day_nine";
+
+    const SIZES: &'static [Param] = &[Param {
+        name: "files",
+        default: "10000",
+        help: "files on the disk, up to 65536",
+    }];
+
+    const EXAMPLES: &'static [Example] = &[Example::new(
+        include_str!("examples/day09.txt"),
+        "1928",
+        "2858",
+    )];
+
+    type Input = Vec<u16>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let disk_map = input.trim_end();
+        if disk_map.is_empty() {
+            return Err(Error::MissingSection {
+                location: Location::new(1, 1),
+                section: "disk map",
+            });
+        }
+        disk_map
+            .char_indices()
+            .map(|(i, c)| {
+                c.to_digit(10)
+                    .map(|d| d as u16)
+                    .ok_or_else(|| Error::malformed(Location::of(input, &disk_map[i..]), "a digit"))
+            })
+            .collect()
+    }
+
+    fn part_one(input: &Self::Input) -> Answer {
+        let mut data = Data::new(input.clone());
+        data.expand();
+        data.reduce_raw();
+        data.get_checksum().into()
+    }
+
+    fn part_two(input: &Self::Input) -> Answer {
+        let mut data = Data::new(input.clone());
+        data.get_blocks();
+        data.move_blocks();
+        data.block_checksum().into()
+    }
+
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let files = generate::size(sizes, "files", 1..=65_536)?;
+        let mut out = String::with_capacity(2 * files as usize);
+        for file in 0..files {
+            // the free space between files can be none, but files never are
+            if file > 0 {
+                out.push(char::from(b'0' + rng.below(10) as u8));
+            }
+            out.push(char::from(b'1' + rng.below(9) as u8));
+        }
+        out.push('\n');
+        Ok(Some(out))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::check;
 
     #[test]
     fn test_data_expand() {
@@ -309,6 +354,15 @@ mod test {
         assert_eq!(data.raw_reduced, expected);
     }
 
+    #[test]
+    fn test_data_reduce_raw_no_gaps() {
+        let mut data = Data::new(vec![2]);
+        data.expand();
+        data.reduce_raw();
+        assert_eq!(data.raw_reduced, [Bits::Value(0), Bits::Value(0)]);
+        assert_eq!(data.get_checksum(), 0);
+    }
+
     #[test]
     fn test_data_get_checksum() {
         let input = vec![1, 2, 3, 4, 5];
@@ -319,4 +373,126 @@ mod test {
         let expected = 60;
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_data_block_checksum() {
+        let mut data = Data::new(vec![
+            2, 3, 3, 3, 1, 3, 3, 1, 2, 1, 4, 1, 4, 1, 3, 1, 4, 0, 2,
+        ]);
+        data.get_blocks();
+        data.move_blocks();
+        assert_eq!(data.block_checksum(), 2858);
+    }
+
+    #[test]
+    fn test_data_block_checksum_empty_file() {
+        // the gaps either side of the empty file 1 hold all of file 2
+        let mut data = Data::new(vec![0, 1, 0, 1, 2]);
+        data.get_blocks();
+        data.move_blocks();
+        assert_eq!(data.block_checksum(), 2);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = DayNine::parse("12a4\n").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(1, 3)));
+        let error = DayNine::parse("\n").unwrap_err();
+        assert!(matches!(error, Error::MissingSection { .. }));
+    }
+
+    /// the disk block by block, the file each one is part of
+    fn naive_disk(disk_map: &[u16]) -> Vec<Option<usize>> {
+        disk_map
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &n)| std::iter::repeat_n((i % 2 == 0).then_some(i / 2), n as usize))
+            .collect()
+    }
+
+    fn naive_checksum(disk: &[Option<usize>]) -> u64 {
+        disk.iter()
+            .enumerate()
+            .map(|(i, id)| id.map_or(0, |id| (i * id) as u64))
+            .sum()
+    }
+
+    /// move the last block into the first gap until there aren't any
+    fn naive_compact(disk_map: &[u16]) -> u64 {
+        let mut disk = naive_disk(disk_map);
+        while let Some(gap) = disk.iter().position(Option::is_none) {
+            if let Some(last) = disk.pop().flatten() {
+                disk[gap] = Some(last);
+            }
+        }
+        naive_checksum(&disk)
+    }
+
+    /// move each whole file, the highest id first, to the first gap on its
+    /// left that it fits in
+    fn naive_defragment(disk_map: &[u16]) -> u64 {
+        let mut disk = naive_disk(disk_map);
+        for id in (0..disk_map.len().div_ceil(2)).rev() {
+            let Some(start) = disk.iter().position(|&b| b == Some(id)) else {
+                continue;
+            };
+            let length = disk.iter().filter(|&&b| b == Some(id)).count();
+            let gap = (0..start).find(|&s| disk[s..s + length].iter().all(Option::is_none));
+            if let Some(gap) = gap {
+                disk[start..start + length].fill(None);
+                disk[gap..gap + length].fill(Some(id));
+            }
+        }
+        naive_checksum(&disk)
+    }
+
+    fn disk_map(rng: &mut Rng, size: usize) -> Vec<u16> {
+        (0..=rng.below(size as u64))
+            .map(|_| rng.below(10) as u16)
+            .collect()
+    }
+
+    fn shrink_disk_map(disk_map: &[u16]) -> Vec<Vec<u16>> {
+        let mut smaller = check::shrink_vec(disk_map, |&n| {
+            check::shrink_number(n as u64, 0)
+                .into_iter()
+                .map(|n| n as u16)
+                .collect()
+        });
+        // the parser never gives an empty map
+        smaller.retain(|m| !m.is_empty());
+        smaller
+    }
+
+    #[test]
+    fn test_compact_against_naive() {
+        check::check(
+            "compacting",
+            disk_map,
+            |m| shrink_disk_map(m),
+            |disk_map| {
+                check::equal(
+                    "the checksum",
+                    DayNine::part_one(disk_map),
+                    naive_compact(disk_map).into(),
+                )
+            },
+        );
+    }
+
+    #[test]
+    fn test_defragment_against_naive() {
+        check::check(
+            "defragmenting",
+            disk_map,
+            |m| shrink_disk_map(m),
+            |disk_map| {
+                check::equal(
+                    "the checksum",
+                    DayNine::part_two(disk_map),
+                    naive_defragment(disk_map).into(),
+                )
+            },
+        );
+    }
 }
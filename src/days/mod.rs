@@ -12,3 +12,168 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::params::{Param, Params};
+use crate::solver::{self, Answer, Answers, Example, Part, Solver, Timings};
+use crate::visual::Frames;
+
+/// A registered day. This erases the `Solver::Input` type so that every day can
+/// sit in the same list and be called generically
+#[derive(Debug)]
+pub struct Day {
+    pub number: u8,
+    pub name: &'static str,
+    /// the parameters the day can be tuned with
+    pub params: &'static [Param],
+    /// how big a generated input can be made
+    pub sizes: &'static [Param],
+    /// the examples from the puzzle text
+    pub examples: &'static [Example],
+    solve: fn(&str, &Params) -> Result<Answers>,
+    solve_timed: fn(&str, &Params) -> Result<(Answers, Timings)>,
+    solve_part: fn(&str, Part, &Params) -> Result<(Answer, Timings)>,
+    visualize: fn(&str, &Params) -> Result<Option<Frames>>,
+    generate: fn(u64, &Params) -> Result<Option<String>>,
+}
+
+impl Day {
+    pub const fn new<S: Solver>() -> Self {
+        Day {
+            number: S::DAY,
+            name: S::NAME,
+            params: S::PARAMS,
+            sizes: S::SIZES,
+            examples: S::EXAMPLES,
+            solve: solver::solve_with::<S>,
+            solve_timed: solver::solve_timed::<S>,
+            solve_part: solver::solve_part::<S>,
+            visualize: solver::visualize_with::<S>,
+            generate: solver::generate_with::<S>,
+        }
+    }
+
+    /// parse the input and return the answers to both parts
+    pub fn solve(&self, input: &str) -> Result<Answers> {
+        (self.solve)(input, &Params::new())
+    }
+
+    /// `solve` with some of the day's parameters overridden
+    pub fn solve_with(&self, input: &str, params: &Params) -> Result<Answers> {
+        (self.solve)(input, params)
+    }
+
+    /// `solve_with` along with the time spent parsing and in each part
+    pub fn solve_timed(&self, input: &str, params: &Params) -> Result<(Answers, Timings)> {
+        (self.solve_timed)(input, params)
+    }
+
+    /// parse the input and answer only `part`
+    pub fn solve_part(
+        &self,
+        input: &str,
+        part: Part,
+        params: &Params,
+    ) -> Result<(Answer, Timings)> {
+        (self.solve_part)(input, part, params)
+    }
+
+    /// parse the input and play it out as frames, `None` for a day that
+    /// has nothing to show
+    pub fn visualize(&self, input: &str, params: &Params) -> Result<Option<Frames>> {
+        (self.visualize)(input, params)
+    }
+
+    /// a random input made from `seed`, the same every time for the same seed
+    /// and sizes. `None` for a day without a generator
+    pub fn generate(&self, seed: u64, sizes: &Params) -> Result<Option<String>> {
+        (self.generate)(seed, sizes)
+    }
+}
+
+/// Every day in the calendar, in order. A new day only needs adding here, which
+/// `aoc24 new-day` does along with making its module
+pub const DAYS: &[Day] = &[
+    Day::new::<day1::DayOne>(),
+    Day::new::<day2::DayTwo>(),
+    Day::new::<day3::DayThree>(),
+    Day::new::<day4::DayFour>(),
+    Day::new::<day5::DayFive>(),
+    Day::new::<day6::DaySix>(),
+    Day::new::<day7::DaySeven>(),
+    Day::new::<day8::DayEight>(),
+    Day::new::<day9::DayNine>(),
+    Day::new::<day10::DayTen>(),
+    Day::new::<day11::DayEleven>(),
+    Day::new::<day12::DayTwelve>(),
+    Day::new::<day13::DayThirteen>(),
+    Day::new::<day14::DayFourteen>(),
+];
+
+/// read a puzzle input from disk, remembering the path for any error
+pub fn read_input(path: impl AsRef<Path>) -> Result<String> {
+    std::fs::read_to_string(&path).map_err(|e| Error::from(e).in_file(path))
+}
+
+/// look up a registered day by its name i.e. `day_six`
+pub fn find(name: &str) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_ordered_and_unique() {
+        // days can be skipped, but never out of order or twice
+        for pair in DAYS.windows(2) {
+            assert!(pair[0].number < pair[1].number);
+        }
+        assert!(DAYS.iter().all(|d| (1..=25).contains(&d.number)));
+    }
+
+    #[test]
+    fn test_generators() {
+        for day in DAYS {
+            // small enough to solve quickly
+            let mut sizes = Params::new();
+            for size in day.sizes {
+                let default: u64 = size.default.parse().unwrap();
+                sizes.set(size.name, default.min(8).to_string());
+            }
+            if day.number == 14 {
+                // the room can't be as tall as it's wide
+                sizes.set("height", "7");
+            }
+            // a day fresh from the template has no generator yet
+            let Some(input) = day.generate(1, &sizes).unwrap() else {
+                continue;
+            };
+            assert_eq!(day.generate(1, &sizes).unwrap().unwrap(), input);
+            assert_ne!(day.generate(2, &sizes).unwrap().unwrap(), input);
+            if let Err(e) = day.solve(&input) {
+                panic!("day {} can't solve its own input: {e}", day.number);
+            }
+
+            // and once the size the puzzle's are, only part one so it's quick
+            let Some(input) = day.generate(1, &Params::new()).unwrap() else {
+                continue;
+            };
+            if let Err(e) = day.solve_part(&input, Part::One, &Params::new()) {
+                panic!(
+                    "day {} can't solve its own full size input: {e}",
+                    day.number
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find("day_six").map(|d| d.number), Some(6));
+        assert!(find("day_sixty").is_none());
+    }
+}
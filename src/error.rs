@@ -0,0 +1,316 @@
+use std::{fmt, io, path::Path, path::PathBuf};
+
+/// Where in the input something went wrong. Lines and columns count from 1 like
+/// an editor does, and the file is only known once the error reaches the caller
+/// that read it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: Option<PathBuf>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self {
+            file: None,
+            line,
+            column,
+        }
+    }
+
+    /// the location of `fragment` within `input`. The fragment has to be a slice
+    /// of the input (as handed out by `lines`, `split` and friends), anything else
+    /// is reported at the very start
+    pub fn of(input: &str, fragment: &str) -> Self {
+        let start = input.as_ptr() as usize;
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|o| o + fragment.len() <= input.len())
+            .unwrap_or(0);
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = input[line_start..offset].chars().count() + 1;
+        Self::new(line, column)
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}", file.display(), self.line, self.column),
+            None => write!(f, "line {}, column {}", self.line, self.column),
+        }
+    }
+}
+
+/// The crate wide error. Anything that comes out of parsing an input carries the
+/// location of the offending text so it can be pointed at
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// a line that doesn't have the shape the puzzle describes
+    MalformedLine {
+        location: Location,
+        expected: String,
+    },
+    InvalidTile {
+        location: Location,
+        found: char,
+    },
+    InvalidHeight {
+        location: Location,
+        found: String,
+    },
+    /// a part of the input that never turned up, like day 5's blank line
+    MissingSection {
+        location: Location,
+        section: &'static str,
+    },
+    /// a JSON file, like a leaderboard export, that isn't what was expected
+    InvalidJson {
+        location: Location,
+        reason: String,
+    },
+    /// a parameter the day doesn't have
+    UnknownParam {
+        name: String,
+        known: Vec<&'static str>,
+    },
+    /// a parameter value that doesn't read as the type the day needs
+    InvalidParam {
+        name: String,
+        value: String,
+        expected: &'static str,
+    },
+    /// a parameter that reads fine but is too big or small to work with
+    OutOfRange {
+        name: String,
+        value: u64,
+        min: u64,
+        max: u64,
+    },
+    /// there's no session token to fetch an input with
+    MissingSession {
+        path: PathBuf,
+    },
+    /// the server couldn't or wouldn't hand over an input
+    Fetch {
+        url: String,
+        reason: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn malformed(location: Location, expected: impl Into<String>) -> Self {
+        Error::MalformedLine {
+            location,
+            expected: expected.into(),
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::Io { .. }
+            | Error::UnknownParam { .. }
+            | Error::InvalidParam { .. }
+            | Error::OutOfRange { .. }
+            | Error::MissingSession { .. }
+            | Error::Fetch { .. } => None,
+            Error::MalformedLine { location, .. }
+            | Error::InvalidTile { location, .. }
+            | Error::InvalidHeight { location, .. }
+            | Error::MissingSection { location, .. }
+            | Error::InvalidJson { location, .. } => Some(location),
+        }
+    }
+
+    fn location_mut(&mut self) -> Option<&mut Location> {
+        match self {
+            Error::Io { .. }
+            | Error::UnknownParam { .. }
+            | Error::InvalidParam { .. }
+            | Error::OutOfRange { .. }
+            | Error::MissingSession { .. }
+            | Error::Fetch { .. } => None,
+            Error::MalformedLine { location, .. }
+            | Error::InvalidTile { location, .. }
+            | Error::InvalidHeight { location, .. }
+            | Error::MissingSection { location, .. }
+            | Error::InvalidJson { location, .. } => Some(location),
+        }
+    }
+
+    /// attach the file the input was read from
+    pub fn in_file(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        match &mut self {
+            Error::Io { path: p, .. } => *p = Some(path),
+            _ => {
+                if let Some(location) = self.location_mut() {
+                    location.file = Some(path);
+                }
+            }
+        }
+        self
+    }
+
+    /// for parsers that only see a single line, move the error onto the line it
+    /// actually came from
+    pub fn on_line(mut self, line: usize) -> Self {
+        if let Some(location) = self.location_mut() {
+            location.line = line;
+        }
+        self
+    }
+
+    /// a human readable report quoting the offending line of `source` with a
+    /// marker under the column
+    pub fn diagnostic(&self, source: &str) -> String {
+        let Some(location) = self.location() else {
+            return format!("error: {self}");
+        };
+        let Some(text) = source.lines().nth(location.line.saturating_sub(1)) else {
+            return format!("error: {self}");
+        };
+        let gutter = location.line.to_string().len();
+        format!(
+            "error: {self}\n{:gutter$} |\n{} | {}\n{:gutter$} | {}^",
+            "",
+            location.line,
+            text,
+            "",
+            " ".repeat(location.column.saturating_sub(1)),
+        )
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "{}: {source}", path.display()),
+            Error::Io { path: None, source } => write!(f, "{source}"),
+            Error::MalformedLine { location, expected } => {
+                write!(f, "{location}: malformed line, expected {expected}")
+            }
+            Error::InvalidTile { location, found } => {
+                write!(f, "{location}: invalid tile '{found}'")
+            }
+            Error::InvalidHeight { location, found } => {
+                write!(f, "{location}: invalid height '{found}', expected 0-9")
+            }
+            Error::MissingSection { location, section } => {
+                write!(f, "{location}: missing {section}")
+            }
+            Error::InvalidJson { location, reason } => {
+                write!(f, "{location}: invalid JSON, {reason}")
+            }
+            Error::UnknownParam { name, known } if known.is_empty() => {
+                write!(f, "unknown parameter '{name}', this day has none")
+            }
+            Error::UnknownParam { name, known } => {
+                write!(
+                    f,
+                    "unknown parameter '{name}', expected one of {}",
+                    known.join(", ")
+                )
+            }
+            Error::InvalidParam {
+                name,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value '{value}' for parameter '{name}', expected {expected}"
+            ),
+            Error::OutOfRange {
+                name,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "parameter '{name}' is {value}, it has to be from {min} to {max}"
+            ),
+            Error::MissingSession { path } => write!(
+                f,
+                "no session token to fetch the input with, set AOC_SESSION or write it to {}",
+                path.display()
+            ),
+            Error::Fetch { url, reason } => write!(f, "couldn't fetch {url}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        // the message ends in where it went wrong, which the location carries
+        let message = e.to_string();
+        let reason = match message.rfind(" at line ") {
+            Some(at) => &message[..at],
+            None => &message,
+        };
+        Error::InvalidJson {
+            location: Location::new(e.line(), e.column()),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Error::Io { path: None, source }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_of() {
+        let input = "abc\ndef ghi\n";
+        let line = input.lines().nth(1).unwrap();
+        let word = line.split(' ').nth(1).unwrap();
+        assert_eq!(Location::of(input, line), Location::new(2, 1));
+        assert_eq!(Location::of(input, word), Location::new(2, 5));
+        assert_eq!(Location::of(input, "elsewhere"), Location::new(1, 1));
+    }
+
+    #[test]
+    fn test_in_file_and_display() {
+        let error = Error::malformed(Location::new(3, 7), "a number").in_file("day01.txt");
+        assert_eq!(
+            error.to_string(),
+            "day01.txt:3:7: malformed line, expected a number"
+        );
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let error = Error::InvalidTile {
+            location: Location::new(2, 3),
+            found: '?',
+        };
+        let expected = "error: line 2, column 3: invalid tile '?'\n  |\n2 | ..?.\n  |   ^";
+        assert_eq!(error.diagnostic("....\n..?.\n"), expected);
+    }
+}
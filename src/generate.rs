@@ -0,0 +1,149 @@
+use std::ops::RangeInclusive;
+
+use crate::error::Result;
+use crate::params::Params;
+
+/// A small random number generator that gives the same numbers for the same
+/// seed on every machine and every version, so a generated input can always
+/// be made again from its seed. This is splitmix64, which is plenty for
+/// puzzle inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// a number from 0 up to but not including `n`, which can't be 0
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "there's no number below 0");
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// a number in `range`, which can't be empty
+    pub fn between(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "{start}..={end} is empty");
+        let span = end.abs_diff(start);
+        match span.checked_add(1) {
+            Some(n) => start.wrapping_add(self.below(n) as i64),
+            None => self.next_u64() as i64,
+        }
+    }
+
+    /// an index into something `len` long
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// true with a chance of `p` out of 1
+    pub fn chance(&mut self, p: f64) -> bool {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        unit < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// the size `name` from the day's resolved sizes, checked to be in `range`
+pub fn size(sizes: &Params, name: &str, range: RangeInclusive<u64>) -> Result<u64> {
+    sizes.get_within(name, range)
+}
+
+/// a grid `width` across and `height` down with each cell from `cell`, a line
+/// a row
+pub fn grid(width: u64, height: u64, mut cell: impl FnMut(u64, u64) -> char) -> String {
+    let mut out = String::with_capacity(((width + 1) * height) as usize);
+    for y in 0..height {
+        out.extend((0..width).map(|x| cell(x, y)));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::Param;
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        // the reference values for splitmix64, which a seed has to keep giving
+        assert_eq!(Rng::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!(rng.below(3) < 3);
+            assert!((-2..=2).contains(&rng.between(-2..=2)));
+        }
+        assert_eq!(rng.between(5..=5), 5);
+        let mut seen = [false; 6];
+        for _ in 0..100 {
+            seen[rng.between(0..=5) as usize] = true;
+        }
+        assert!(seen.iter().all(|s| *s));
+        let _ = rng.between(i64::MIN..=i64::MAX);
+
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_size() {
+        const SIZES: &[Param] = &[Param {
+            name: "width",
+            default: "10",
+            help: "",
+        }];
+        let mut given = Params::new();
+        assert_eq!(
+            size(&given.resolve(SIZES).unwrap(), "width", 1..=100).unwrap(),
+            10
+        );
+        given.set("width", "1000");
+        let error = size(&given.resolve(SIZES).unwrap(), "width", 1..=100).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "parameter 'width' is 1000, it has to be from 1 to 100"
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        assert_eq!(
+            grid(3, 2, |x, y| if x == y { '#' } else { '.' }),
+            "#..\n.#.\n"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::grid::Point;
+
+/// A spot on the plane, x counting across to the right and y counting down
+/// like the rows of an input. It is signed so that walking off the top or left
+/// of a map is something that can be represented and then checked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Position {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// The difference between two positions. It is the same shape so the two mix
+/// freely, the name just says which is meant
+pub type Vector = Position;
+
+/// The steps to the four positions sharing an edge, clockwise from up
+pub const ORTHOGONAL: [Vector; 4] = [
+    Position::new(0, -1),
+    Position::new(1, 0),
+    Position::new(0, 1),
+    Position::new(-1, 0),
+];
+
+/// The steps to all eight surrounding positions, clockwise from up
+pub const ADJACENT: [Vector; 8] = [
+    Position::new(0, -1),
+    Position::new(1, -1),
+    Position::new(1, 0),
+    Position::new(1, 1),
+    Position::new(0, 1),
+    Position::new(-1, 1),
+    Position::new(-1, 0),
+    Position::new(-1, -1),
+];
+
+impl Position {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// the number of orthogonal steps between the two
+    pub fn manhattan(self, other: Position) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// the z of the cross product, zero when the two are parallel
+    pub fn cross(self, other: Vector) -> i64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// a quarter turn clockwise, as seen with y pointing down
+    pub fn turn_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// a quarter turn anticlockwise, as seen with y pointing down
+    pub fn turn_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// the same length pointing the other way
+    pub fn reverse(self) -> Self {
+        -self
+    }
+
+    /// the grid index for this position, `None` if either part is negative
+    pub fn to_point(self) -> Option<Point> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+}
+
+impl From<Point> for Position {
+    fn from((x, y): Point) -> Self {
+        Self::new(x as i64, y as i64)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl Add for Position {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Position {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Position {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Position {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i64> for Position {
+    type Output = Self;
+
+    fn mul(self, scale: i64) -> Self {
+        Self::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl Neg for Position {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+/// One of the four ways to walk on a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// every direction, clockwise from up
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// the step one position along in this direction
+    pub fn step(self) -> Vector {
+        ORTHOGONAL[self as usize]
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Position::new(3, -2);
+        let b = Vector::new(-1, 5);
+        assert_eq!(a + b, Position::new(2, 3));
+        assert_eq!(a - b, Position::new(4, -7));
+        assert_eq!(b * 3, Vector::new(-3, 15));
+        assert_eq!(-a, Position::new(-3, 2));
+        let mut c = a;
+        c += b;
+        c -= b;
+        assert_eq!(c, a);
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(Vector::new(2, 4).cross(Vector::new(1, 2)), 0);
+    }
+
+    #[test]
+    fn test_turns() {
+        let up = Direction::Up.step();
+        assert_eq!(up.turn_right(), Direction::Right.step());
+        assert_eq!(up.turn_left(), Direction::Left.step());
+        assert_eq!(up.reverse(), Direction::Down.step());
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.reverse().reverse(), direction);
+            assert_eq!(direction.turn_right().step(), direction.step().turn_right());
+            assert_eq!(direction.reverse().step(), direction.step().reverse());
+        }
+        assert_eq!(ADJACENT.iter().filter(|v| v.x != 0 && v.y != 0).count(), 4);
+    }
+
+    #[test]
+    fn test_to_point() {
+        assert_eq!(Position::new(4, 2).to_point(), Some((4, 2)));
+        assert_eq!(Position::new(-1, 2).to_point(), None);
+        assert_eq!(Position::new(4, -2).to_point(), None);
+        assert_eq!(Position::from((7, 8)), Position::new(7, 8));
+    }
+}
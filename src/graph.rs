@@ -0,0 +1,447 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::grid::{Grid, Point};
+
+/// Anything with nodes that lead on to other nodes. The searches below only
+/// ever ask for the neighbours of a node, so a grid, an adjacency list or a
+/// closure that works them out on the fly all fit
+pub trait Graph {
+    type Node: Copy + Eq + Hash;
+
+    /// the nodes one edge on from `node`
+    fn neighbours(&self, node: Self::Node) -> impl Iterator<Item = Self::Node>;
+}
+
+/// every cell of a grid leads to the cells sharing an edge with it
+impl<T> Graph for Grid<T> {
+    type Node = Point;
+
+    fn neighbours(&self, node: Point) -> impl Iterator<Item = Point> {
+        self.neighbours4(node)
+    }
+}
+
+/// an adjacency list, a node missing from the map has no edges
+impl<N: Copy + Eq + Hash> Graph for HashMap<N, Vec<N>> {
+    type Node = N;
+
+    fn neighbours(&self, node: N) -> impl Iterator<Item = N> {
+        self.get(&node).into_iter().flatten().copied()
+    }
+}
+
+/// A graph whose edges come from a closure, see [`from_fn`]
+pub struct FromFn<N, F> {
+    neighbours: F,
+    node: PhantomData<N>,
+}
+
+/// a graph where `neighbours` says where each node leads, i.e. the cells of a
+/// map that are one higher than the one you're stood on
+pub fn from_fn<N, I, F>(neighbours: F) -> FromFn<N, F>
+where
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    FromFn {
+        neighbours,
+        node: PhantomData,
+    }
+}
+
+impl<N, I, F> Graph for FromFn<N, F>
+where
+    N: Copy + Eq + Hash,
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Node = N;
+
+    fn neighbours(&self, node: N) -> impl Iterator<Item = N> {
+        (self.neighbours)(node).into_iter()
+    }
+}
+
+/// the number of edges from `start` to every node it can reach, `start`
+/// itself included at zero
+pub fn bfs<G: Graph>(graph: &G, start: G::Node) -> HashMap<G::Node, u64> {
+    let mut distances = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node] + 1;
+        for next in graph.neighbours(node) {
+            if let Entry::Vacant(entry) = distances.entry(next) {
+                entry.insert(distance);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// every node reachable from `start` in the order a depth first search first
+/// meets them
+pub fn dfs<G: Graph>(graph: &G, start: G::Node) -> Vec<G::Node> {
+    let mut seen = HashSet::new();
+    let mut order = Vec::new();
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        if !seen.insert(node) {
+            continue;
+        }
+        order.push(node);
+        // pushed backwards so the first neighbour is the first one walked
+        let next: Vec<_> = graph.neighbours(node).collect();
+        stack.extend(next.into_iter().rev().filter(|n| !seen.contains(n)));
+    }
+    order
+}
+
+/// the cheapest cost from `start` to every node it can reach, `cost` giving
+/// the price of each edge
+pub fn dijkstra<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    cost: impl Fn(G::Node, G::Node) -> u64,
+) -> HashMap<G::Node, u64> {
+    let mut best = HashMap::from([(start, 0)]);
+    let mut queue = Queue::new(start);
+    while let Some((spent, node)) = queue.pop() {
+        if spent > best[&node] {
+            continue;
+        }
+        for next in graph.neighbours(node) {
+            let total = spent + cost(node, next);
+            if best.get(&next).is_none_or(|&b| total < b) {
+                best.insert(next, total);
+                queue.push(total, next);
+            }
+        }
+    }
+    best
+}
+
+/// the cheapest path from `start` to a node where `goal` holds, along with what
+/// it costs. `heuristic` guesses the cost left from a node, and as long as it
+/// never guesses too high the path is the cheapest there is. A heuristic of
+/// zero makes this plain Dijkstra
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    goal: impl Fn(G::Node) -> bool,
+    cost: impl Fn(G::Node, G::Node) -> u64,
+    heuristic: impl Fn(G::Node) -> u64,
+) -> Option<(u64, Vec<G::Node>)> {
+    let mut best = HashMap::from([(start, 0)]);
+    let mut came_from = HashMap::new();
+    let mut queue = Queue::new(start);
+    while let Some((_, node)) = queue.pop() {
+        let spent = best[&node];
+        if goal(node) {
+            let mut path = vec![node];
+            while let Some(&previous) = came_from.get(path.last().unwrap()) {
+                path.push(previous);
+            }
+            path.reverse();
+            return Some((spent, path));
+        }
+        for next in graph.neighbours(node) {
+            let total = spent + cost(node, next);
+            if best.get(&next).is_none_or(|&b| total < b) {
+                best.insert(next, total);
+                came_from.insert(next, node);
+                queue.push(total + heuristic(next), next);
+            }
+        }
+    }
+    None
+}
+
+/// A min-heap of nodes by priority. The nodes sit in a list and the heap just
+/// orders their indexes, so they don't need to be `Ord` themselves
+struct Queue<N> {
+    heap: BinaryHeap<Reverse<(u64, usize)>>,
+    nodes: Vec<N>,
+}
+
+impl<N: Copy> Queue<N> {
+    fn new(start: N) -> Self {
+        Self {
+            heap: BinaryHeap::from([Reverse((0, 0))]),
+            nodes: vec![start],
+        }
+    }
+
+    fn push(&mut self, priority: u64, node: N) {
+        self.heap.push(Reverse((priority, self.nodes.len())));
+        self.nodes.push(node);
+    }
+
+    fn pop(&mut self) -> Option<(u64, N)> {
+        let Reverse((priority, index)) = self.heap.pop()?;
+        Some((priority, self.nodes[index]))
+    }
+}
+
+/// The nodes of a cycle in the order the edges run, the last one leading back
+/// round to the first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+/// order `nodes` and everything they lead to so every edge points forwards,
+/// or the cycle that makes that impossible
+pub fn toposort<G: Graph>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = G::Node>,
+) -> Result<Vec<G::Node>, Cycle<G::Node>> {
+    // false while a node is on the path being walked, true once it's finished
+    let mut done: HashMap<G::Node, bool> = HashMap::new();
+    let mut order = Vec::new();
+    for root in nodes {
+        if done.contains_key(&root) {
+            continue;
+        }
+        done.insert(root, false);
+        let mut path = vec![(root, graph.neighbours(root))];
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            match next.next() {
+                Some(next) => match done.get(&next) {
+                    None => {
+                        done.insert(next, false);
+                        path.push((next, graph.neighbours(next)));
+                    }
+                    Some(false) => {
+                        let start = path.iter().position(|(n, _)| *n == next).unwrap();
+                        return Err(Cycle(path[start..].iter().map(|(n, _)| *n).collect()));
+                    }
+                    Some(true) => {}
+                },
+                None => {
+                    done.insert(node, true);
+                    order.push(node);
+                    path.pop();
+                }
+            }
+        }
+    }
+    order.reverse();
+    Ok(order)
+}
+
+/// the strongly connected components among `nodes` and everything they lead
+/// to, each a set of nodes that can all reach each other. Tarjan's algorithm,
+/// which hands the components back with the ones nothing leaves first
+pub fn scc<G: Graph>(graph: &G, nodes: impl IntoIterator<Item = G::Node>) -> Vec<Vec<G::Node>> {
+    // the order each node was found in and the earliest found node it reaches
+    let mut found: HashMap<G::Node, (usize, usize)> = HashMap::new();
+    let mut stack = Vec::new();
+    let mut on_stack = HashSet::new();
+    let mut components = Vec::new();
+    for root in nodes {
+        if found.contains_key(&root) {
+            continue;
+        }
+        let mut enter = Some(root);
+        let mut path = Vec::new();
+        loop {
+            if let Some(node) = enter.take() {
+                let index = found.len();
+                found.insert(node, (index, index));
+                stack.push(node);
+                on_stack.insert(node);
+                path.push((node, graph.neighbours(node)));
+            }
+            let Some((node, next)) = path.last_mut() else {
+                break;
+            };
+            let node = *node;
+            match next.next() {
+                Some(next) => {
+                    if !found.contains_key(&next) {
+                        enter = Some(next);
+                    } else if on_stack.contains(&next) {
+                        let index = found[&next].0;
+                        let low = &mut found.get_mut(&node).unwrap().1;
+                        *low = (*low).min(index);
+                    }
+                }
+                None => {
+                    path.pop();
+                    let (index, low) = found[&node];
+                    if let Some((parent, _)) = path.last() {
+                        let parent = &mut found.get_mut(parent).unwrap().1;
+                        *parent = (*parent).min(low);
+                    }
+                    if index == low {
+                        let mut component = Vec::new();
+                        while let Some(member) = stack.pop() {
+                            on_stack.remove(&member);
+                            component.push(member);
+                            if member == node {
+                                break;
+                            }
+                        }
+                        components.push(component);
+                    }
+                }
+            }
+        }
+    }
+    components
+}
+
+/// Sets of the numbers `0..n` that can be merged, answering which set a number
+/// is in as it goes
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    /// `n` sets of one
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            sets: n,
+        }
+    }
+
+    /// the number standing for the set `x` is in
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            // point every other node at its grandparent to flatten the tree
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// merge the sets of `a` and `b`, false if they were already the same
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] < self.size[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.sets -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// how many numbers are in the set with `x`
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// how many separate sets there are
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(list: &[(u32, u32)]) -> HashMap<u32, Vec<u32>> {
+        let mut graph: HashMap<u32, Vec<u32>> = HashMap::new();
+        for &(from, to) in list {
+            graph.entry(from).or_default().push(to);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_bfs_on_grid() {
+        let grid = Grid::new(3, 2, ());
+        let distances = bfs(&grid, (0, 0));
+        assert_eq!(distances.len(), 6);
+        assert_eq!(distances[&(2, 1)], 3);
+        let walls: Grid<char> = "..#\n#..\n".parse().unwrap();
+        let open = from_fn(|p| walls.neighbours4(p).filter(|&n| walls[n] == '.'));
+        assert_eq!(bfs(&open, (0, 0))[&(2, 1)], 3);
+        assert!(!bfs(&open, (0, 0)).contains_key(&(2, 0)));
+    }
+
+    #[test]
+    fn test_dfs() {
+        let graph = edges(&[(1, 2), (1, 3), (2, 4), (3, 4), (5, 1)]);
+        assert_eq!(dfs(&graph, 1), vec![1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let graph = edges(&[(1, 2), (2, 3), (1, 3), (3, 4)]);
+        let cost = |a: u32, b: u32| if (a, b) == (1, 3) { 10 } else { 1 };
+        let best = dijkstra(&graph, 1, cost);
+        assert_eq!(best[&3], 2);
+        assert_eq!(best[&4], 3);
+        assert_eq!(
+            astar(&graph, 1, |n| n == 4, cost, |_| 0),
+            Some((3, vec![1, 2, 3, 4]))
+        );
+        assert_eq!(astar(&graph, 4, |n| n == 1, cost, |_| 0), None);
+
+        let grid = Grid::new(5, 5, ());
+        let goal = (4, 3);
+        let distance = |(x, y): Point| (x.abs_diff(goal.0) + y.abs_diff(goal.1)) as u64;
+        let (spent, path) = astar(&grid, (0, 0), |p| p == goal, |_, _| 1, distance).unwrap();
+        assert_eq!(spent, 7);
+        assert_eq!(path.len(), 8);
+    }
+
+    #[test]
+    fn test_toposort() {
+        let graph = edges(&[(47, 53), (97, 13), (97, 47), (75, 47), (47, 13)]);
+        let order = toposort(&graph, [13, 47, 53, 75, 97]).unwrap();
+        let at = |n| order.iter().position(|&m| m == n).unwrap();
+        for (&from, to) in &graph {
+            assert!(to.iter().all(|&to| at(from) < at(to)));
+        }
+        assert_eq!(order.len(), 5);
+
+        let graph = edges(&[(1, 2), (2, 3), (3, 4), (4, 2)]);
+        assert_eq!(toposort(&graph, [1]), Err(Cycle(vec![2, 3, 4])));
+        assert_eq!(toposort(&graph, [4]), Err(Cycle(vec![4, 2, 3])));
+    }
+
+    #[test]
+    fn test_scc() {
+        let graph = edges(&[(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (6, 5)]);
+        let mut components = scc(&graph, 1..=6);
+        components.iter_mut().for_each(|c| c.sort());
+        assert_eq!(components, vec![vec![4, 5], vec![1, 2, 3], vec![6]]);
+    }
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.same(0, 3));
+        assert!(!sets.same(0, 4));
+        assert_eq!(sets.size(2), 4);
+        assert_eq!(sets.size(5), 1);
+        assert_eq!(sets.sets(), 3);
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::error::{Error, Location, Result};
+use crate::geom::{ADJACENT, ORTHOGONAL, Position, Vector};
+
+/// A cell of a grid as `(x, y)`, x counting across from the left and y down from
+/// the top
+pub type Point = (usize, usize);
+
+/// A rectangle of cells stored flat, row after row. Everything that takes a
+/// point checks it against the bounds so the days don't have to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// a `width` by `height` grid with every cell set to `fill`
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// build a grid from its rows, `None` if they aren't all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = rows.len();
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// read a grid with a cell per character, `tile` saying what each is. The
+    /// rows must all be as wide as the first and there has to be at least one
+    pub fn try_parse(
+        input: &str,
+        mut tile: impl FnMut(char, Location) -> Result<T>,
+    ) -> Result<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (y, line) in input.lines().enumerate() {
+            let before = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                cells.push(tile(c, Location::new(y + 1, x + 1))?);
+            }
+            let row = cells.len() - before;
+            match width {
+                None => width = Some(row),
+                Some(width) if width != row => {
+                    return Err(Error::malformed(
+                        Location::of(input, line),
+                        format!("a row of {width} tiles"),
+                    ));
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(Error::MissingSection {
+                location: Location::new(1, 1),
+                section: "grid",
+            }),
+        }
+    }
+
+    /// `try_parse` where any character `tile` doesn't know is an invalid tile
+    pub fn parse(input: &str, mut tile: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        Self::try_parse(input, |c, location| {
+            tile(c).ok_or(Error::InvalidTile { location, found: c })
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point).then(|| point.1 * self.width + point.0)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    /// store `value` at `point`, handing back the old value. Nothing changes
+    /// if the point is off the grid
+    pub fn set(&mut self, point: Point, value: T) -> Option<T> {
+        self.get_mut(point)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// the point for `position`, if it's on the grid
+    pub fn point(&self, position: Position) -> Option<Point> {
+        position.to_point().filter(|&point| self.contains(point))
+    }
+
+    /// the point `step` away from `point`, if it's still on the grid
+    pub fn offset(&self, point: Point, step: Vector) -> Option<Point> {
+        self.point(Position::from(point) + step)
+    }
+
+    /// the points sharing an edge with `point`, clockwise from up
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |&step| self.offset(point, step))
+    }
+
+    /// every point around `point` including the diagonals, clockwise from up
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> {
+        ADJACENT
+            .iter()
+            .filter_map(move |&step| self.offset(point, step))
+    }
+
+    /// every point, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<T> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i % width, i / width))
+    }
+
+    /// every cell along with its point, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    /// every cell row by row, without the points
+    pub fn cells(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// the cells of row `y`, empty if there is no such row
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        let row = if y < self.height {
+            &self.cells[y * self.width..(y + 1) * self.width]
+        } else {
+            &[]
+        };
+        row.iter()
+    }
+
+    /// the cells of column `x` from top to bottom
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.ray((x, 0), Vector::new(0, 1)).map(|(_, cell)| cell)
+    }
+
+    /// the cells from `start` stepping by `step` until the edge, starting with
+    /// `start` itself. Nothing if `start` is off the grid
+    pub fn ray(&self, start: Point, step: Vector) -> impl Iterator<Item = (Point, &T)> {
+        std::iter::successors(self.contains(start).then_some(start), move |&point| {
+            self.offset(point, step)
+        })
+        .map(|point| (point, &self[point]))
+    }
+
+    /// the cells from `start` down and to the right
+    pub fn diagonal(&self, start: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.ray(start, Vector::new(1, 1))
+    }
+
+    /// the cells from `start` down and to the left
+    pub fn anti_diagonal(&self, start: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.ray(start, Vector::new(-1, 1))
+    }
+
+    /// the first point, row by row, holding `value`
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.position(|cell| cell == value)
+    }
+
+    /// the first point, row by row, whose cell matches
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// a grid of the same shape with `f` applied to every cell
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    /// panics if `point` is off the grid, use `get` when it might be
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is off the {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} is off the {width}x{height} grid"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    /// the characters as they are, only checking the shape
+    fn from_str(input: &str) -> Result<Self> {
+        Self::parse(input, Some)
+    }
+}
+
+/// the cells row by row, each row on its own line
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        "abc\ndef\n".parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], 'e');
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+
+        let error = "abc\nde\n".parse::<Grid<char>>().unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 1)));
+        let error = "".parse::<Grid<char>>().unwrap_err();
+        assert!(matches!(error, Error::MissingSection { .. }));
+        let error = Grid::parse("..\n.?", |c| (c == '.').then_some(())).unwrap_err();
+        assert!(matches!(error, Error::InvalidTile { found: '?', .. }));
+        assert_eq!(error.location(), Some(&Location::new(2, 2)));
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let grid = Grid::from_rows(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(grid[(0, 1)], 3);
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_none());
+    }
+
+    #[test]
+    fn test_get_and_set() {
+        let mut grid = grid();
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((0, 2)), None);
+        assert_eq!(grid.set((2, 1), 'x'), Some('f'));
+        assert_eq!(grid.set((9, 9), 'x'), None);
+        assert_eq!(grid.to_string(), "abc\ndex\n");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+        let corner: Vec<Point> = grid.neighbours4((0, 0)).collect();
+        assert_eq!(corner, vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours4((1, 1)).count(), 3);
+        assert_eq!(grid.neighbours8((1, 0)).count(), 5);
+        assert_eq!(grid.offset((0, 0), Vector::new(-1, 0)), None);
+        assert_eq!(grid.point(Position::new(2, 1)), Some((2, 1)));
+        assert_eq!(grid.point(Position::new(3, 1)), None);
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = grid();
+        assert_eq!(grid.row(1).collect::<String>(), "def");
+        assert_eq!(grid.row(2).count(), 0);
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        let diagonal: String = grid.diagonal((0, 0)).map(|(_, c)| c).collect();
+        assert_eq!(diagonal, "ae");
+        let anti: String = grid.anti_diagonal((2, 0)).map(|(_, c)| c).collect();
+        assert_eq!(anti, "ce");
+        assert_eq!(grid.ray((5, 5), Vector::new(1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_find_and_map() {
+        let grid = grid();
+        assert_eq!(grid.find(&'e'), Some((1, 1)));
+        assert_eq!(grid.find(&'z'), None);
+        assert_eq!(grid.position(|c| *c > 'c'), Some((0, 1)));
+        let upper = grid.map(|c| c.to_ascii_uppercase());
+        assert_eq!(upper.to_string(), "ABC\nDEF\n");
+    }
+}
@@ -0,0 +1,351 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::visual::{Cell, Colour, Frame};
+
+/// A picture of a frame, each cell a square of `scale` pixels a side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// red, green and blue for each pixel, a row at a time
+    pub pixels: Vec<[u8; 3]>,
+}
+
+/// The kinds of file a frame can be saved as, going by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Ppm,
+    Png,
+    Gif,
+}
+
+impl Kind {
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "ppm" => Some(Kind::Ppm),
+            "png" => Some(Kind::Png),
+            "gif" => Some(Kind::Gif),
+            _ => None,
+        }
+    }
+}
+
+impl Image {
+    pub fn from_frame(frame: &Frame, scale: usize) -> Self {
+        let width = frame.cells.width() * scale;
+        let mut pixels = Vec::with_capacity(width * frame.cells.height() * scale);
+        for row in frame.cells.rows() {
+            let line: Vec<[u8; 3]> = row
+                .iter()
+                .flat_map(|cell| std::iter::repeat_n(cell.rgb(), scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        Self {
+            width,
+            height: frame.cells.height() * scale,
+            pixels,
+        }
+    }
+
+    /// write the image as a binary PPM, the simplest format there is
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(self.pixels.as_flattened())
+    }
+
+    pub fn write_png(&self, out: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(self.pixels.as_flattened())?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+/// every colour a cell can be, the first being plain, as a GIF palette
+const PALETTE: [Option<Colour>; 9] = [
+    None,
+    Some(Colour::Red),
+    Some(Colour::Green),
+    Some(Colour::Yellow),
+    Some(Colour::Blue),
+    Some(Colour::Magenta),
+    Some(Colour::Cyan),
+    Some(Colour::White),
+    Some(Colour::Grey),
+];
+
+fn palette_index(cell: &Cell) -> u8 {
+    PALETTE.iter().position(|c| *c == cell.colour).unwrap_or(0) as u8
+}
+
+/// write `frames` as an animated GIF that loops forever, each frame held for
+/// `delay`. The frames all have to be the size of the first. Hands back how
+/// many were written
+pub fn write_gif(
+    frames: impl Iterator<Item = Frame>,
+    scale: usize,
+    delay: Duration,
+    out: impl Write,
+) -> io::Result<usize> {
+    let mut frames = frames.peekable();
+    let Some(first) = frames.peek() else {
+        return Err(io::Error::other("there are no frames to write"));
+    };
+    let (columns, rows) = (first.cells.width(), first.cells.height());
+    let size = |cells: usize| {
+        u16::try_from(cells * scale)
+            .map_err(|_| io::Error::other("the frames are too big for a GIF at this scale"))
+    };
+    let (width, height) = (size(columns)?, size(rows)?);
+
+    let palette: Vec<u8> = PALETTE
+        .iter()
+        .flat_map(|&colour| Cell { glyph: ' ', colour }.rgb())
+        .collect();
+    let mut encoder = gif::Encoder::new(out, width, height, &palette).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+    // the delay is in hundredths of a second
+    let delay = (delay.as_millis() / 10).clamp(1, u16::MAX as u128) as u16;
+    let mut written = 0;
+    for frame in frames {
+        if (frame.cells.width(), frame.cells.height()) != (columns, rows) {
+            return Err(io::Error::other(format!(
+                "frame {} is a different size to the first",
+                written + 1
+            )));
+        }
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for row in frame.cells.rows() {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|cell| std::iter::repeat_n(palette_index(cell), scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        let mut image = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+        image.delay = delay;
+        encoder.write_frame(&image).map_err(io::Error::other)?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// the file at `path` to write an image to, buffered
+fn create(path: &Path) -> Result<BufWriter<File>> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|e| Error::from(e).in_file(path))
+}
+
+/// save a single frame as a PPM or PNG, going by the extension of `path`
+pub fn save(frame: &Frame, scale: usize, path: &Path) -> Result<()> {
+    // which kind it is comes first so there's no empty file left when it's neither
+    let Some(kind @ (Kind::Ppm | Kind::Png)) = Kind::of(path) else {
+        let error = io::Error::other("a single frame is saved as a .ppm or .png");
+        return Err(Error::from(error).in_file(path));
+    };
+    let image = Image::from_frame(frame, scale);
+    let mut out = create(path)?;
+    match kind {
+        Kind::Png => image.write_png(&mut out),
+        _ => image.write_ppm(&mut out),
+    }
+    .and_then(|_| out.flush())
+    .map_err(|e| Error::from(e).in_file(path))
+}
+
+/// save `frames` as an animated GIF at `path`
+pub fn save_gif(
+    frames: impl Iterator<Item = Frame>,
+    scale: usize,
+    delay: Duration,
+    path: &Path,
+) -> Result<usize> {
+    let mut frames = frames.peekable();
+    if frames.peek().is_none() {
+        let error = io::Error::other("there are no frames to write");
+        return Err(Error::from(error).in_file(path));
+    }
+    let mut out = create(path)?;
+    write_gif(frames, scale, delay, &mut out)
+        .and_then(|written| out.flush().map(|_| written))
+        .map_err(|e| Error::from(e).in_file(path))
+}
+
+/// the file a numbered frame goes in, `{}` in `path` replaced by its number
+fn numbered(path: &Path, number: usize) -> PathBuf {
+    PathBuf::from(
+        path.to_string_lossy()
+            .replace("{}", &format!("{number:05}")),
+    )
+}
+
+/// write numbered `frames` to `path`, all of them as a GIF or each to a PPM or
+/// PNG of its own when there's a `{}` in the name for its number. Without one
+/// there has to be just the one frame. Hands back how many were written
+pub fn export(
+    frames: impl Iterator<Item = (usize, Frame)>,
+    scale: usize,
+    delay: Duration,
+    path: &Path,
+) -> Result<usize> {
+    if Kind::of(path) == Some(Kind::Gif) {
+        return save_gif(frames.map(|(_, frame)| frame), scale, delay, path);
+    }
+    if path.to_string_lossy().contains("{}") {
+        let mut written = 0;
+        for (number, frame) in frames {
+            save(&frame, scale, &numbered(path, number))?;
+            written += 1;
+        }
+        return Ok(written);
+    }
+    let mut frames = frames.map(|(_, frame)| frame);
+    let only = match (frames.next(), frames.next()) {
+        (Some(only), None) => Ok(only),
+        (None, _) => Err(io::Error::other("there are no frames to write")),
+        (Some(_), Some(_)) => Err(io::Error::other(
+            "there's more than one frame, pick one or put {} in the name for each one's number",
+        )),
+    }
+    .map_err(|e| Error::from(e).in_file(path))?;
+    save(&only, scale, path)?;
+    Ok(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    fn frame(glyph: char) -> Frame {
+        let cells = Grid::from_rows(vec![
+            vec![Cell::new(glyph, Colour::Red), Cell::plain('.')],
+            vec![Cell::new('.', Colour::Grey), Cell::new('#', Colour::Green)],
+        ])
+        .unwrap();
+        Frame::new(cells, "")
+    }
+
+    #[test]
+    fn test_from_frame() {
+        let image = Image::from_frame(&frame('^'), 2);
+        assert_eq!((image.width, image.height), (4, 4));
+        let red = Colour::Red.rgb();
+        let plain = Cell::plain('.').rgb();
+        assert_eq!(&image.pixels[..4], &[red, red, plain, plain]);
+        assert_eq!(&image.pixels[4..8], &[red, red, plain, plain]);
+        assert_eq!(image.pixels[15], Colour::Green.rgb());
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut out = Vec::new();
+        Image::from_frame(&frame('^'), 1)
+            .write_ppm(&mut out)
+            .unwrap();
+        assert!(out.starts_with(b"P6\n2 2\n255\n"));
+        assert_eq!(out.len(), 11 + 2 * 2 * 3);
+        assert_eq!(&out[11..14], &Colour::Red.rgb());
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let image = Image::from_frame(&frame('^'), 3);
+        let mut out = Vec::new();
+        image.write_png(&mut out).unwrap();
+
+        let mut reader = png::Decoder::new(io::Cursor::new(out)).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (6, 6));
+        assert_eq!(pixels, image.pixels.as_flattened());
+    }
+
+    #[test]
+    fn test_gif_round_trip() {
+        let frames = ['^', '>', 'v'].map(frame);
+        let mut out = Vec::new();
+        let written =
+            write_gif(frames.into_iter(), 2, Duration::from_millis(50), &mut out).unwrap();
+        assert_eq!(written, 3);
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(io::Cursor::new(out)).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (4, 4));
+        let mut decoded = 0;
+        while let Some(image) = decoder.read_next_frame().unwrap() {
+            assert_eq!(image.delay, 5);
+            assert_eq!(&image.buffer[..3], &Colour::Red.rgb());
+            decoded += 1;
+        }
+        assert_eq!(decoded, 3);
+    }
+
+    #[test]
+    fn test_gif_errors() {
+        let none = write_gif(std::iter::empty(), 1, Duration::ZERO, Vec::new()).unwrap_err();
+        assert!(none.to_string().contains("no frames"));
+
+        let small = Frame::new(Grid::new(1, 1, Cell::plain('.')), "");
+        let frames = [frame('^'), small].into_iter();
+        let error = write_gif(frames, 1, Duration::ZERO, Vec::new()).unwrap_err();
+        assert!(error.to_string().contains("frame 2 is a different size"));
+    }
+
+    #[test]
+    fn test_export() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let frames = || ['^', '>', 'v'].map(frame).into_iter().enumerate().skip(1);
+
+        let each = dir.path().join("frame-{}.png");
+        assert_eq!(export(frames(), 1, Duration::ZERO, &each).unwrap(), 2);
+        assert!(dir.path().join("frame-00001.png").exists());
+        assert!(dir.path().join("frame-00002.png").exists());
+
+        let gif = dir.path().join("walk.gif");
+        assert_eq!(export(frames(), 1, Duration::ZERO, &gif).unwrap(), 2);
+
+        let one = dir.path().join("one.ppm");
+        let error = export(frames(), 1, Duration::ZERO, &one).unwrap_err();
+        assert!(error.to_string().contains("more than one frame"));
+        assert_eq!(
+            export(frames().take(1), 1, Duration::ZERO, &one).unwrap(),
+            1
+        );
+        assert!(std::fs::read(&one).unwrap().starts_with(b"P6\n2 2\n"));
+
+        // nothing is left behind when there's nothing that can be written
+        let jpg = dir.path().join("one.jpg");
+        let error = export(frames().take(1), 1, Duration::ZERO, &jpg).unwrap_err();
+        assert!(error.to_string().contains(".ppm or .png"));
+        assert!(!jpg.exists());
+        let empty = dir.path().join("empty.gif");
+        let error = export(frames().take(0), 1, Duration::ZERO, &empty).unwrap_err();
+        assert!(error.to_string().contains("no frames"));
+        assert!(!empty.exists());
+    }
+
+    #[test]
+    fn test_kind() {
+        assert_eq!(Kind::of(Path::new("tree.PNG")), Some(Kind::Png));
+        assert_eq!(Kind::of(Path::new("walk.gif")), Some(Kind::Gif));
+        assert_eq!(Kind::of(Path::new("frame.jpg")), None);
+        assert_eq!(Kind::of(Path::new("frame")), None);
+    }
+}
@@ -0,0 +1,402 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::days::{Day, read_input};
+use crate::error::{Error, Result};
+use crate::runner::input_path;
+use crate::solver::Part;
+
+pub const YEAR: u16 = 2024;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// the environment variable a session token is read from before the file
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// the file in the data directory holding the session token by default
+pub const SESSION_FILE: &str = ".session";
+/// the file in the data directory whose age says when the last fetch was
+const STAMP_FILE: &str = ".last-fetch";
+const DEFAULT_USER_AGENT: &str = concat!("aoc24/", env!("CARGO_PKG_VERSION"));
+
+/// The directory of puzzle inputs, one file per day named like `day06.txt`. A
+/// day that isn't there yet is fetched when the store has a fetcher, and kept
+/// so it is never fetched again
+pub struct Store {
+    dir: PathBuf,
+    fetcher: Option<Fetcher>,
+}
+
+impl Store {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            fetcher: None,
+        }
+    }
+
+    /// fetch missing inputs with `fetcher`, which waits between fetches by the
+    /// last one noted in the directory unless it has been told elsewhere
+    pub fn with_fetcher(mut self, mut fetcher: Fetcher) -> Self {
+        fetcher
+            .stamp
+            .get_or_insert_with(|| self.dir.join(STAMP_FILE));
+        self.fetcher = Some(fetcher);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// where the input for `day` is kept
+    pub fn path(&self, day: &Day) -> PathBuf {
+        input_path(&self.dir, day)
+    }
+
+    pub fn is_cached(&self, day: &Day) -> bool {
+        self.path(day).is_file()
+    }
+
+    /// the input for `day`, read from the directory when it's there and
+    /// fetched and saved when it isn't
+    pub fn get(&self, day: &Day) -> Result<String> {
+        let path = self.path(day);
+        let Some(fetcher) = self.fetcher.as_ref().filter(|_| !path.is_file()) else {
+            return read_input(&path);
+        };
+        fs::create_dir_all(&self.dir).map_err(|e| Error::from(e).in_file(&self.dir))?;
+        let content = fetcher.fetch(day.number)?;
+        // written to the side first so a failed write never looks cached
+        let partial = path.with_extension("part");
+        fs::write(&partial, &content)
+            .and_then(|_| fs::rename(&partial, &path))
+            .map_err(|e| Error::from(e).in_file(&path))?;
+        Ok(content)
+    }
+}
+
+/// Downloads inputs from the puzzle server, or anything that serves the same
+/// paths, leaving at least the rate limit between requests
+pub struct Fetcher {
+    base_url: String,
+    session: String,
+    user_agent: String,
+    interval: Duration,
+    /// a file whose modification time is the last fetch, so separate runs
+    /// wait for each other too
+    stamp: Option<PathBuf>,
+    last: Mutex<Option<SystemTime>>,
+}
+
+impl Fetcher {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session: session.into(),
+            user_agent: DEFAULT_USER_AGENT.into(),
+            interval: Duration::from_secs(5),
+            stamp: None,
+            last: Mutex::new(None),
+        }
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// the least time to leave between two requests
+    pub fn rate_limit(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// the address the input for `day` is served from
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/{YEAR}/day/{day}/input", self.base_url)
+    }
+
+    /// where answers for `day` are posted to
+    pub fn answer_url(&self, day: u8) -> String {
+        format!("{}/{YEAR}/day/{day}/answer", self.base_url)
+    }
+
+    /// download the input for `day`, waiting first if the last request was
+    /// too recent
+    pub fn fetch(&self, day: u8) -> Result<String> {
+        let url = self.url(day);
+        self.wait_turn();
+        read_response(&url, self.request("GET", &url).call())
+    }
+
+    /// post `answer` for a part of `day` and hand back the page the server
+    /// answers with, see `submit::Verdict` for making sense of it
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<String> {
+        let url = self.answer_url(day);
+        self.wait_turn();
+        let level = part.number().to_string();
+        let response = self
+            .request("POST", &url)
+            .send_form(&[("level", &level), ("answer", answer)]);
+        read_response(&url, response)
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .request(method, url)
+            .set("User-Agent", &self.user_agent)
+            .set("Cookie", &format!("session={}", self.session))
+    }
+
+    fn wait_turn(&self) {
+        let mut last = self.last.lock().unwrap();
+        let previous = last.or_else(|| {
+            let stamp = self.stamp.as_ref()?;
+            fs::metadata(stamp).ok()?.modified().ok()
+        });
+        if let Some(elapsed) = previous.and_then(|p| p.elapsed().ok())
+            && elapsed < self.interval
+        {
+            thread::sleep(self.interval - elapsed);
+        }
+        *last = Some(SystemTime::now());
+        if let Some(stamp) = &self.stamp {
+            // only a courtesy to the next run, so a failure is no reason to stop
+            let _ = fs::write(stamp, "");
+        }
+    }
+}
+
+/// the body of a response, or why there wasn't one
+fn read_response(
+    url: &str,
+    response: std::result::Result<ureq::Response, ureq::Error>,
+) -> Result<String> {
+    let failed = |reason: String| Error::Fetch {
+        url: url.to_string(),
+        reason,
+    };
+    match response {
+        Ok(response) => response.into_string().map_err(|e| failed(e.to_string())),
+        Err(ureq::Error::Status(code, response)) => Err(failed(format!(
+            "the server answered {code} {}",
+            response.status_text()
+        ))),
+        Err(e) => Err(failed(e.to_string())),
+    }
+}
+
+/// the session token, `env` when that is set, i.e. from `AOC_SESSION`, and
+/// otherwise the first line of `file`
+pub fn read_session(env: Option<String>, file: &Path) -> Result<String> {
+    let missing = || Error::MissingSession {
+        path: file.to_path_buf(),
+    };
+    let token = match env {
+        Some(token) => token,
+        None => fs::read_to_string(file).map_err(|_| missing())?,
+    };
+    match token.lines().next().map(str::trim) {
+        Some(token) if !token.is_empty() => Ok(token.to_string()),
+        _ => Err(missing()),
+    }
+}
+
+/// A stand-in for the puzzle server on a local port, for testing anything that
+/// talks to it
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// A request as the server saw it, the request line and headers followed by
+    /// the body
+    #[derive(Debug, Clone)]
+    pub struct Request {
+        pub lines: Vec<String>,
+        pub body: String,
+    }
+
+    impl Request {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            let prefix = format!("{name}: ");
+            self.lines.iter().find_map(|l| l.strip_prefix(&prefix))
+        }
+    }
+
+    /// Answers every request with the same `status` and `body` and keeps the
+    /// requests it was sent
+    pub struct MockServer {
+        pub url: String,
+        requests: Arc<Mutex<Vec<Request>>>,
+    }
+
+    impl MockServer {
+        pub fn start(status: &'static str, body: &'static str) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let seen = Arc::clone(&requests);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(&stream);
+                    let lines: Vec<String> = (&mut reader)
+                        .lines()
+                        .map(|l| l.unwrap())
+                        .take_while(|l| !l.is_empty())
+                        .collect();
+                    let mut request = Request {
+                        lines,
+                        body: String::new(),
+                    };
+                    let length = request
+                        .header("Content-Length")
+                        .map_or(0, |l| l.parse().unwrap());
+                    reader
+                        .take(length)
+                        .read_to_string(&mut request.body)
+                        .unwrap();
+                    seen.lock().unwrap().push(request);
+                    write!(
+                        stream,
+                        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .unwrap();
+                }
+            });
+            Self { url, requests }
+        }
+
+        pub fn requests(&self) -> Vec<Request> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::MockServer;
+    use super::*;
+    use crate::days::DAYS;
+    use std::time::Instant;
+
+    fn fetcher(server: &MockServer) -> Fetcher {
+        Fetcher::new(&server.url, "abc123")
+            .user_agent("aoc24 tests")
+            .rate_limit(Duration::ZERO)
+    }
+
+    #[test]
+    fn test_fetch_sends_session_and_user_agent() {
+        let server = MockServer::start("200 OK", "3   4\n4   3\n");
+        assert_eq!(fetcher(&server).fetch(1).unwrap(), "3   4\n4   3\n");
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        let request = &requests[0];
+        assert_eq!(request.lines[0], "GET /2024/day/1/input HTTP/1.1");
+        assert_eq!(request.header("Cookie"), Some("session=abc123"));
+        assert_eq!(request.header("User-Agent"), Some("aoc24 tests"));
+    }
+
+    #[test]
+    fn test_submit_posts_the_answer() {
+        let server = MockServer::start("200 OK", "<article>ok</article>");
+        let page = fetcher(&server).submit(6, Part::Two, "1234").unwrap();
+        assert_eq!(page, "<article>ok</article>");
+        let request = &server.requests()[0];
+        assert_eq!(request.lines[0], "POST /2024/day/6/answer HTTP/1.1");
+        assert_eq!(request.header("Cookie"), Some("session=abc123"));
+        assert_eq!(request.body, "level=2&answer=1234");
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let server = MockServer::start("404 Not Found", "not yet");
+        let error = fetcher(&server).fetch(6).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "couldn't fetch {}/2024/day/6/input: the server answered 404 Not Found",
+                server.url
+            )
+        );
+    }
+
+    #[test]
+    fn test_store_caches_what_it_fetches() {
+        let server = MockServer::start("200 OK", "125 17\n");
+        let dir = assert_fs::TempDir::new().unwrap();
+        let store = Store::new(dir.path().join("inputs")).with_fetcher(fetcher(&server));
+        let day = &DAYS[10];
+        assert!(!store.is_cached(day));
+        assert_eq!(store.get(day).unwrap(), "125 17\n");
+        assert!(store.is_cached(day));
+        assert_eq!(store.get(day).unwrap(), "125 17\n");
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("inputs/day11.txt")).unwrap(),
+            "125 17\n"
+        );
+        assert!(dir.path().join("inputs").join(STAMP_FILE).is_file());
+    }
+
+    #[test]
+    fn test_store_without_fetcher() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let store = Store::new(dir.path());
+        let error = store.get(&DAYS[0]).unwrap_err();
+        assert!(error.to_string().contains("day01.txt"));
+        fs::write(dir.path().join("day01.txt"), "1 2\n").unwrap();
+        assert_eq!(store.get(&DAYS[0]).unwrap(), "1 2\n");
+    }
+
+    #[test]
+    fn test_failed_fetch_is_not_cached() {
+        let server = MockServer::start("400 Bad Request", "log in");
+        let dir = assert_fs::TempDir::new().unwrap();
+        let store = Store::new(dir.path()).with_fetcher(fetcher(&server));
+        assert!(store.get(&DAYS[0]).is_err());
+        assert!(!store.is_cached(&DAYS[0]));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let server = MockServer::start("200 OK", "x");
+        let dir = assert_fs::TempDir::new().unwrap();
+        let stamp = dir.path().join(STAMP_FILE);
+        let limited = |server: &MockServer| {
+            let mut fetcher = fetcher(server).rate_limit(Duration::from_millis(300));
+            fetcher.stamp = Some(stamp.clone());
+            fetcher
+        };
+        let first = limited(&server);
+        let start = Instant::now();
+        first.fetch(1).unwrap();
+        first.fetch(2).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        // a new fetcher, as a later run would have, still waits for the stamp
+        let start = Instant::now();
+        limited(&server).fetch(3).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_read_session() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.path().join(SESSION_FILE);
+        assert_eq!(read_session(Some("env".into()), &file).unwrap(), "env");
+        let error = read_session(None, &file).unwrap_err();
+        assert!(matches!(error, Error::MissingSession { .. }));
+        fs::write(&file, "  token \n").unwrap();
+        assert_eq!(read_session(None, &file).unwrap(), "token");
+        fs::write(&file, "\n").unwrap();
+        assert!(read_session(None, &file).is_err());
+    }
+}
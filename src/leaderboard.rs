@@ -0,0 +1,493 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::days::read_input;
+use crate::error::Result;
+
+/// A private leaderboard as exported from the event's site, with the members
+/// in rank order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub event: String,
+    pub members: Vec<Member>,
+}
+
+/// A member and how they got on, each day's times counted from when the
+/// puzzle opened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub rank: usize,
+    pub id: u64,
+    /// members who haven't set a name show up without one
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    /// only the days with at least one star, in order
+    pub days: Vec<Times>,
+}
+
+/// How long a member took over the parts of a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Times {
+    pub day: u8,
+    pub part_one: Option<Duration>,
+    pub part_two: Option<Duration>,
+}
+
+// the export as it comes, the days and parts are keyed by their number
+#[derive(Deserialize)]
+struct Export {
+    event: Event,
+    /// when the first puzzle opened, left out of older exports
+    day1_ts: Option<u64>,
+    members: BTreeMap<String, ExportMember>,
+}
+
+#[derive(Deserialize)]
+struct ExportMember {
+    id: u64,
+    name: Option<String>,
+    stars: u32,
+    local_score: u64,
+    #[serde(default)]
+    last_star_ts: u64,
+    #[serde(default)]
+    completion_day_level: BTreeMap<Day, BTreeMap<u8, ExportStar>>,
+}
+
+/// The event the leaderboard is for, which is the year it ran
+#[derive(Deserialize)]
+#[serde(try_from = "String")]
+struct Event {
+    name: String,
+    year: u64,
+}
+
+impl TryFrom<String> for Event {
+    type Error = String;
+
+    fn try_from(name: String) -> std::result::Result<Self, String> {
+        match name.trim().parse() {
+            // the first event was in 2015
+            Ok(year @ 2015..=9999) => Ok(Self { name, year }),
+            _ => Err(format!("the event '{name}' isn't a year from 2015 on")),
+        }
+    }
+}
+
+/// A day of the event, there being one a day up to the 25th
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(try_from = "u8")]
+struct Day(u8);
+
+impl TryFrom<u8> for Day {
+    type Error = String;
+
+    fn try_from(day: u8) -> std::result::Result<Self, String> {
+        if (1..=25).contains(&day) {
+            Ok(Self(day))
+        } else {
+            Err(format!("there's no day {day}, they go from 1 to 25"))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ExportStar {
+    get_star_ts: u64,
+}
+
+impl Member {
+    /// the name, or what the site shows for a member without one
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+}
+
+impl Times {
+    /// how much longer the second part took than the first
+    pub fn delta(&self) -> Option<Duration> {
+        Some(self.part_two?.saturating_sub(self.part_one?))
+    }
+}
+
+impl Leaderboard {
+    pub fn parse(json: &str) -> Result<Self> {
+        let export: Export = serde_json::from_str(json)?;
+        let first = export
+            .day1_ts
+            .unwrap_or_else(|| first_unlock(export.event.year));
+        let mut members: Vec<(u64, Member)> = export
+            .members
+            .into_values()
+            .map(|m| {
+                let days = m
+                    .completion_day_level
+                    .iter()
+                    .map(|(&Day(day), parts)| {
+                        let opened = first + (day as u64 - 1) * 86400;
+                        let took = |part| {
+                            parts.get(&part).map(|star: &ExportStar| {
+                                Duration::from_secs(star.get_star_ts.saturating_sub(opened))
+                            })
+                        };
+                        Times {
+                            day,
+                            part_one: took(1),
+                            part_two: took(2),
+                        }
+                    })
+                    .collect();
+                let member = Member {
+                    rank: 0,
+                    id: m.id,
+                    name: m.name,
+                    stars: m.stars,
+                    local_score: m.local_score,
+                    days,
+                };
+                (m.last_star_ts, member)
+            })
+            .collect();
+        // the site breaks ties in favour of whoever got there first
+        members.sort_by_key(|(last, m)| {
+            (
+                std::cmp::Reverse(m.local_score),
+                std::cmp::Reverse(m.stars),
+                *last,
+                m.id,
+            )
+        });
+        let members = members
+            .into_iter()
+            .enumerate()
+            .map(|(i, (_, member))| Member {
+                rank: i + 1,
+                ..member
+            })
+            .collect();
+        Ok(Self {
+            event: export.event.name,
+            members,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = read_input(path)?;
+        Self::parse(&content).map_err(|e| e.in_file(path))
+    }
+}
+
+/// when the first puzzle of the event in `year` opened, midnight in the
+/// eastern US on the 1st of December
+fn first_unlock(year: u64) -> u64 {
+    days_from_civil(year, 12, 1) * 86400 + 5 * 3600
+}
+
+/// the days from 1970-01-01 to a date in the Gregorian calendar
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// a time as `h:mm:ss`, with the hours running past a day when they need to
+fn clock(time: Option<Duration>) -> String {
+    match time {
+        Some(time) => {
+            let s = time.as_secs();
+            format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+        }
+        None => "-".into(),
+    }
+}
+
+fn tsv_seconds(time: Option<Duration>) -> String {
+    time.map_or(String::new(), |t| t.as_secs().to_string())
+}
+
+/// the rankings, then each member's times day by day
+pub fn table(board: &Leaderboard) -> String {
+    let width = board
+        .members
+        .iter()
+        .map(|m| m.display_name().chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:>4}  {:<width$}  {:>5}  {:>5}",
+        "rank", "name", "stars", "score"
+    );
+    for member in &board.members {
+        let _ = writeln!(
+            out,
+            "{:>4}  {:<width$}  {:>5}  {:>5}",
+            member.rank,
+            member.display_name(),
+            member.stars,
+            member.local_score
+        );
+    }
+    for member in board.members.iter().filter(|m| !m.days.is_empty()) {
+        let _ = writeln!(out, "\n{}", member.display_name());
+        let _ = writeln!(
+            out,
+            "{:>5}  {:>10}  {:>10}  {:>10}",
+            "day", "part 1", "part 2", "delta"
+        );
+        for times in &member.days {
+            let _ = writeln!(
+                out,
+                "{:>5}  {:>10}  {:>10}  {:>10}",
+                times.day,
+                clock(times.part_one),
+                clock(times.part_two),
+                clock(times.delta())
+            );
+        }
+    }
+    out
+}
+
+// a member the way they're written out as JSON
+#[derive(Serialize)]
+struct JsonMember<'a> {
+    rank: usize,
+    id: u64,
+    name: Option<&'a str>,
+    stars: u32,
+    local_score: u64,
+    days: Vec<JsonTimes>,
+}
+
+#[derive(Serialize)]
+struct JsonTimes {
+    day: u8,
+    part_one_s: Option<u64>,
+    part_two_s: Option<u64>,
+    delta_s: Option<u64>,
+}
+
+/// a JSON object per member in rank order, times in seconds and null for a
+/// part that isn't done
+pub fn json_lines(board: &Leaderboard) -> String {
+    let seconds = |time: Option<Duration>| time.map(|t| t.as_secs());
+    let mut out = String::new();
+    for member in &board.members {
+        let json = JsonMember {
+            rank: member.rank,
+            id: member.id,
+            name: member.name.as_deref(),
+            stars: member.stars,
+            local_score: member.local_score,
+            days: member
+                .days
+                .iter()
+                .map(|t| JsonTimes {
+                    day: t.day,
+                    part_one_s: seconds(t.part_one),
+                    part_two_s: seconds(t.part_two),
+                    delta_s: seconds(t.delta()),
+                })
+                .collect(),
+        };
+        let json = serde_json::to_string(&json).expect("a member always serializes");
+        let _ = writeln!(out, "{json}");
+    }
+    out
+}
+
+/// a row per member and day under a header, times in seconds and left empty
+/// for a part that isn't done
+pub fn tsv(board: &Leaderboard) -> String {
+    let mut out =
+        String::from("rank\tid\tname\tstars\tlocal_score\tday\tpart_one_s\tpart_two_s\tdelta_s\n");
+    for member in &board.members {
+        let name = member.display_name().replace(['\t', '\n'], " ");
+        let row = format!(
+            "{}\t{}\t{name}\t{}\t{}",
+            member.rank, member.id, member.stars, member.local_score
+        );
+        if member.days.is_empty() {
+            let _ = writeln!(out, "{row}\t\t\t\t");
+        }
+        for times in &member.days {
+            let _ = writeln!(
+                out,
+                "{row}\t{}\t{}\t{}\t{}",
+                times.day,
+                tsv_seconds(times.part_one),
+                tsv_seconds(times.part_two),
+                tsv_seconds(times.delta())
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{Error, Location};
+
+    // day 1 of 2024 opened at 1733029200
+    const EXPORT: &str = r#"{
+  "owner_id": 1,
+  "event": "2024",
+  "members": {
+    "1": {
+      "id": 1, "name": "Alice", "stars": 3, "local_score": 10,
+      "global_score": 0, "last_star_ts": 1733115600,
+      "completion_day_level": {
+        "1": {
+          "1": {"get_star_ts": 1733029512, "star_index": 1},
+          "2": {"get_star_ts": 1733029780, "star_index": 2}
+        },
+        "2": {"1": {"get_star_ts": 1733126400, "star_index": 3}}
+      }
+    },
+    "2": {
+      "id": 2, "name": null, "stars": 2, "local_score": 10,
+      "global_score": 0, "last_star_ts": 1733030000,
+      "completion_day_level": {
+        "1": {
+          "1": {"get_star_ts": 1733029800, "star_index": 4},
+          "2": {"get_star_ts": 1733030000, "star_index": 5}
+        }
+      }
+    },
+    "3": {
+      "id": 3, "name": "Carol", "stars": 0, "local_score": 0,
+      "global_score": 0, "last_star_ts": 0, "completion_day_level": {}
+    }
+  }
+}"#;
+
+    fn secs(s: u64) -> Option<Duration> {
+        Some(Duration::from_secs(s))
+    }
+
+    #[test]
+    fn test_first_unlock() {
+        assert_eq!(first_unlock(2024), 1733029200);
+        assert_eq!(first_unlock(2015), 1448946000);
+    }
+
+    #[test]
+    fn test_parse() {
+        let board = Leaderboard::parse(EXPORT).unwrap();
+        assert_eq!(board.event, "2024");
+        let order: Vec<(usize, u64)> = board.members.iter().map(|m| (m.rank, m.id)).collect();
+        // alice and the anonymous member tie on score, alice has more stars
+        assert_eq!(order, vec![(1, 1), (2, 2), (3, 3)]);
+
+        let alice = &board.members[0];
+        assert_eq!(
+            alice.days,
+            vec![
+                Times {
+                    day: 1,
+                    part_one: secs(312),
+                    part_two: secs(580),
+                },
+                Times {
+                    day: 2,
+                    part_one: secs(10800),
+                    part_two: None,
+                },
+            ]
+        );
+        assert_eq!(alice.days[0].delta(), secs(268));
+        assert_eq!(alice.days[1].delta(), None);
+        assert_eq!(board.members[1].display_name(), "(anonymous user #2)");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Leaderboard::parse("{\n  \"event\": 2024\n}").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 15)));
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 15: invalid JSON, invalid type: integer `2024`, expected a string"
+        );
+
+        let export = |event: &str, day: &str| {
+            format!(
+                r#"{{"event": "{event}", "members": {{"1": {{
+                    "id": 1, "name": null, "stars": 1, "local_score": 1,
+                    "completion_day_level": {{"{day}": {{"1": {{"get_star_ts": 1}}}}}}
+                }}}}}}"#
+            )
+        };
+        assert!(Leaderboard::parse(&export("2024", "25")).is_ok());
+        for (event, day, reason) in [
+            ("2024", "0", "there's no day 0, they go from 1 to 25"),
+            ("2024", "26", "there's no day 26, they go from 1 to 25"),
+            ("1969", "1", "the event '1969' isn't a year from 2015 on"),
+            ("AoC", "1", "the event 'AoC' isn't a year from 2015 on"),
+        ] {
+            let error = Leaderboard::parse(&export(event, day)).unwrap_err();
+            assert!(matches!(error, Error::InvalidJson { .. }));
+            assert!(error.to_string().contains(reason), "{error}");
+        }
+    }
+
+    #[test]
+    fn test_table() {
+        let board = Leaderboard::parse(EXPORT).unwrap();
+        assert_eq!(
+            table(&board),
+            "\
+rank  name                 stars  score
+   1  Alice                    3     10
+   2  (anonymous user #2)      2     10
+   3  Carol                    0      0
+
+Alice
+  day      part 1      part 2       delta
+    1     0:05:12     0:09:40     0:04:28
+    2     3:00:00           -           -
+
+(anonymous user #2)
+  day      part 1      part 2       delta
+    1     0:10:00     0:13:20     0:03:20
+"
+        );
+    }
+
+    #[test]
+    fn test_json_and_tsv() {
+        let board = Leaderboard::parse(EXPORT).unwrap();
+        let json = json_lines(&board);
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!(
+            lines[0],
+            r#"{"rank":1,"id":1,"name":"Alice","stars":3,"local_score":10,"days":[{"day":1,"part_one_s":312,"part_two_s":580,"delta_s":268},{"day":2,"part_one_s":10800,"part_two_s":null,"delta_s":null}]}"#
+        );
+        assert_eq!(
+            lines[2],
+            r#"{"rank":3,"id":3,"name":"Carol","stars":0,"local_score":0,"days":[]}"#
+        );
+        assert!(lines[1].contains(r#""name":null"#));
+
+        let tsv = tsv(&board);
+        let rows: Vec<&str> = tsv.lines().collect();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[2], "1\t1\tAlice\t3\t10\t2\t10800\t\t");
+        assert_eq!(rows[4], "3\t3\tCarol\t0\t0\t\t\t\t");
+    }
+}
@@ -1 +1,25 @@
+pub mod answers;
+pub mod bench;
+#[cfg(test)]
+pub(crate) mod check;
+pub mod cli;
 pub mod days;
+pub mod error;
+pub mod generate;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod image;
+pub mod inputs;
+pub mod leaderboard;
+pub mod logging;
+pub mod math;
+pub mod memo;
+pub mod output;
+pub mod params;
+pub mod parse;
+pub mod runner;
+pub mod scaffold;
+pub mod solver;
+pub mod submit;
+pub mod visual;
@@ -0,0 +1,86 @@
+use std::io::{self, Write};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Writes log records to stderr so they never end up mixed in with the
+/// answers on stdout
+struct Stderr;
+
+impl Log for Stderr {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            // there's nowhere left to report a failure to write to stderr
+            let _ = writeln!(io::stderr().lock(), "{}", line(record));
+        }
+    }
+
+    fn flush(&self) {
+        let _ = io::stderr().flush();
+    }
+}
+
+/// the line written for `record`, the debugging levels saying which module it
+/// came from
+fn line(record: &Record) -> String {
+    let level = record.level().as_str().to_ascii_lowercase();
+    match record.level() {
+        Level::Debug | Level::Trace => format!("{level} [{}]: {}", record.target(), record.args()),
+        _ => format!("{level}: {}", record.args()),
+    }
+}
+
+/// the most detailed level shown for a verbosity, each `-v` adding one and
+/// each `-q` taking one away from showing warnings and errors
+pub fn filter(verbosity: i8) -> LevelFilter {
+    match verbosity {
+        ..=-2 => LevelFilter::Off,
+        -1 => LevelFilter::Error,
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        3.. => LevelFilter::Trace,
+    }
+}
+
+/// send everything logged at `verbosity` or above to stderr
+pub fn init(verbosity: i8) {
+    // only the first logger set is used, a second go just changes the level
+    let _ = log::set_logger(&Stderr);
+    log::set_max_level(filter(verbosity));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        assert_eq!(filter(-5), LevelFilter::Off);
+        assert_eq!(filter(-1), LevelFilter::Error);
+        assert_eq!(filter(0), LevelFilter::Warn);
+        assert_eq!(filter(2), LevelFilter::Debug);
+        assert_eq!(filter(7), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_line() {
+        let line = |level| {
+            super::line(
+                &Record::builder()
+                    .level(level)
+                    .target("aoc24::days::day2")
+                    .args(format_args!("unsafe: 1 2 7 8 9"))
+                    .build(),
+            )
+        };
+        assert_eq!(line(Level::Warn), "warn: unsafe: 1 2 7 8 9");
+        assert_eq!(
+            line(Level::Debug),
+            "debug [aoc24::days::day2]: unsafe: 1 2 7 8 9"
+        );
+    }
+}
@@ -1,49 +1,609 @@
-use aoc24::days::day1::day_one;
-use aoc24::days::day2::day_two;
-use aoc24::days::day3::day_three;
-use aoc24::days::day4::day_four;
-use aoc24::days::day5::day_five;
-use aoc24::days::day6::day_six;
-use aoc24::days::day7::day_seven;
-use aoc24::days::day8::day_eight;
-use aoc24::days::day9::day_nine;
-use aoc24::days::day10::day_ten;
-use aoc24::days::day11::day_eleven;
-use aoc24::days::day12::day_twelve;
-use aoc24::days::day13::day_thirteen;
-use aoc24::days::day14::day_fourteen;
+use aoc24::answers::{self, AnswerKey};
+use aoc24::bench;
+use aoc24::cli::{self, Cli, Command, Input};
+use aoc24::days::{self, Day};
+use aoc24::error::{Error, Result};
+use aoc24::image;
+use aoc24::inputs::{self, Fetcher, Store};
+use aoc24::leaderboard::{self, Leaderboard};
+use aoc24::logging;
+use aoc24::output::{self, Format, Record};
+use aoc24::params::{Config, Params};
+use aoc24::runner::{self, Outcome};
+use aoc24::scaffold;
+use aoc24::solver::{Answer, Part};
+use aoc24::submit::{self, History, Submission, Verdict};
+use aoc24::visual::{self, FrameRange, Frames};
 use std::env;
-use std::io;
-
-fn main() -> io::Result<()> {
-    let mut args = env::args();
-    let (Some(_), Some(day), Some(path)) = (args.next(), args.next(), args.next()) else {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "Expected day arg",
-        ));
-    };
-    match day.as_str() {
-        "day_one" => day_one(path)?,
-        "day_two" => day_two(path.as_str())?,
-        "day_three" => day_three(path.as_str())?,
-        "day_four" => day_four(path.as_str())?,
-        "day_five" => day_five(path.as_str())?,
-        "day_six" => day_six(path.as_str())?,
-        "day_seven" => day_seven(path.as_str())?,
-        "day_eight" => day_eight(path.as_str())?,
-        "day_nine" => day_nine(path.as_str())?,
-        "day_ten" => day_ten(path.as_str())?,
-        "day_eleven" => day_eleven(path.as_str())?,
-        "day_twelve" => day_twelve(path.as_str())?,
-        "day_thirteen" => day_thirteen(path.as_str())?,
-        "day_fourteen" => day_fourteen(path.as_str())?,
-        _ => {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Expected day_x",
-            ));
-        }
-    }
-    Ok(())
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// answer both parts, or just `part`, along with how long each took
+fn answer(
+    day: &Day,
+    content: &str,
+    part: Option<Part>,
+    params: &Params,
+) -> Result<Vec<(Part, Answer, Duration)>> {
+    match part {
+        None => day.solve_timed(content, params).map(|(answers, timings)| {
+            vec![
+                (Part::One, answers.part_one, timings.part_one),
+                (Part::Two, answers.part_two, timings.part_two),
+            ]
+        }),
+        Some(part) => day
+            .solve_part(content, part, params)
+            .map(|(answer, timings)| {
+                let elapsed = match part {
+                    Part::One => timings.part_one,
+                    Part::Two => timings.part_two,
+                };
+                vec![(part, answer, elapsed)]
+            }),
+    }
+}
+
+fn render(day: &Day, answers: &[(Part, Answer, Duration)], format: Format) -> String {
+    let records: Vec<Record> = answers
+        .iter()
+        .map(|(part, answer, elapsed)| Record {
+            day: day.number,
+            part: part.number(),
+            answer,
+            elapsed: *elapsed,
+        })
+        .collect();
+    output::render(format, &records)
+}
+
+/// solve each of the day's examples, failing if an answer isn't the one the
+/// puzzle gives. Overriding a parameter changes the answers so then nothing is
+/// checked
+fn examples(day: &Day, part: Option<Part>, params: &Params, format: Format) -> ExitCode {
+    if day.examples.is_empty() {
+        eprintln!("error: day {} has no examples", day.number);
+        return ExitCode::FAILURE;
+    }
+    let check = *params == Params::new();
+    let mut failed = false;
+    for (i, example) in day.examples.iter().enumerate() {
+        let name = format!("<example {}>", i + 1);
+        let answers = match answer(day, example.input, part, &example.params().merged(params)) {
+            Ok(answers) => answers,
+            Err(e) => {
+                eprintln!("{}", e.in_file(&name).diagnostic(example.input));
+                failed = true;
+                continue;
+            }
+        };
+        print!("{}", render(day, &answers, format));
+        for (part, answer, _) in &answers {
+            if let Some(expected) = example.expected(*part).filter(|_| check)
+                && answer.to_string() != expected
+            {
+                eprintln!(
+                    "day {} {name} part {}: expected {expected}, found {answer}",
+                    day.number,
+                    part.number()
+                );
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Where stored inputs are kept and how to fetch the ones that are missing
+struct Source {
+    data_dir: PathBuf,
+    base_url: String,
+    session_file: Option<PathBuf>,
+}
+
+impl Source {
+    /// the store for the data directory, able to fetch only when one of `days`
+    /// isn't there yet so cached inputs never need a session
+    fn store(&self, days: &[&Day]) -> Result<Store> {
+        let store = Store::new(&self.data_dir);
+        if days.iter().all(|day| store.is_cached(day)) {
+            return Ok(store);
+        }
+        Ok(store.with_fetcher(self.fetcher()?))
+    }
+
+    /// a fetcher for the server, with the session from the environment or
+    /// the session file
+    fn fetcher(&self) -> Result<Fetcher> {
+        let session_file = self
+            .session_file
+            .clone()
+            .unwrap_or_else(|| self.data_dir.join(inputs::SESSION_FILE));
+        let session = inputs::read_session(env::var(inputs::SESSION_VAR).ok(), &session_file)?;
+        let mut fetcher = Fetcher::new(&self.base_url, session);
+        if let Ok(user_agent) = env::var("AOC_USER_AGENT") {
+            fetcher = fetcher.user_agent(user_agent);
+        }
+        Ok(fetcher)
+    }
+}
+
+/// the puzzle text for `day` and the name to give it in errors. The examples
+/// aren't read here, there's more than one of them
+fn read(day: &Day, input: &Input, source: &Source) -> Result<(String, String)> {
+    match input {
+        Input::Example => unreachable!("the examples are read by their callers"),
+        Input::Stdin => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            Ok((input.to_string(), content))
+        }
+        Input::File(path) => Ok((input.to_string(), days::read_input(path)?)),
+        Input::Stored => {
+            let store = source.store(&[day])?;
+            Ok((store.path(day).display().to_string(), store.get(day)?))
+        }
+    }
+}
+
+/// solve a single day, or just one part of it
+fn solve(
+    day: &Day,
+    input: Input,
+    part: Option<Part>,
+    params: &Params,
+    source: &Source,
+    format: Format,
+) -> ExitCode {
+    if input == Input::Example {
+        return examples(day, part, params, format);
+    }
+    let (name, content) = match read(day, &input, source) {
+        Ok(read) => read,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match answer(day, &content, part, params) {
+        Ok(answers) => {
+            print!("{}", render(day, &answers, format));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e.in_file(name).diagnostic(&content));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// the frames of the day played out, the first example's when asked for one.
+/// `None` once whatever stopped it has been reported
+fn visualize(day: &Day, input: Input, params: &Params, source: &Source) -> Option<Frames> {
+    let (name, content, params) = match (&input, day.examples.first()) {
+        (Input::Example, None) => {
+            eprintln!("error: day {} has no examples", day.number);
+            return None;
+        }
+        (Input::Example, Some(example)) => (
+            "<example 1>".to_string(),
+            example.input.to_string(),
+            example.params().merged(params),
+        ),
+        _ => match read(day, &input, source) {
+            Ok((name, content)) => (name, content, params.clone()),
+            Err(e) => {
+                eprintln!("error: {e}");
+                return None;
+            }
+        },
+    };
+    match day.visualize(&content, &params) {
+        Ok(Some(frames)) => Some(frames),
+        Ok(None) => {
+            eprintln!("error: day {} has nothing to visualize", day.number);
+            None
+        }
+        Err(e) => {
+            eprintln!("{}", e.in_file(name).diagnostic(&content));
+            None
+        }
+    }
+}
+
+/// write the frames in `range` to `path`, a GIF playing at `fps` or images of
+/// `scale` pixels a cell
+fn export(frames: Frames, range: FrameRange, path: &Path, scale: usize, fps: f64) -> ExitCode {
+    let delay = visual::delay(fps);
+    match image::export(range.select(frames.enumerate()), scale, delay, path) {
+        Ok(written) => {
+            let frames = if written == 1 { "frame" } else { "frames" };
+            println!("wrote {written} {frames} to {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// write a random input for `day` to `output`, or stdout without one
+fn generate(day: &Day, seed: u64, sizes: &Params, output: Option<&Path>) -> ExitCode {
+    let input = match day.generate(seed, sizes) {
+        Ok(Some(input)) => input,
+        Ok(None) => {
+            eprintln!("error: day {} has no generator", day.number);
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let written = match output {
+        Some(path) => std::fs::write(path, input).map_err(|e| Error::from(e).in_file(path)),
+        None => io::stdout()
+            .write_all(input.as_bytes())
+            .map_err(Error::from),
+    };
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// solves every day that has an input. Plain output is a summary table, the
+/// other formats a record per answer
+fn run_all(dir: &Path, parallel: bool, config: &Config, format: Format) -> ExitCode {
+    let runs = runner::run_all(dir, parallel, config);
+    if format == Format::Plain {
+        print!("{}", runner::summary(&runs));
+    } else {
+        let mut records = Vec::new();
+        for run in &runs {
+            match &run.outcome {
+                Outcome::Solved { answers, timings } => {
+                    records.extend(output::records(run.day.number, answers, timings))
+                }
+                Outcome::Skipped { path } => {
+                    log::warn!("skipping day {}, no {}", run.day.number, path.display())
+                }
+                Outcome::Failed(e) => eprintln!("error: {e}"),
+            }
+        }
+        print!("{}", output::render(format, &records));
+    }
+    if runs.iter().any(|r| matches!(r.outcome, Outcome::Failed(_))) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// checks every day against the answers file, or with `record` writes the
+/// current answers to it instead
+fn verify(
+    dir: &Path,
+    answers_path: Option<PathBuf>,
+    record: bool,
+    parallel: bool,
+    config: &Config,
+) -> ExitCode {
+    let answers_path = answers_path.unwrap_or_else(|| dir.join("answers.txt"));
+    let mut key = match AnswerKey::load(&answers_path) {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let runs = runner::run_all(dir, parallel, config);
+    if record {
+        key.record(&runs);
+        if let Err(e) = key.save(&answers_path) {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+        let solved = runs
+            .iter()
+            .filter(|r| matches!(r.outcome, Outcome::Solved { .. }))
+            .count();
+        println!(
+            "recorded the answers of {solved} days to {}",
+            answers_path.display()
+        );
+        return ExitCode::SUCCESS;
+    }
+
+    let checks = answers::verify(&runs, &key);
+    print!("{}", answers::report(&checks));
+    if answers::has_failures(&checks) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// times the parse and each part of the days over many runs
+fn bench(
+    selected: Vec<&'static Day>,
+    dir: &Path,
+    iterations: usize,
+    warmup: usize,
+    config: &Config,
+    params: &Params,
+    format: Format,
+) -> ExitCode {
+    let mut benches = Vec::new();
+    let mut failed = false;
+    for day in selected {
+        let path = runner::input_path(dir, day);
+        if !path.is_file() {
+            log::warn!("skipping day {}, no {}", day.number, path.display());
+            continue;
+        }
+        let result = days::read_input(&path).and_then(|content| {
+            let params = config.day(day.number).merged(params);
+            bench::bench(day, &content, &params, warmup, iterations).map_err(|e| e.in_file(&path))
+        });
+        match result {
+            Ok(b) => benches.push(b),
+            Err(e) => {
+                eprintln!("error: {e}");
+                failed = true;
+            }
+        }
+    }
+    let out = match format {
+        Format::Plain => bench::table(&benches),
+        Format::Json => bench::json_lines(&benches),
+        Format::Tsv => bench::tsv(&benches),
+    };
+    print!("{out}");
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// make sure the data directory has the input of each day, fetching the ones
+/// it doesn't
+fn fetch(days: Vec<&'static Day>, source: &Source) -> ExitCode {
+    let store = match source.store(&days) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut failed = false;
+    for day in days {
+        let path = store.path(day);
+        if store.is_cached(day) {
+            println!("day {}: already in {}", day.number, path.display());
+            continue;
+        }
+        match store.get(day) {
+            Ok(_) => println!("day {}: fetched to {}", day.number, path.display()),
+            Err(e) => {
+                eprintln!("error: {e}");
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// send an answer for a part of a day, working it out from the stored input
+/// when it isn't given, and keep the attempt in the history
+fn submit(
+    day: &Day,
+    part: Part,
+    answer: Option<String>,
+    history_path: Option<PathBuf>,
+    params: &Params,
+    source: &Source,
+) -> ExitCode {
+    let history_path = history_path.unwrap_or_else(|| source.data_dir.join("history.txt"));
+    let result = History::load(&history_path).and_then(|mut history| {
+        let answer = match answer {
+            Some(answer) => answer,
+            None => {
+                let store = source.store(&[day])?;
+                let path = store.path(day);
+                let content = store.get(day)?;
+                day.solve_part(&content, part, params)
+                    .map_err(|e| e.in_file(&path))?
+                    .0
+                    .to_string()
+            }
+        };
+        // checked before anything needs a session
+        if let Some(refusal) = history.check(day.number, part.number(), &answer) {
+            return Ok((answer, Submission::Refused(refusal)));
+        }
+        let submission =
+            submit::submit(&source.fetcher()?, &mut history, day.number, part, &answer)?;
+        if let Submission::Sent(_) = submission {
+            history.save(&history_path)?;
+        }
+        Ok((answer, submission))
+    });
+    let label = format!("day {} part {}", day.number, part.number());
+    match result {
+        Ok((answer, Submission::Sent(verdict))) => {
+            println!("{label}: {answer} was {verdict}");
+            if verdict == Verdict::Correct {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Ok((answer, Submission::Refused(refusal))) => {
+            eprintln!("{label}: not sending {answer}, {refusal}");
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// the rankings and times of a private leaderboard export
+fn leaderboard(path: &Path, format: Format) -> ExitCode {
+    let content = match days::read_input(path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let board = match Leaderboard::parse(&content) {
+        Ok(board) => board,
+        Err(e) => {
+            eprintln!("{}", e.in_file(path).diagnostic(&content));
+            return ExitCode::FAILURE;
+        }
+    };
+    let out = match format {
+        Format::Plain => leaderboard::table(&board),
+        Format::Json => leaderboard::json_lines(&board),
+        Format::Tsv => leaderboard::tsv(&board),
+    };
+    print!("{out}");
+    ExitCode::SUCCESS
+}
+
+/// start a new day from the template
+fn new_day(day: u8, root: &Path) -> ExitCode {
+    match scaffold::new_day(root, day) {
+        Ok(written) => {
+            for path in written {
+                println!("wrote {}", path.display());
+            }
+            println!(
+                "day {day} is registered as {}, its test fails until it's solved",
+                scaffold::day_name(day)
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let Cli {
+        format,
+        config,
+        params,
+        data_dir,
+        base_url,
+        session_file,
+        verbosity,
+        command,
+    } = match cli::parse(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    logging::init(verbosity);
+    let config = match config.map(Config::load).transpose() {
+        Ok(config) => config.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let source = Source {
+        data_dir,
+        base_url,
+        session_file,
+    };
+    match command {
+        Command::Solve { day, input, part } => {
+            let params = config.day(day.number).merged(&params);
+            solve(day, input, part, &params, &source, format)
+        }
+        Command::Visualize {
+            day,
+            input,
+            fps,
+            paused,
+            frames: range,
+            export: path,
+            scale,
+        } => {
+            let params = config.day(day.number).merged(&params);
+            match (visualize(day, input, &params, &source), path) {
+                (None, _) => ExitCode::FAILURE,
+                (Some(frames), Some(path)) => export(frames, range, &path, scale, fps),
+                (Some(frames), None) => {
+                    match visual::play(Box::new(range.select(frames)), fps, paused) {
+                        Ok(_) => ExitCode::SUCCESS,
+                        Err(e) => {
+                            eprintln!("error: {e}");
+                            ExitCode::FAILURE
+                        }
+                    }
+                }
+            }
+        }
+        Command::Generate {
+            day,
+            seed,
+            sizes,
+            output,
+        } => generate(day, seed, &sizes, output.as_deref()),
+        Command::RunAll { dir, parallel } => run_all(&dir, parallel, &config, format),
+        Command::Verify {
+            dir,
+            answers,
+            record,
+            parallel,
+        } => verify(&dir, answers, record, parallel, &config),
+        Command::Bench {
+            days,
+            dir,
+            iterations,
+            warmup,
+        } => bench(days, &dir, iterations, warmup, &config, &params, format),
+        Command::Fetch { days } => fetch(days, &source),
+        Command::Submit {
+            day,
+            part,
+            answer,
+            history,
+        } => {
+            let params = config.day(day.number).merged(&params);
+            submit(day, part, answer, history, &params, &source)
+        }
+        Command::Leaderboard { path } => leaderboard(&path, format),
+        Command::NewDay { day, root } => new_day(day, &root),
+        Command::Help => {
+            print!("{}", cli::usage());
+            ExitCode::SUCCESS
+        }
+    }
 }
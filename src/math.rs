@@ -0,0 +1,426 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// the greatest common divisor, never negative. `gcd(0, 0)` is 0
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a as i64
+}
+
+/// the least common multiple, never negative. `None` if it doesn't fit
+pub fn lcm(a: i64, b: i64) -> Option<i64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b).map(i64::abs)
+}
+
+/// `a / b` rounded down rather than towards zero
+pub fn div_floor(a: i64, b: i64) -> i64 {
+    let (q, r) = (a / b, a % b);
+    if r != 0 && (r < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+/// `a / b` rounded up rather than towards zero
+pub fn div_ceil(a: i64, b: i64) -> i64 {
+    let (q, r) = (a / b, a % b);
+    if r != 0 && (r < 0) == (b < 0) {
+        q + 1
+    } else {
+        q
+    }
+}
+
+/// `(g, x, y)` where `g` is the gcd of `a` and `b` and `a * x + b * y == g`
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// the `x` in `0..m` with `a * x` one more than a multiple of `m`, `None` when
+/// `a` and `m` share a factor
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// the smallest `x >= 0` leaving each remainder after dividing by its modulus,
+/// along with the modulus it repeats after. `None` when no number fits them all
+/// or the answer doesn't fit in an `i64`. The moduli don't have to be coprime
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut x, mut m) = (0i128, 1i128);
+    for &(r, n) in congruences {
+        let (r, n) = (r as i128, n as i128);
+        // x + m * k == r (mod n) has a k when gcd(m, n) divides the difference
+        let g = gcd(m.try_into().ok()?, n.try_into().ok()?) as i128;
+        if (r - x) % g != 0 {
+            return None;
+        }
+        let step = n / g;
+        let inverse = mod_inverse((m / g).try_into().ok()?, step.try_into().ok()?)? as i128;
+        let k = ((r - x) / g % step * inverse).rem_euclid(step);
+        x += m * k;
+        m *= step;
+        x = x.rem_euclid(m);
+        if m > i64::MAX as i128 {
+            return None;
+        }
+    }
+    Some((x as i64, m as i64))
+}
+
+/// A fraction kept in lowest terms with a positive denominator, so two equal
+/// values always have the same parts. The operators panic on overflow, the
+/// `checked_` versions give `None`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational::integer(0);
+    pub const ONE: Rational = Rational::integer(1);
+
+    /// `numerator / denominator`, `None` if the denominator is zero
+    pub fn new(numerator: i64, denominator: i64) -> Option<Self> {
+        Self::reduce(numerator as i128, denominator as i128)
+    }
+
+    pub const fn integer(n: i64) -> Self {
+        Self {
+            numerator: n,
+            denominator: 1,
+        }
+    }
+
+    fn reduce(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let sign = denominator.signum();
+        let (mut a, mut b) = (numerator.unsigned_abs(), denominator.unsigned_abs());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        let g = a.max(1) as i128;
+        Some(Self {
+            numerator: (sign * numerator / g).try_into().ok()?,
+            denominator: (sign * denominator / g).try_into().ok()?,
+        })
+    }
+
+    pub fn numerator(self) -> i64 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i64 {
+        self.denominator
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denominator == 1
+    }
+
+    /// the value as a whole number, `None` if it has a fractional part
+    pub fn to_integer(self) -> Option<i64> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.1 + b.0 * a.1, a.1 * b.1)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.1 - b.0 * a.1, a.1 * b.1)
+    }
+
+    /// `None` for the most negative numerator, which has no positive to match
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.0, a.1 * b.1)
+    }
+
+    /// `None` when dividing by zero as well as on overflow
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        let (a, b) = (self.wide(), other.wide());
+        Self::reduce(a.0 * b.1, a.1 * b.0)
+    }
+
+    // both parts of an i64 multiply into an i128 without overflowing
+    fn wide(self) -> (i128, i128) {
+        (self.numerator as i128, self.denominator as i128)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self::integer(n)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            d => write!(f, "{}/{d}", self.numerator),
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.wide(), other.wide());
+        (a.0 * b.1).cmp(&(b.0 * a.1))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("rational negation overflowed")
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other)
+            .expect("rational addition overflowed")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other)
+            .expect("rational subtraction overflowed")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(other)
+            .expect("rational multiplication overflowed")
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        self.checked_div(other)
+            .expect("rational division by zero or overflow")
+    }
+}
+
+/// Why a system of equations couldn't be solved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    /// a value didn't fit in an `i64` along the way
+    Overflow,
+    /// the determinant is zero, so there's no single answer
+    Singular,
+}
+
+/// A square matrix of integers, row by row
+pub type Matrix<const N: usize> = [[i64; N]; N];
+
+/// the determinant, worked out exactly with Bareiss elimination which keeps
+/// every step a whole number
+pub fn determinant<const N: usize>(matrix: &Matrix<N>) -> Result<i64, MathError> {
+    let mut m = matrix.map(|row| row.map(i128::from));
+    let mut sign = 1;
+    let mut previous = 1;
+    for k in 0..N {
+        if m[k][k] == 0 {
+            // swap in a row below with something in this column
+            let Some(swap) = (k + 1..N).find(|&i| m[i][k] != 0) else {
+                return Ok(0);
+            };
+            m.swap(k, swap);
+            sign = -sign;
+        }
+        for i in k + 1..N {
+            for j in k + 1..N {
+                let value = m[i][j]
+                    .checked_mul(m[k][k])
+                    .zip(m[i][k].checked_mul(m[k][j]))
+                    .and_then(|(a, b)| a.checked_sub(b))
+                    .ok_or(MathError::Overflow)?;
+                // Bareiss guarantees this divides exactly
+                m[i][j] = value / previous;
+                i64::try_from(m[i][j]).map_err(|_| MathError::Overflow)?;
+            }
+        }
+        previous = m[k][k];
+    }
+    let det = if N == 0 { 1 } else { sign * m[N - 1][N - 1] };
+    i64::try_from(det).map_err(|_| MathError::Overflow)
+}
+
+/// the `x` with `matrix * x == b`, exactly, by Cramer's rule
+pub fn solve<const N: usize>(matrix: &Matrix<N>, b: &[i64; N]) -> Result<[Rational; N], MathError> {
+    let det = determinant(matrix)?;
+    if det == 0 {
+        return Err(MathError::Singular);
+    }
+    let mut x = [Rational::ZERO; N];
+    for (column, x) in x.iter_mut().enumerate() {
+        let mut replaced = *matrix;
+        for (row, value) in replaced.iter_mut().zip(b) {
+            row[column] = *value;
+        }
+        *x = Rational::new(determinant(&replaced)?, det).ok_or(MathError::Overflow)?;
+    }
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(-4, 6), Some(12));
+        assert_eq!(lcm(101, 103), Some(10403));
+        assert_eq!(lcm(i64::MAX, i64::MAX - 1), None);
+    }
+
+    #[test]
+    fn test_div_rounding() {
+        assert_eq!(div_floor(7, 2), 3);
+        assert_eq!(div_floor(-7, 2), -4);
+        assert_eq!(div_floor(7, -2), -4);
+        assert_eq!(div_floor(-6, 2), -3);
+        assert_eq!(div_ceil(7, 2), 4);
+        assert_eq!(div_ceil(-7, 2), -3);
+        assert_eq!(div_ceil(-7, -2), 4);
+        assert_eq!(div_ceil(6, 2), 3);
+    }
+
+    #[test]
+    fn test_extended_gcd_and_inverse() {
+        for (a, b) in [(240, 46), (-240, 46), (17, 5), (0, 7), (94, 22)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        let (x, m) = crt(&[(68, 101), (19, 103)]).unwrap();
+        assert_eq!((x % 101, x % 103, m), (68, 19, 10403));
+    }
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(2, 4).unwrap();
+        assert_eq!(half, Rational::new(-1, -2).unwrap());
+        assert_eq!((half.numerator(), half.denominator()), (1, 2));
+        assert_eq!(Rational::new(1, 0), None);
+        let third = Rational::new(1, 3).unwrap();
+        assert_eq!(half + third, Rational::new(5, 6).unwrap());
+        assert_eq!(half - third, Rational::new(1, 6).unwrap());
+        assert_eq!(half * third, Rational::new(1, 6).unwrap());
+        assert_eq!(half / third, Rational::new(3, 2).unwrap());
+        assert_eq!((third * Rational::integer(3)).to_integer(), Some(1));
+        assert_eq!(half.to_integer(), None);
+        assert!(third < half && -half < third);
+        assert_eq!(Rational::new(-3, 6).unwrap().to_string(), "-1/2");
+        assert_eq!(half.checked_div(Rational::ZERO), None);
+        let big = Rational::integer(i64::MAX);
+        assert_eq!(big.checked_mul(big), None);
+        assert_eq!(big.checked_div(big), Some(Rational::ONE));
+        let least = Rational::integer(i64::MIN);
+        assert_eq!(least.checked_neg(), None);
+        assert_eq!(-big, Rational::integer(-i64::MAX));
+        assert_eq!(least.checked_sub(Rational::integer(-1)), Some(-big));
+        assert_eq!(Rational::ZERO.checked_sub(least), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "rational negation overflowed")]
+    fn test_rational_neg_overflow() {
+        let _ = -Rational::integer(i64::MIN);
+    }
+
+    #[test]
+    fn test_determinant() {
+        assert_eq!(determinant(&[[94, 22], [34, 67]]), Ok(5550));
+        assert_eq!(determinant(&[[1, 2], [2, 4]]), Ok(0));
+        assert_eq!(determinant(&[[0, 1], [1, 0]]), Ok(-1));
+        assert_eq!(determinant(&[[2, -3, 1], [2, 0, -1], [1, 4, 5]]), Ok(49));
+        assert_eq!(determinant(&[[0, 1, 2], [0, 3, 4], [5, 6, 7]]), Ok(-10));
+        let big = i64::MAX / 2;
+        assert_eq!(
+            determinant(&[[big, 1], [-1, big]]),
+            Err(MathError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_solve() {
+        let x = solve(&[[94, 22], [34, 67]], &[8400, 5400]).unwrap();
+        assert_eq!(x, [Rational::integer(80), Rational::integer(40)]);
+        let x = solve(&[[2, 1], [1, 3]], &[1, 1]).unwrap();
+        assert_eq!(
+            x,
+            [Rational::new(2, 5).unwrap(), Rational::new(1, 5).unwrap()]
+        );
+        assert_eq!(solve(&[[1, 2], [2, 4]], &[1, 1]), Err(MathError::Singular));
+    }
+}
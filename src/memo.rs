@@ -0,0 +1,139 @@
+use std::fmt;
+use std::hash::Hash;
+
+use ahash::AHashMap;
+
+/// A cache for a recursive function, keyed on whatever its arguments are.
+/// The function takes the memo along with it so the calls it makes further
+/// down are cached too, see the stairs in the tests or day 11's `blink_n`
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: AHashMap<K, V>,
+    stats: Stats,
+}
+
+/// How often a memo already knew the answer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: AHashMap::new(),
+            stats: Stats::default(),
+        }
+    }
+
+    /// the value for `key`, running `compute` to work it out the first time
+    /// it's asked for
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// how many values are cached
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// forget every value and start the counts again
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.stats = Stats::default();
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for Memo<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cache == other.cache && self.stats == other.stats
+    }
+}
+
+impl Stats {
+    /// the share of lookups that were already cached, 0 before any
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            100.0 * self.hit_rate()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the ways to climb n stairs taking one or two at a time
+    fn stairs(n: u32, memo: &mut Memo<u32, u64>) -> u64 {
+        if n < 2 {
+            return 1;
+        }
+        memo.get_or_compute(n, |memo| stairs(n - 1, memo) + stairs(n - 2, memo))
+    }
+
+    #[test]
+    fn test_recursive_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(stairs(10, &mut memo), 89);
+        assert_eq!(memo.len(), 9);
+        assert_eq!(memo.stats(), Stats { hits: 7, misses: 9 });
+        assert_eq!(stairs(10, &mut memo), 89);
+        assert_eq!(memo.stats().hits, 8);
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), Stats::default());
+    }
+
+    #[test]
+    fn test_tuple_keys() {
+        // keys can be anything hashable, here a string and a count
+        let mut memo: Memo<(String, usize), String> = Memo::new();
+        let mut repeat = |s: &str, n| memo.get_or_compute((s.into(), n), |_| s.repeat(n));
+        assert_eq!(repeat("ab", 3), "ababab");
+        assert_eq!(repeat("ab", 3), "ababab");
+        assert_eq!(repeat("ab", 2), "abab");
+        assert_eq!(memo.stats(), Stats { hits: 1, misses: 2 });
+    }
+
+    #[test]
+    fn test_stats_display() {
+        assert_eq!(Stats::default().hit_rate(), 0.0);
+        let stats = Stats { hits: 3, misses: 1 };
+        assert_eq!(stats.to_string(), "3 hits, 1 misses (75.0% hit rate)");
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::solver::{Answer, Answers, Timings};
+
+/// How answers are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// just the answers, one per line
+    #[default]
+    Plain,
+    /// a JSON object per line
+    Json,
+    /// tab separated rows under a header
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Format::Plain),
+            "json" => Ok(Format::Json),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(format!("unknown format '{s}', expected plain, json or tsv")),
+        }
+    }
+}
+
+/// A single answer along with where it came from and how long it took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'a> {
+    pub day: u8,
+    pub part: u8,
+    pub answer: &'a Answer,
+    pub elapsed: Duration,
+}
+
+/// the records for both parts of a day
+pub fn records<'a>(day: u8, answers: &'a Answers, timings: &Timings) -> [Record<'a>; 2] {
+    [
+        Record {
+            day,
+            part: 1,
+            answer: &answers.part_one,
+            elapsed: timings.part_one,
+        },
+        Record {
+            day,
+            part: 2,
+            answer: &answers.part_two,
+            elapsed: timings.part_two,
+        },
+    ]
+}
+
+// a record the way it's written out as JSON
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    day: u8,
+    part: u8,
+    answer: &'a Answer,
+    elapsed_us: f64,
+}
+
+/// render the records in the given format, every line ends in a newline
+pub fn render(format: Format, records: &[Record]) -> String {
+    let mut out = String::new();
+    match format {
+        Format::Plain => {
+            for record in records {
+                let _ = writeln!(out, "{}", record.answer);
+            }
+        }
+        Format::Json => {
+            for record in records {
+                let json = JsonRecord {
+                    day: record.day,
+                    part: record.part,
+                    answer: record.answer,
+                    elapsed_us: record.elapsed.as_secs_f64() * 1e6,
+                };
+                let json = serde_json::to_string(&json).expect("a record always serializes");
+                let _ = writeln!(out, "{json}");
+            }
+        }
+        Format::Tsv => {
+            out.push_str("day\tpart\tanswer\telapsed_us\n");
+            for record in records {
+                // a tab or newline in an answer would break the row
+                let answer = record.answer.to_string().replace(['\t', '\n'], " ");
+                let _ = writeln!(
+                    out,
+                    "{}\t{}\t{answer}\t{:.3}",
+                    record.day,
+                    record.part,
+                    record.elapsed.as_secs_f64() * 1e6,
+                );
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("xml".parse::<Format>().unwrap_err().contains("'xml'"));
+    }
+
+    #[test]
+    fn test_render() {
+        let one = Answer::Number(11);
+        let two = Answer::Text("a\"b".into());
+        let records = [
+            Record {
+                day: 1,
+                part: 1,
+                answer: &one,
+                elapsed: Duration::from_micros(5),
+            },
+            Record {
+                day: 1,
+                part: 2,
+                answer: &two,
+                elapsed: Duration::from_nanos(1500),
+            },
+        ];
+        assert_eq!(render(Format::Plain, &records), "11\na\"b\n");
+        assert_eq!(
+            render(Format::Json, &records),
+            "{\"day\":1,\"part\":1,\"answer\":11,\"elapsed_us\":5.0}\n\
+             {\"day\":1,\"part\":2,\"answer\":\"a\\\"b\",\"elapsed_us\":1.5}\n"
+        );
+        assert_eq!(
+            render(Format::Tsv, &records),
+            "day\tpart\tanswer\telapsed_us\n1\t1\t11\t5.000\n1\t2\ta\"b\t1.500\n"
+        );
+    }
+}
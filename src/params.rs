@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::days::{DAYS, read_input};
+use crate::error::{Error, Location, Result};
+
+/// A tunable constant a day declares, along with the value the puzzle uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub help: &'static str,
+}
+
+/// Named values given for a day's parameters. Anything not given falls back to
+/// the default the day declares
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(name.into(), value.into());
+    }
+
+    /// `self` with every value in `other` taking precedence
+    pub fn merged(&self, other: &Params) -> Params {
+        let mut values = self.values.clone();
+        values.extend(other.values.clone());
+        Params { values }
+    }
+
+    /// check every value is one the day declares and fill in the defaults for
+    /// the rest
+    pub fn resolve(&self, declared: &[Param]) -> Result<Params> {
+        if let Some(name) = self
+            .values
+            .keys()
+            .find(|name| !declared.iter().any(|p| p.name == name.as_str()))
+        {
+            return Err(Error::UnknownParam {
+                name: name.clone(),
+                known: declared.iter().map(|p| p.name).collect(),
+            });
+        }
+        let mut resolved = self.clone();
+        for param in declared {
+            resolved
+                .values
+                .entry(param.name.to_string())
+                .or_insert_with(|| param.default.to_string());
+        }
+        Ok(resolved)
+    }
+
+    /// the value of `name` read as a `T`
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<T> {
+        let value = self.values.get(name).ok_or_else(|| Error::UnknownParam {
+            name: name.to_string(),
+            known: Vec::new(),
+        })?;
+        value.parse().map_err(|_| Error::InvalidParam {
+            name: name.to_string(),
+            value: value.clone(),
+            expected: std::any::type_name::<T>()
+                .rsplit("::")
+                .next()
+                .unwrap_or("a value"),
+        })
+    }
+
+    /// the value of `name` as a number, checked to be in `range`
+    pub fn get_within(&self, name: &str, range: RangeInclusive<u64>) -> Result<u64> {
+        let value = self.get(name)?;
+        if range.contains(&value) {
+            Ok(value)
+        } else {
+            Err(Error::OutOfRange {
+                name: name.to_string(),
+                value,
+                min: *range.start(),
+                max: *range.end(),
+            })
+        }
+    }
+
+    /// read `name=value`, as given to `--param`
+    pub fn parse_assignment(s: &str) -> Option<(&str, &str)> {
+        let (name, value) = s.split_once('=')?;
+        let (name, value) = (name.trim(), value.trim());
+        (!name.is_empty()).then_some((name, value))
+    }
+}
+
+/// Parameters for many days, as read from a config file. The file has a section
+/// per day holding `name = value` lines:
+///
+/// ```text
+/// # the example grid is smaller
+/// [day14]
+/// width = 11
+/// height = 7
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    days: BTreeMap<u8, Params>,
+}
+
+impl Config {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut days: BTreeMap<u8, Params> = BTreeMap::new();
+        let mut current = None;
+        for line in input.lines() {
+            let content = line.split('#').next().unwrap_or_default().trim();
+            if content.is_empty() {
+                continue;
+            }
+            if let Some(section) = content.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                let name = section.trim();
+                let number = name.strip_prefix("day").unwrap_or(name);
+                let number = number.strip_prefix('_').unwrap_or(number);
+                let day = DAYS
+                    .iter()
+                    .find(|d| d.name == name || number.parse() == Ok(d.number))
+                    .ok_or_else(|| {
+                        Error::malformed(Location::of(input, line), "a section like [day14]")
+                    })?;
+                current = Some(day.number);
+                continue;
+            }
+            let Some(day) = current else {
+                return Err(Error::MissingSection {
+                    location: Location::of(input, line),
+                    section: "[dayN] header before the first parameter",
+                });
+            };
+            let (name, value) = Params::parse_assignment(content).ok_or_else(|| {
+                Error::malformed(Location::of(input, line), "a parameter like blinks = 25")
+            })?;
+            days.entry(day).or_default().set(name, value);
+        }
+        Ok(Self { days })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = read_input(path)?;
+        Self::parse(&content).map_err(|e| e.in_file(path))
+    }
+
+    /// the parameters given for `day`, empty if it has no section
+    pub fn day(&self, day: u8) -> Params {
+        self.days.get(&day).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECLARED: &[Param] = &[
+        Param {
+            name: "blinks",
+            default: "75",
+            help: "",
+        },
+        Param {
+            name: "bandwidth",
+            default: "2.0",
+            help: "",
+        },
+    ];
+
+    #[test]
+    fn test_resolve_and_get() {
+        let mut params = Params::new();
+        params.set("blinks", "25");
+        let resolved = params.resolve(DECLARED).unwrap();
+        assert_eq!(resolved.get::<u32>("blinks").unwrap(), 25);
+        assert_eq!(resolved.get::<f64>("bandwidth").unwrap(), 2.0);
+
+        params.set("blinkz", "1");
+        let error = params.resolve(DECLARED).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown parameter 'blinkz', expected one of blinks, bandwidth"
+        );
+
+        params = Params::new();
+        params.set("blinks", "lots");
+        let error = params.resolve(DECLARED).unwrap().get::<u32>("blinks");
+        assert!(matches!(error, Err(Error::InvalidParam { .. })));
+    }
+
+    #[test]
+    fn test_get_within() {
+        let mut params = Params::new();
+        params.set("blinks", "25");
+        let resolved = params.resolve(DECLARED).unwrap();
+        assert_eq!(resolved.get_within("blinks", 0..=75).unwrap(), 25);
+        let error = resolved.get_within("blinks", 0..=10).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "parameter 'blinks' is 25, it has to be from 0 to 10"
+        );
+    }
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(
+            Params::parse_assignment("blinks=25"),
+            Some(("blinks", "25"))
+        );
+        assert_eq!(Params::parse_assignment("=25"), None);
+        assert_eq!(Params::parse_assignment("blinks"), None);
+    }
+
+    #[test]
+    fn test_config() {
+        let config = Config::parse("# examples\n[day14]\nwidth = 11\n\n[11]\nblinks=6\n").unwrap();
+        assert_eq!(config.day(14).get::<u32>("width").unwrap(), 11);
+        assert_eq!(config.day(11).get::<u32>("blinks").unwrap(), 6);
+        assert_eq!(config.day(1), Params::new());
+
+        let error = Config::parse("blinks = 6\n").unwrap_err();
+        assert!(matches!(error, Error::MissingSection { .. }));
+        let error = Config::parse("[day99]\n").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(1, 1)));
+        let error = Config::parse("[day11]\nblinks\n").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 1)));
+    }
+}
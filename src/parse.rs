@@ -0,0 +1,154 @@
+use std::str::FromStr;
+
+use crate::error::{Error, Location, Result};
+use crate::grid::Grid;
+
+// Every helper takes the whole `input` along with the piece being parsed, which
+// has to be a slice of it, so that errors can say where in the input they are
+
+/// read `token` as a `T`, pointing at it when it isn't one
+fn value<T: FromStr>(input: &str, token: &str, expected: &str) -> Result<T> {
+    token
+        .parse()
+        .map_err(|_| Error::malformed(Location::of(input, token), expected))
+}
+
+/// the whitespace separated values in `text`, i.e. the levels of a day 2 report
+pub fn numbers<T: FromStr>(input: &str, text: &str, expected: &str) -> Result<Vec<T>> {
+    text.split_whitespace()
+        .map(|token| value(input, token, expected))
+        .collect()
+}
+
+/// every integer in `text`, with its sign, ignoring whatever is around them. The
+/// 94 and 34 of `Button A: X+94, Y+34` or the 3 and -3 of `v=3,-3`
+pub fn integers<T: FromStr>(input: &str, text: &str) -> Result<Vec<T>> {
+    let bytes = text.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let signed =
+            matches!(bytes[i], b'+' | b'-') && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+        if !signed && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        found.push(value(input, &text[start..i], "a number that fits")?);
+    }
+    Ok(found)
+}
+
+/// the blocks of lines that blank lines separate, each without its last line
+/// break. Windows line endings are fine
+pub fn sections(input: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            if let Some(start) = start.take() {
+                sections.push(&input[start..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + content.len();
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        sections.push(&input[start..end]);
+    }
+    sections
+}
+
+/// the characters of `input` as a grid, every row as wide as the first
+pub fn char_grid(input: &str) -> Result<Grid<char>> {
+    input.parse()
+}
+
+/// a line like `190: 10 19`, the key before the colon and the whitespace
+/// separated values after it
+pub fn key_values<K: FromStr, V: FromStr>(
+    input: &str,
+    line: &str,
+    expected: &str,
+) -> Result<(K, Vec<V>)> {
+    let (key, values) = line
+        .split_once(':')
+        .ok_or_else(|| Error::malformed(Location::of(input, line), expected))?;
+    Ok((
+        value(input, key.trim(), expected)?,
+        numbers(input, values, expected)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        let input = "7 6 4\n1 x 3\n";
+        let mut lines = input.lines();
+        assert_eq!(
+            numbers::<u8>(input, lines.next().unwrap(), "a level").unwrap(),
+            vec![7, 6, 4]
+        );
+        let error = numbers::<u8>(input, lines.next().unwrap(), "a level").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 3)));
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: malformed line, expected a level"
+        );
+    }
+
+    #[test]
+    fn test_integers() {
+        let input = "Button A: X+94, Y-34\np=0,4 v=3,-3\nv=300,-3";
+        let lines: Vec<&str> = input.lines().collect();
+        assert_eq!(integers::<i64>(input, lines[0]).unwrap(), vec![94, -34]);
+        assert_eq!(integers::<i64>(input, lines[1]).unwrap(), vec![0, 4, 3, -3]);
+        assert_eq!(
+            integers::<i64>(input, "no numbers - here").unwrap(),
+            Vec::<i64>::new()
+        );
+        let error = integers::<i8>(input, lines[2]).unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(3, 3)));
+    }
+
+    #[test]
+    fn test_sections() {
+        assert_eq!(sections("a\nb\n\nc\n"), vec!["a\nb", "c"]);
+        assert_eq!(sections("a\r\nb\r\n\r\n\r\nc"), vec!["a\r\nb", "c"]);
+        assert_eq!(sections("\n  \na\n"), vec!["a"]);
+        assert!(sections("").is_empty());
+        let input = "a\n\nb";
+        assert_eq!(Location::of(input, sections(input)[1]), Location::new(3, 1));
+    }
+
+    #[test]
+    fn test_key_values() {
+        let input = "190: 10 19\n83 17 5\n21: 1 x\n";
+        let lines: Vec<&str> = input.lines().collect();
+        let (key, values): (u64, Vec<u64>) = key_values(input, lines[0], "an equation").unwrap();
+        assert_eq!((key, values), (190, vec![10, 19]));
+        let error = key_values::<u64, u64>(input, lines[1], "an equation").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(2, 1)));
+        let error = key_values::<u64, u64>(input, lines[2], "an equation").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(3, 7)));
+    }
+
+    #[test]
+    fn test_char_grid() {
+        let grid = char_grid("ab\ncd\n").unwrap();
+        assert_eq!(grid[(1, 1)], 'd');
+        assert!(char_grid("ab\nc\n").is_err());
+    }
+}
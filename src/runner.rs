@@ -0,0 +1,198 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::unbounded;
+use crossbeam_utils::thread::scope;
+
+use crate::days::{DAYS, Day, read_input};
+use crate::error::Error;
+use crate::params::Config;
+use crate::solver::{Answers, Timings};
+
+/// What happened when a day was run
+#[derive(Debug)]
+pub enum Outcome {
+    Solved {
+        answers: Answers,
+        timings: Timings,
+    },
+    /// there was no input for the day so it wasn't run
+    Skipped {
+        path: PathBuf,
+    },
+    Failed(Error),
+}
+
+/// The result of running a single day
+#[derive(Debug)]
+pub struct Run {
+    pub day: &'static Day,
+    pub outcome: Outcome,
+}
+
+/// where the input for a day lives inside the inputs directory, i.e.
+/// `inputs/day06.txt`
+pub fn input_path(dir: impl AsRef<Path>, day: &Day) -> PathBuf {
+    dir.as_ref().join(format!("day{:02}.txt", day.number))
+}
+
+/// run a single day from the inputs directory with its parameters from the
+/// config. The timing only covers solving, not reading the file
+pub fn run_day(dir: &Path, day: &'static Day, config: &Config) -> Run {
+    let path = input_path(dir, day);
+    if !path.is_file() {
+        return Run {
+            day,
+            outcome: Outcome::Skipped { path },
+        };
+    }
+    let outcome = match read_input(&path) {
+        Ok(content) => match day.solve_timed(&content, &config.day(day.number)) {
+            Ok((answers, timings)) => Outcome::Solved { answers, timings },
+            Err(e) => Outcome::Failed(e.in_file(&path)),
+        },
+        Err(e) => Outcome::Failed(e),
+    };
+    Run { day, outcome }
+}
+
+/// run every registered day that has an input in `dir`. When `parallel` is set
+/// the days are shared out over a worker per core, either way the runs come
+/// back in calendar order
+pub fn run_all(dir: impl AsRef<Path>, parallel: bool, config: &Config) -> Vec<Run> {
+    let dir = dir.as_ref();
+    if !parallel {
+        return DAYS.iter().map(|day| run_day(dir, day, config)).collect();
+    }
+
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let (job_tx, job_rx) = unbounded::<&'static Day>();
+    let (run_tx, run_rx) = unbounded();
+    DAYS.iter().for_each(|day| job_tx.send(day).unwrap());
+    drop(job_tx);
+
+    scope(|s| {
+        for _ in 0..workers.min(DAYS.len()) {
+            let job_rx = job_rx.clone();
+            let run_tx = run_tx.clone();
+            s.spawn(move |_| {
+                for day in job_rx {
+                    run_tx.send(run_day(dir, day, config)).unwrap();
+                }
+            });
+        }
+    })
+    .expect("a day panicked");
+    drop(run_tx);
+
+    let mut runs: Vec<Run> = run_rx.into_iter().collect();
+    runs.sort_by_key(|r| r.day.number);
+    runs
+}
+
+/// a table of every run with its answers and how long it took
+pub fn summary(runs: &[Run]) -> String {
+    let rows: Vec<[String; 5]> = runs
+        .iter()
+        .map(|run| {
+            let (status, one, two, time) = match &run.outcome {
+                Outcome::Solved { answers, timings } => (
+                    "ok".to_string(),
+                    answers.part_one.to_string(),
+                    answers.part_two.to_string(),
+                    format!("{:.3} ms", timings.total().as_secs_f64() * 1000.0),
+                ),
+                Outcome::Skipped { path } => (
+                    format!("skipped, no {}", path.display()),
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                ),
+                Outcome::Failed(e) => (
+                    format!("failed, {e}"),
+                    "-".to_string(),
+                    "-".to_string(),
+                    "-".to_string(),
+                ),
+            };
+            [run.day.number.to_string(), one, two, time, status]
+        })
+        .collect();
+
+    let header = ["day", "part one", "part two", "time", "status"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut table = String::new();
+    let mut line = |cells: [&str; 5]| {
+        let [day, one, two, time, status] = cells;
+        let _ = writeln!(
+            table,
+            "{day:>w0$}  {one:>w1$}  {two:>w2$}  {time:>w3$}  {status}",
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        );
+    };
+    line(header);
+    for row in &rows {
+        line(row.each_ref().map(String::as_str));
+    }
+
+    let solved = runs
+        .iter()
+        .filter(|r| matches!(r.outcome, Outcome::Solved { .. }))
+        .count();
+    let skipped = runs
+        .iter()
+        .filter(|r| matches!(r.outcome, Outcome::Skipped { .. }))
+        .count();
+    let total: Duration = runs
+        .iter()
+        .filter_map(|r| match r.outcome {
+            Outcome::Solved { timings, .. } => Some(timings.total()),
+            _ => None,
+        })
+        .sum();
+    let _ = writeln!(
+        table,
+        "{solved} solved, {skipped} skipped, {} failed in {:.3} ms",
+        runs.len() - solved - skipped,
+        total.as_secs_f64() * 1000.0
+    );
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::find;
+
+    #[test]
+    fn test_input_path() {
+        let day = find("day_six").unwrap();
+        assert_eq!(
+            input_path("inputs", day),
+            Path::new("inputs").join("day06.txt")
+        );
+    }
+
+    #[test]
+    fn test_run_all_skips_missing_inputs() {
+        let runs = run_all("this/does/not/exist", true, &Config::default());
+        assert_eq!(runs.len(), DAYS.len());
+        assert!(
+            runs.iter()
+                .all(|r| matches!(r.outcome, Outcome::Skipped { .. }))
+        );
+        let last = format!("0 solved, {} skipped, 0 failed in 0.000 ms\n", DAYS.len());
+        assert!(summary(&runs).ends_with(&last));
+    }
+}
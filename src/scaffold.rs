@@ -0,0 +1,287 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+
+const TEMPLATE: &str = include_str!("days/template.txt");
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+/// `day` spelled out the way the registered names are, i.e. `twenty_one`
+pub fn spell(day: u8) -> String {
+    match day {
+        0..20 => ONES[day as usize].to_string(),
+        20 => "twenty".into(),
+        _ => format!("twenty_{}", ONES[day as usize - 20]),
+    }
+}
+
+/// the name a day is registered under, i.e. `day_six`
+pub fn day_name(day: u8) -> String {
+    format!("day_{}", spell(day))
+}
+
+/// the solver's type name, i.e. `DayTwentyOne`
+pub fn type_name(day: u8) -> String {
+    day_name(day)
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// the template filled in for `day`
+pub fn render(day: u8) -> String {
+    TEMPLATE
+        .replace("{{type}}", &type_name(day))
+        .replace("{{name}}", &day_name(day))
+        .replace("{{number}}", &day.to_string())
+        .replace("{{padded}}", &format!("{day:02}"))
+}
+
+/// The files a new day is made of, relative to the crate's root
+struct Paths {
+    module: PathBuf,
+    example: PathBuf,
+    registry: PathBuf,
+}
+
+impl Paths {
+    fn new(root: &Path, day: u8) -> Self {
+        let days = root.join("src").join("days");
+        Self {
+            module: days.join(format!("day{day}.rs")),
+            example: days.join("examples").join(format!("day{day:02}.txt")),
+            registry: days.join("mod.rs"),
+        }
+    }
+}
+
+fn exists(path: &Path, day: u8) -> Error {
+    Error::from(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("day {day} already exists, refusing to overwrite it"),
+    ))
+    .in_file(path)
+}
+
+/// add `day` to the registry in `mod.rs`, both its `mod` line and its entry in
+/// `DAYS` in order of the day. `None` when it's already there, an error when
+/// there's no `DAYS` to add it to
+pub fn register(registry: &str, day: u8) -> Result<Option<String>> {
+    let module = format!("pub mod day{day};");
+    if registry.lines().any(|l| l.trim() == module) {
+        return Ok(None);
+    }
+    let entry = format!("    Day::new::<day{day}::{}>(),", type_name(day));
+    // the number in a `Day::new::<dayN::...>(),` line
+    let registered = |line: &str| -> Option<u8> {
+        line.trim()
+            .strip_prefix("Day::new::<day")?
+            .split("::")
+            .next()?
+            .parse()
+            .ok()
+    };
+
+    let mut lines: Vec<String> = registry.lines().map(String::from).collect();
+    let mods: Vec<usize> = (0..lines.len())
+        .filter(|&i| lines[i].starts_with("pub mod day"))
+        .collect();
+    let mut block: Vec<String> = mods.iter().map(|&i| lines[i].clone()).collect();
+    block.push(module);
+    // ordered by name the way rustfmt has them
+    block.sort_by_key(|l| l.trim_end_matches(';').to_string());
+    let at = mods.first().copied().unwrap_or(0);
+    lines.splice(at..at + mods.len(), block);
+
+    let missing = || Error::MissingSection {
+        location: Location::of(registry, &registry[registry.len()..]),
+        section: "list of days, from pub const DAYS to its closing ];",
+    };
+    let start = lines
+        .iter()
+        .position(|l| l.starts_with("pub const DAYS"))
+        .ok_or_else(missing)?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|l| l.trim() == "];")
+            .ok_or_else(missing)?;
+    let before = (start + 1..end)
+        .find(|&i| registered(&lines[i]).is_some_and(|n| n > day))
+        .unwrap_or(end);
+    lines.insert(before, entry);
+
+    Ok(Some(lines.join("\n") + "\n"))
+}
+
+/// create the module for `day` from the template with an empty example and a
+/// failing test, and register it, all under the crate at `root`. Nothing is
+/// touched when the day is already there. Hands back the files written
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let paths = Paths::new(root, day);
+    let registry = read_input(&paths.registry)?;
+    for path in [&paths.module, &paths.example] {
+        if path.exists() {
+            return Err(exists(path, day));
+        }
+    }
+    let registered = register(&registry, day)
+        .map_err(|e| e.in_file(&paths.registry))?
+        .ok_or_else(|| exists(&paths.registry, day))?;
+
+    let create = |path: &Path, content: &str| {
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .map_err(|e| Error::from(e).in_file(path))
+    };
+    create(&paths.module, &render(day))?;
+    create(&paths.example, "")?;
+    fs::write(&paths.registry, registered).map_err(|e| Error::from(e).in_file(&paths.registry))?;
+    Ok(vec![paths.module, paths.example, paths.registry])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::DAYS;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(day_name(6), "day_six");
+        assert_eq!(day_name(20), "day_twenty");
+        assert_eq!(type_name(21), "DayTwentyOne");
+        // the same names the registered days already use
+        for day in DAYS {
+            assert_eq!(day_name(day.number), day.name);
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let module = render(21);
+        assert!(module.contains("pub struct DayTwentyOne;"));
+        assert!(module.contains("const NAME: &'static str = \"day_twenty_one\";"));
+        assert!(module.contains("include_str!(\"examples/day21.txt\")"));
+        assert!(module.contains("todo!(\"day 21 part one\")"));
+        assert!(!module.contains("{{"));
+    }
+
+    // a registry of its own, so the tests don't depend on which days the real
+    // one has
+    const REGISTRY: &str = "pub mod day1;\npub mod day2;\npub mod day6;\n\nuse crate::solver;\n\n\
+                            pub const DAYS: &[Day] = &[\n    \
+                            Day::new::<day1::DayOne>(),\n    \
+                            Day::new::<day2::DayTwo>(),\n    \
+                            Day::new::<day6::DaySix>(),\n];\n";
+
+    #[test]
+    fn test_register() {
+        let registry = "pub mod day1;\npub mod day2;\n\nuse crate::solver;\n\n\
+                        pub const DAYS: &[Day] = &[\n    \
+                        Day::new::<day1::DayOne>(),\n    \
+                        Day::new::<day2::DayTwo>(),\n];\n";
+        let registered = register(registry, 15).unwrap().unwrap();
+        assert_eq!(
+            registered,
+            "pub mod day1;\npub mod day15;\npub mod day2;\n\nuse crate::solver;\n\n\
+             pub const DAYS: &[Day] = &[\n    \
+             Day::new::<day1::DayOne>(),\n    \
+             Day::new::<day2::DayTwo>(),\n    \
+             Day::new::<day15::DayFifteen>(),\n];\n"
+        );
+        // a day between the others goes in its place
+        let with_one = register(&registered, 10).unwrap().unwrap();
+        assert!(
+            with_one.contains("DayTwo>(),\n    Day::new::<day10::DayTen>(),\n    Day::new::<day15")
+        );
+        assert_eq!(register(&registered, 15).unwrap(), None);
+
+        // without the list there's nowhere to put it, which isn't the same as
+        // the day being there already
+        for broken in [
+            "pub mod day1;\n",
+            "pub mod day1;\n\npub const DAYS: &[Day] = &[\n",
+        ] {
+            let error = register(broken, 15).unwrap_err();
+            assert!(matches!(error, Error::MissingSection { .. }));
+            assert!(error.to_string().contains("pub const DAYS"));
+        }
+    }
+
+    #[test]
+    fn test_new_day() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let days = dir.path().join("src/days");
+        fs::create_dir_all(days.join("examples")).unwrap();
+        fs::write(days.join("mod.rs"), REGISTRY).unwrap();
+
+        let written = new_day(dir.path(), 15).unwrap();
+        assert_eq!(written.len(), 3);
+        assert_eq!(
+            fs::read_to_string(days.join("day15.rs")).unwrap(),
+            render(15)
+        );
+        assert_eq!(
+            fs::read_to_string(days.join("examples/day15.txt")).unwrap(),
+            ""
+        );
+        let registry = fs::read_to_string(days.join("mod.rs")).unwrap();
+        assert!(registry.contains("pub mod day15;\n"));
+        assert!(registry.contains("    Day::new::<day15::DayFifteen>(),\n];"));
+
+        // a second go leaves everything as it was
+        fs::write(days.join("day15.rs"), "mine").unwrap();
+        let error = new_day(dir.path(), 15).unwrap_err();
+        assert!(error.to_string().contains("refusing to overwrite"));
+        assert_eq!(fs::read_to_string(days.join("day15.rs")).unwrap(), "mine");
+        assert_eq!(fs::read_to_string(days.join("mod.rs")).unwrap(), registry);
+
+        // as does a day that's registered but missing its files
+        let error = new_day(dir.path(), 6).unwrap_err();
+        assert!(error.to_string().contains("mod.rs: day 6 already exists"));
+        assert!(!days.join("day6.rs").exists());
+
+        // and a registry without its list of days says so
+        fs::write(days.join("mod.rs"), "pub mod day1;\n").unwrap();
+        let error = new_day(dir.path(), 16).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("mod.rs:2:1: missing list of days")
+        );
+        assert!(!days.join("day16.rs").exists());
+    }
+}
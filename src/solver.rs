@@ -0,0 +1,283 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::generate::Rng;
+use crate::params::{Param, Params};
+use crate::visual::Frames;
+
+/// A Solver is a single day of the calendar. The input text is parsed once and
+/// then handed to each part, so anything expensive that both parts need can live
+/// in `parse`.
+pub trait Solver {
+    /// The day of the month this solver answers, 1-25
+    const DAY: u8;
+    /// The spelled out name used on the command line i.e. `day_six`
+    const NAME: &'static str;
+
+    /// The constants the puzzle bakes in that a run can override, i.e. the grid
+    /// size of day 14. The defaults are the values for the real puzzle
+    const PARAMS: &'static [Param] = &[];
+
+    /// How big a generated input is, i.e. the number of robots of day 14, with
+    /// the defaults about the size of the real puzzle's
+    const SIZES: &'static [Param] = &[];
+
+    /// The examples from the puzzle text with the answers they should give.
+    /// Every example gets run by the integration tests
+    const EXAMPLES: &'static [Example] = &[];
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    /// parse with the parameters resolved against `PARAMS`. Days with
+    /// parameters override this and carry what they need in their input
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        let _ = params;
+        Self::parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Answer;
+
+    fn part_two(input: &Self::Input) -> Answer;
+
+    /// the puzzle played out as frames for `--visualize`, for the days that
+    /// have something worth watching
+    fn visualize(input: &Self::Input) -> Option<Frames> {
+        let _ = input;
+        None
+    }
+
+    /// a random input of the size asked for that keeps to everything the
+    /// puzzle promises about its input, for the days that have a generator
+    fn generate(rng: &mut Rng, sizes: &Params) -> Result<Option<String>> {
+        let _ = (rng, sizes);
+        Ok(None)
+    }
+}
+
+/// An example input from the puzzle text along with its expected answers. The
+/// puzzle doesn't always give both, in which case the answer is left out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub input: &'static str,
+    /// parameters the example needs, i.e. the smaller room of day 14
+    pub params: &'static [(&'static str, &'static str)],
+    pub part_one: Option<&'static str>,
+    pub part_two: Option<&'static str>,
+}
+
+impl Example {
+    /// an example with no parameters and both answers known
+    pub const fn new(input: &'static str, part_one: &'static str, part_two: &'static str) -> Self {
+        Example {
+            input,
+            params: &[],
+            part_one: Some(part_one),
+            part_two: Some(part_two),
+        }
+    }
+
+    /// the example's parameters, ready to be merged with any given on the
+    /// command line
+    pub fn params(&self) -> Params {
+        let mut params = Params::new();
+        for (name, value) in self.params {
+            params.set(*name, *value);
+        }
+        params
+    }
+
+    /// the answer `part` should give, if the puzzle says
+    pub fn expected(&self, part: Part) -> Option<&'static str> {
+        match part {
+            Part::One => self.part_one,
+            Part::Two => self.part_two,
+        }
+    }
+}
+
+/// The answers to both parts of a day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part_one: Answer,
+    pub part_two: Answer,
+}
+
+/// Parse the input and solve both parts. There is no file I/O and nothing is
+/// printed so this is safe to call from anywhere
+pub fn solve<S: Solver>(input: &str) -> Result<Answers> {
+    solve_with::<S>(input, &Params::new())
+}
+
+/// check the given parameters against the ones the day declares and parse
+fn parse<S: Solver>(input: &str, params: &Params) -> Result<S::Input> {
+    S::parse_with(input, &params.resolve(S::PARAMS)?)
+}
+
+/// `solve` with some of the day's parameters overridden
+pub fn solve_with<S: Solver>(input: &str, params: &Params) -> Result<Answers> {
+    let parsed = parse::<S>(input, params)?;
+    Ok(Answers {
+        part_one: S::part_one(&parsed),
+        part_two: S::part_two(&parsed),
+    })
+}
+
+/// a random input from `seed` with some of the day's sizes overridden
+pub fn generate_with<S: Solver>(seed: u64, sizes: &Params) -> Result<Option<String>> {
+    S::generate(&mut Rng::new(seed), &sizes.resolve(S::SIZES)?)
+}
+
+/// parse and hand over the day's frames, if it has any
+pub fn visualize_with<S: Solver>(input: &str, params: &Params) -> Result<Option<Frames>> {
+    Ok(S::visualize(&parse::<S>(input, params)?))
+}
+
+/// How long each phase of a solve took
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part_one: Duration,
+    pub part_two: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part_one + self.part_two
+    }
+}
+
+/// `solve` but timing the parse and each part on their own
+pub fn solve_timed<S: Solver>(input: &str, params: &Params) -> Result<(Answers, Timings)> {
+    let now = Instant::now();
+    let parsed = parse::<S>(input, params)?;
+    let parse = now.elapsed();
+    let now = Instant::now();
+    let part_one = S::part_one(&parsed);
+    let part_one_time = now.elapsed();
+    let now = Instant::now();
+    let part_two = S::part_two(&parsed);
+    let part_two_time = now.elapsed();
+    Ok((
+        Answers { part_one, part_two },
+        Timings {
+            parse,
+            part_one: part_one_time,
+            part_two: part_two_time,
+        },
+    ))
+}
+
+/// One of the two halves of a puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+/// parse and solve just one part. The other part's timing is left at zero
+pub fn solve_part<S: Solver>(
+    input: &str,
+    part: Part,
+    params: &Params,
+) -> Result<(Answer, Timings)> {
+    let now = Instant::now();
+    let parsed = parse::<S>(input, params)?;
+    let mut timings = Timings {
+        parse: now.elapsed(),
+        ..Timings::default()
+    };
+    let now = Instant::now();
+    let answer = match part {
+        Part::One => {
+            let answer = S::part_one(&parsed);
+            timings.part_one = now.elapsed();
+            answer
+        }
+        Part::Two => {
+            let answer = S::part_two(&parsed);
+            timings.part_two = now.elapsed();
+            answer
+        }
+    };
+    Ok((answer, timings))
+}
+
+/// The answer to a single part of a puzzle. Almost everything is a number but a
+/// few puzzles want a string back so we keep that door open
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Number(value)
+    }
+}
+
+/// too big for a u64 the answer is written out as its digits
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        u64::try_from(value).map_or_else(|_| Answer::Text(value.to_string()), Answer::Number)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Number(value as u64)
+    }
+}
+
+impl From<u16> for Answer {
+    fn from(value: u16) -> Self {
+        Answer::Number(value as u64)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Number(value as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(42u64).to_string(), "42");
+        assert_eq!(Answer::from(String::from("1,2,3")).to_string(), "1,2,3");
+    }
+}
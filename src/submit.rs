@@ -0,0 +1,492 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::days::read_input;
+use crate::error::{Error, Location, Result};
+use crate::inputs::Fetcher;
+use crate::solver::Part;
+
+/// What the server made of an answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    /// wrong, without saying which way
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// the answer wasn't looked at, another can be sent after `wait` when the
+    /// server says how long that is
+    RateLimited {
+        wait: Option<Duration>,
+    },
+}
+
+impl Verdict {
+    /// make sense of the page the server answers a submission with
+    pub fn read(page: &str) -> Option<Self> {
+        if page.contains("That's the right answer") {
+            Some(Verdict::Correct)
+        } else if page.contains("You gave an answer too recently") {
+            Some(Verdict::RateLimited {
+                wait: read_wait(page),
+            })
+        } else if page.contains("That's not the right answer") {
+            if page.contains("your answer is too high") {
+                Some(Verdict::TooHigh)
+            } else if page.contains("your answer is too low") {
+                Some(Verdict::TooLow)
+            } else {
+                Some(Verdict::Incorrect)
+            }
+        } else {
+            None
+        }
+    }
+
+    /// how the verdict is written in the history file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Incorrect => "incorrect",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::RateLimited { .. } => "rate-limited",
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, ()> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "incorrect" => Ok(Verdict::Incorrect),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "rate-limited" => Ok(Verdict::RateLimited { wait: None }),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "right"),
+            Verdict::Incorrect => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::RateLimited { wait: None } => write!(f, "rate limited"),
+            Verdict::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited, try again in {}s", wait.as_secs())
+            }
+        }
+    }
+}
+
+/// the wait in a page saying `You have 1m 5s left to wait`
+fn read_wait(page: &str) -> Option<Duration> {
+    let (_, rest) = page.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    let mut seconds = 0;
+    for part in wait.split_whitespace() {
+        let (count, unit) = part.split_at(part.len().checked_sub(1)?);
+        let count: u64 = count.parse().ok()?;
+        seconds += match unit {
+            "h" => count * 3600,
+            "m" => count * 60,
+            "s" => count,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// A single answer that was sent and what came of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Why an answer wasn't worth sending
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    /// the part has already been solved with `answer`
+    Solved { answer: String },
+    /// this exact answer was sent before and was wrong
+    Repeated { verdict: Verdict },
+    /// an answer at least this big was already too high
+    AboveBound { bound: String },
+    /// an answer at least this small was already too low
+    BelowBound { bound: String },
+    /// the answer is empty or has whitespace or a `#` in it, which the history
+    /// can't hold
+    Unrecordable,
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Solved { answer } => write!(f, "already solved, the answer was {answer}"),
+            Refusal::Repeated { verdict } => write!(f, "already tried, it was {verdict}"),
+            Refusal::AboveBound { bound } => write!(f, "{bound} was already too high"),
+            Refusal::BelowBound { bound } => write!(f, "{bound} was already too low"),
+            Refusal::Unrecordable => write!(
+                f,
+                "the history can't hold an answer that's empty or has a space or # in it"
+            ),
+        }
+    }
+}
+
+/// Every answer ever sent, oldest first. On disk this is a plain text file with
+/// a `day part verdict answer` line per attempt, i.e. `6 1 too-high 5000`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct History {
+    attempts: Vec<Attempt>,
+}
+
+impl History {
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut attempts = Vec::new();
+        for line in input.lines() {
+            let content = line.split('#').next().unwrap_or_default();
+            if content.trim().is_empty() {
+                continue;
+            }
+            let mut fields = content.split_whitespace();
+            let (Some(day), Some(part), Some(verdict), Some(answer), None) = (
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+            ) else {
+                return Err(Error::malformed(
+                    Location::of(input, line),
+                    "an attempt like 6 1 too-high 5000",
+                ));
+            };
+            let day = day
+                .parse::<u8>()
+                .ok()
+                .filter(|d| (1..=25).contains(d))
+                .ok_or_else(|| Error::malformed(Location::of(input, day), "a day 1-25"))?;
+            let part = part
+                .parse::<u8>()
+                .ok()
+                .filter(|p| (1..=2).contains(p))
+                .ok_or_else(|| Error::malformed(Location::of(input, part), "a part 1 or 2"))?;
+            let verdict = verdict.parse().map_err(|_| {
+                Error::malformed(
+                    Location::of(input, verdict),
+                    "a verdict, one of correct, incorrect, too-high, too-low or rate-limited",
+                )
+            })?;
+            attempts.push(Attempt {
+                day,
+                part,
+                answer: answer.to_string(),
+                verdict,
+            });
+        }
+        Ok(Self { attempts })
+    }
+
+    /// load the history from `path`, a file that doesn't exist yet is empty
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = read_input(path)?;
+        Self::parse(&content).map_err(|e| e.in_file(path))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_string()).map_err(|e| Error::from(e).in_file(path))
+    }
+
+    pub fn push(&mut self, attempt: Attempt) {
+        self.attempts.push(attempt);
+    }
+
+    /// the attempts at a part of a day, oldest first
+    pub fn attempts(&self, day: u8, part: u8) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| a.day == day && a.part == part)
+    }
+
+    /// why `answer` can't be right going by the earlier attempts, if it can't
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Option<Refusal> {
+        // the fields of a line are split on whitespace and a # starts a comment
+        if answer.is_empty() || answer.contains(|c: char| c.is_whitespace() || c == '#') {
+            return Some(Refusal::Unrecordable);
+        }
+        if let Some(solved) = self
+            .attempts(day, part)
+            .find(|a| a.verdict == Verdict::Correct)
+        {
+            return Some(Refusal::Solved {
+                answer: solved.answer.clone(),
+            });
+        }
+        let mut above: Option<(i128, &str)> = None;
+        let mut below: Option<(i128, &str)> = None;
+        for attempt in self.attempts(day, part) {
+            match attempt.verdict {
+                // never looked at, so it says nothing about the answer
+                Verdict::RateLimited { .. } => continue,
+                verdict if attempt.answer == answer => {
+                    return Some(Refusal::Repeated { verdict });
+                }
+                _ => {}
+            }
+            let Ok(value) = attempt.answer.parse::<i128>() else {
+                continue;
+            };
+            match attempt.verdict {
+                Verdict::TooHigh if above.is_none_or(|(bound, _)| value < bound) => {
+                    above = Some((value, &attempt.answer))
+                }
+                Verdict::TooLow if below.is_none_or(|(bound, _)| value > bound) => {
+                    below = Some((value, &attempt.answer))
+                }
+                _ => {}
+            }
+        }
+        let value = answer.parse::<i128>().ok()?;
+        match (above, below) {
+            (Some((bound, text)), _) if value >= bound => Some(Refusal::AboveBound {
+                bound: text.to_string(),
+            }),
+            (_, Some((bound, text))) if value <= bound => Some(Refusal::BelowBound {
+                bound: text.to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# day part verdict answer")?;
+        for attempt in &self.attempts {
+            writeln!(
+                f,
+                "{} {} {} {}",
+                attempt.day,
+                attempt.part,
+                attempt.verdict.name(),
+                attempt.answer
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How a submission went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    /// the history already ruled the answer out so it was never sent
+    Refused(Refusal),
+    Sent(Verdict),
+}
+
+/// send `answer` for a part of `day` unless the history rules it out, noting
+/// the attempt in the history when it is sent
+pub fn submit(
+    fetcher: &Fetcher,
+    history: &mut History,
+    day: u8,
+    part: Part,
+    answer: &str,
+) -> Result<Submission> {
+    if let Some(refusal) = history.check(day, part.number(), answer) {
+        return Ok(Submission::Refused(refusal));
+    }
+    let page = fetcher.submit(day, part, answer)?;
+    let verdict = Verdict::read(&page).ok_or_else(|| Error::Fetch {
+        url: fetcher.answer_url(day),
+        reason: if page.contains("solving the right level") {
+            "the part is already solved or isn't open yet".into()
+        } else {
+            "the reply wasn't one of the known verdicts".into()
+        },
+    })?;
+    history.push(Attempt {
+        day,
+        part: part.number(),
+        answer: answer.to_string(),
+        verdict,
+    });
+    Ok(Submission::Sent(verdict))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::mock::MockServer;
+
+    fn attempt(part: u8, answer: &str, verdict: Verdict) -> Attempt {
+        Attempt {
+            day: 6,
+            part,
+            answer: answer.into(),
+            verdict,
+        }
+    }
+
+    #[test]
+    fn test_read_verdict() {
+        let page = |text: &str| format!("<main>\n<article><p>{text}</p></article>\n</main>");
+        let read = |text: &str| Verdict::read(&page(text));
+        assert_eq!(
+            read("That's the right answer!  You are one gold star closer."),
+            Some(Verdict::Correct)
+        );
+        assert_eq!(
+            read("That's not the right answer.  If you're stuck, make sure..."),
+            Some(Verdict::Incorrect)
+        );
+        assert_eq!(
+            read("That's not the right answer; your answer is too high."),
+            Some(Verdict::TooHigh)
+        );
+        assert_eq!(
+            read("That's not the right answer; your answer is too low."),
+            Some(Verdict::TooLow)
+        );
+        assert_eq!(
+            read("You gave an answer too recently.  You have 1m 5s left to wait."),
+            Some(Verdict::RateLimited {
+                wait: Some(Duration::from_secs(65))
+            })
+        );
+        assert_eq!(
+            read("You gave an answer too recently."),
+            Some(Verdict::RateLimited { wait: None })
+        );
+        assert_eq!(read("You don't seem to be solving the right level."), None);
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let history = History::parse("# comment\n6 1 too-high 5000\n6 2 correct 1234\n").unwrap();
+        assert_eq!(history.attempts(6, 1).count(), 1);
+        assert_eq!(History::parse(&history.to_string()).unwrap(), history);
+
+        let error = History::parse("6 1 maybe 5000\n").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(1, 5)));
+        let error = History::parse("6 1 5000\n").unwrap_err();
+        assert_eq!(error.location(), Some(&Location::new(1, 1)));
+    }
+
+    #[test]
+    fn test_check() {
+        let mut history = History::default();
+        history.push(attempt(1, "5000", Verdict::TooHigh));
+        history.push(attempt(1, "4000", Verdict::TooHigh));
+        history.push(attempt(1, "100", Verdict::TooLow));
+        history.push(attempt(1, "200", Verdict::Incorrect));
+        history.push(attempt(1, "300", Verdict::RateLimited { wait: None }));
+
+        assert_eq!(history.check(6, 1, "150"), None);
+        // a rate limited answer was never judged so it can go again
+        assert_eq!(history.check(6, 1, "300"), None);
+        assert_eq!(
+            history.check(6, 1, "200"),
+            Some(Refusal::Repeated {
+                verdict: Verdict::Incorrect
+            })
+        );
+        assert_eq!(
+            history.check(6, 1, "4500"),
+            Some(Refusal::AboveBound {
+                bound: "4000".into()
+            })
+        );
+        assert_eq!(
+            history.check(6, 1, "100"),
+            Some(Refusal::Repeated {
+                verdict: Verdict::TooLow
+            })
+        );
+        assert_eq!(
+            history.check(6, 1, "99"),
+            Some(Refusal::BelowBound {
+                bound: "100".into()
+            })
+        );
+        // bounds only apply to numbers, and only to the same part
+        assert_eq!(history.check(6, 1, "abc"), None);
+        assert_eq!(history.check(6, 2, "9000"), None);
+
+        history.push(attempt(1, "3000", Verdict::Correct));
+        assert_eq!(
+            history.check(6, 1, "150"),
+            Some(Refusal::Solved {
+                answer: "3000".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = MockServer::start(
+            "200 OK",
+            "<article><p>That's not the right answer; your answer is too low.</p></article>",
+        );
+        let fetcher = Fetcher::new(&server.url, "abc123").rate_limit(Duration::ZERO);
+        let mut history = History::default();
+
+        let sent = submit(&fetcher, &mut history, 6, Part::One, "41").unwrap();
+        assert_eq!(sent, Submission::Sent(Verdict::TooLow));
+        assert_eq!(
+            history.attempts(6, 1).collect::<Vec<_>>(),
+            vec![&attempt(1, "41", Verdict::TooLow)]
+        );
+
+        // known to be too low, so this one never reaches the server
+        let refused = submit(&fetcher, &mut history, 6, Part::One, "40").unwrap();
+        assert_eq!(
+            refused,
+            Submission::Refused(Refusal::BelowBound { bound: "41".into() })
+        );
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(history.attempts(6, 1).count(), 1);
+
+        // nor do answers the history couldn't read back
+        for answer in ["", "4 1", "#41", "41\n"] {
+            let refused = submit(&fetcher, &mut history, 6, Part::Two, answer).unwrap();
+            assert_eq!(refused, Submission::Refused(Refusal::Unrecordable));
+        }
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(History::parse(&history.to_string()).unwrap(), history);
+    }
+
+    #[test]
+    fn test_submit_unknown_reply() {
+        let server = MockServer::start(
+            "200 OK",
+            "<article><p>You don't seem to be solving the right level.</p></article>",
+        );
+        let fetcher = Fetcher::new(&server.url, "abc123").rate_limit(Duration::ZERO);
+        let mut history = History::default();
+        let error = submit(&fetcher, &mut history, 6, Part::Two, "41").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("already solved or isn't open yet")
+        );
+        assert_eq!(history, History::default());
+    }
+}
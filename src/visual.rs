@@ -0,0 +1,438 @@
+use std::io::{self, IsTerminal, Write};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, RecvTimeoutError};
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::IntoAlternateScreen;
+
+use crate::grid::Grid;
+
+/// The colours a cell can be drawn in, the ones every terminal has
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Grey,
+}
+
+impl Colour {
+    const PALETTE: [Colour; 6] = [
+        Colour::Red,
+        Colour::Green,
+        Colour::Yellow,
+        Colour::Blue,
+        Colour::Magenta,
+        Colour::Cyan,
+    ];
+
+    /// a colour for each of any number of things, going round the palette
+    pub fn cycle(i: usize) -> Self {
+        Self::PALETTE[i % Self::PALETTE.len()]
+    }
+
+    /// the colour as red, green and blue for images, picked to look like a
+    /// terminal's
+    pub fn rgb(self) -> [u8; 3] {
+        match self {
+            Colour::Red => [220, 50, 47],
+            Colour::Green => [95, 215, 95],
+            Colour::Yellow => [240, 200, 60],
+            Colour::Blue => [70, 130, 230],
+            Colour::Magenta => [200, 90, 200],
+            Colour::Cyan => [60, 200, 210],
+            Colour::White => [245, 245, 245],
+            Colour::Grey => [50, 50, 50],
+        }
+    }
+
+    /// the ANSI code that sets the text to this colour
+    fn ansi(self) -> u8 {
+        match self {
+            Colour::Red => 31,
+            Colour::Green => 32,
+            Colour::Yellow => 33,
+            Colour::Blue => 34,
+            Colour::Magenta => 35,
+            Colour::Cyan => 36,
+            Colour::White => 97,
+            Colour::Grey => 90,
+        }
+    }
+}
+
+/// A single character of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    /// the terminal's own colour when not given
+    pub colour: Option<Colour>,
+}
+
+impl Cell {
+    pub const fn new(glyph: char, colour: Colour) -> Self {
+        Self {
+            glyph,
+            colour: Some(colour),
+        }
+    }
+
+    pub const fn plain(glyph: char) -> Self {
+        Self {
+            glyph,
+            colour: None,
+        }
+    }
+
+    /// the colour the cell is filled with in an image, light grey for a
+    /// plain one
+    pub fn rgb(&self) -> [u8; 3] {
+        self.colour.map_or([190, 190, 190], Colour::rgb)
+    }
+}
+
+/// A single picture of a simulation, a grid of cells with a caption under it
+/// saying how far along it is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub cells: Grid<Cell>,
+    pub caption: String,
+}
+
+/// What a day hands over to be played, frames in order until the simulation
+/// is done
+pub type Frames = Box<dyn Iterator<Item = Frame>>;
+
+impl Frame {
+    pub fn new(cells: Grid<Cell>, caption: impl Into<String>) -> Self {
+        Self {
+            cells,
+            caption: caption.into(),
+        }
+    }
+
+    /// the frame as ANSI text drawn from the top left corner over whatever was
+    /// there, only switching colour where it changes
+    pub fn ansi(&self) -> String {
+        let mut out = String::from("\x1b[H");
+        for row in self.cells.rows() {
+            let mut current = None;
+            for cell in row {
+                if cell.colour != current {
+                    match cell.colour {
+                        Some(colour) => out.push_str(&format!("\x1b[{}m", colour.ansi())),
+                        None => out.push_str("\x1b[0m"),
+                    }
+                    current = cell.colour;
+                }
+                out.push(cell.glyph);
+            }
+            if current.is_some() {
+                out.push_str("\x1b[0m");
+            }
+            // carriage returns too as a raw terminal won't add them
+            out.push_str("\x1b[K\r\n");
+        }
+        out.push_str(&self.caption);
+        out.push_str("\x1b[K\r\n\x1b[J");
+        out
+    }
+}
+
+/// Which of a day's frames to show or export, counting from 0. Given as `N`
+/// for just the one, `A..B` for A up to but not including B, or `A..` for A
+/// onwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl FrameRange {
+    pub const ALL: Self = Self {
+        start: 0,
+        end: None,
+    };
+
+    /// the items from an iterator of frames that fall in the range
+    pub fn select<T>(self, items: impl Iterator<Item = T>) -> impl Iterator<Item = T> {
+        let count = self
+            .end
+            .map_or(usize::MAX, |end| end.saturating_sub(self.start));
+        items.skip(self.start).take(count)
+    }
+}
+
+impl FromStr for FrameRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| n.parse::<usize>().ok();
+        let range = match s.split_once("..") {
+            None => number(s).map(|n| Self {
+                start: n,
+                end: Some(n + 1),
+            }),
+            Some((start, "")) => number(start).map(|start| Self { start, end: None }),
+            Some((start, end)) => number(start)
+                .zip(number(end))
+                .filter(|(start, end)| start < end)
+                .map(|(start, end)| Self {
+                    start,
+                    end: Some(end),
+                }),
+        };
+        range.ok_or_else(|| format!("expected a frame like 3, 3..10 or 3.., found '{s}'"))
+    }
+}
+
+/// The keys a player answers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// stop on the current frame, or carry on from it
+    Pause,
+    /// show the next frame and stay paused there
+    Step,
+    Faster,
+    Slower,
+    Quit,
+}
+
+impl Key {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            ' ' | 'p' => Some(Key::Pause),
+            'n' | 's' | '.' => Some(Key::Step),
+            '+' | '=' => Some(Key::Faster),
+            '-' => Some(Key::Slower),
+            'q' => Some(Key::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// the slowest frames can be played, one every 100 seconds
+pub const MIN_FPS: f64 = 0.01;
+const SLOWEST: Duration = Duration::from_secs(100);
+
+/// how long each frame is held at `fps`, never slower than `MIN_FPS`
+pub fn delay(fps: f64) -> Duration {
+    Duration::from_secs_f64(1.0 / fps.max(MIN_FPS))
+}
+
+/// Draws frames one over the other at a steady rate, taking keys from
+/// `keys` as it goes. With nobody to send keys it just plays to the end
+pub struct Player<W> {
+    out: W,
+    keys: Receiver<Key>,
+    delay: Duration,
+    paused: bool,
+}
+
+impl<W: Write> Player<W> {
+    pub fn new(out: W, keys: Receiver<Key>, fps: f64) -> Self {
+        Self {
+            out,
+            keys,
+            delay: delay(fps),
+            paused: false,
+        }
+    }
+
+    /// wait for a key before moving on from the first frame
+    pub fn paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+
+    /// draw each frame in turn until they run out or quit is pressed, handing
+    /// back how many were shown
+    pub fn play(&mut self, frames: impl Iterator<Item = Frame>) -> io::Result<usize> {
+        write!(self.out, "\x1b[2J")?;
+        let mut shown = 0;
+        for frame in frames {
+            self.out.write_all(frame.ansi().as_bytes())?;
+            self.out.flush()?;
+            shown += 1;
+            if !self.wait() {
+                break;
+            }
+        }
+        Ok(shown)
+    }
+
+    /// hold the frame for as long as it should be seen, false to stop playing
+    fn wait(&mut self) -> bool {
+        loop {
+            let key = if self.paused {
+                match self.keys.recv() {
+                    Ok(key) => key,
+                    // nothing can unpause it any more
+                    Err(_) => {
+                        self.paused = false;
+                        return true;
+                    }
+                }
+            } else {
+                match self.keys.recv_timeout(self.delay) {
+                    Ok(key) => key,
+                    Err(RecvTimeoutError::Timeout) => return true,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        thread::sleep(self.delay);
+                        return true;
+                    }
+                }
+            };
+            match key {
+                Key::Quit => return false,
+                Key::Step => {
+                    self.paused = true;
+                    return true;
+                }
+                Key::Pause => self.paused = !self.paused,
+                Key::Faster => self.delay /= 2,
+                Key::Slower => self.delay = self.delay.saturating_mul(2).min(SLOWEST),
+            }
+        }
+    }
+}
+
+/// the keys as they are pressed, read from stdin on a thread of their own
+fn keys() -> Receiver<Key> {
+    let (send, keys) = crossbeam_channel::unbounded();
+    thread::spawn(move || {
+        for key in io::stdin().keys() {
+            let key = match key {
+                Ok(termion::event::Key::Char(c)) => Key::from_char(c),
+                Ok(termion::event::Key::Right) => Some(Key::Step),
+                Ok(termion::event::Key::Esc | termion::event::Key::Ctrl('c')) => Some(Key::Quit),
+                Ok(_) => None,
+                Err(_) => break,
+            };
+            if let Some(key) = key
+                && send.send(key).is_err()
+            {
+                break;
+            }
+        }
+    });
+    keys
+}
+
+/// play `frames` in the terminal, on a screen of their own with the keys read
+/// straight away rather than a line at a time. When stdout isn't a terminal
+/// the frames are just written out
+pub fn play(frames: Frames, fps: f64, paused: bool) -> io::Result<usize> {
+    let stdout = io::stdout();
+    if !stdout.is_terminal() {
+        return Player::new(stdout.lock(), keys(), fps).play(frames);
+    }
+    let mut screen = stdout.into_raw_mode()?.into_alternate_screen()?;
+    write!(screen, "{}", termion::cursor::Hide)?;
+    let mut player = Player::new(&mut screen, keys(), fps);
+    if paused {
+        player = player.paused();
+    }
+    let shown = player.play(frames);
+    write!(screen, "{}", termion::cursor::Show)?;
+    shown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(caption: &str) -> Frame {
+        let cells = Grid::from_rows(vec![
+            vec![Cell::new('#', Colour::Red), Cell::new('#', Colour::Red)],
+            vec![Cell::plain('.'), Cell::new('^', Colour::Green)],
+        ])
+        .unwrap();
+        Frame::new(cells, caption)
+    }
+
+    #[test]
+    fn test_ansi() {
+        assert_eq!(
+            frame("step 1").ansi(),
+            "\x1b[H\x1b[31m##\x1b[0m\x1b[K\r\n\
+             .\x1b[32m^\x1b[0m\x1b[K\r\n\
+             step 1\x1b[K\r\n\x1b[J"
+        );
+        assert_eq!(Colour::cycle(7), Colour::Green);
+    }
+
+    #[test]
+    fn test_frame_range() {
+        let range: FrameRange = "2..4".parse().unwrap();
+        assert_eq!(range.select(0..10).collect::<Vec<_>>(), [2, 3]);
+        let range: FrameRange = "7".parse().unwrap();
+        assert_eq!(range.select(0..10).collect::<Vec<_>>(), [7]);
+        let range: FrameRange = "8..".parse().unwrap();
+        assert_eq!(range.select(0..10).collect::<Vec<_>>(), [8, 9]);
+        assert_eq!(FrameRange::ALL.select(0..3).count(), 3);
+        for bad in ["", "4..2", "a..", "..3", "-1"] {
+            assert!(bad.parse::<FrameRange>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_play_to_the_end() {
+        let (_send, keys) = crossbeam_channel::unbounded();
+        let mut out = Vec::new();
+        let frames = (1..=3).map(|i| frame(&format!("step {i}")));
+        let shown = Player::new(&mut out, keys, 1000.0).play(frames).unwrap();
+        assert_eq!(shown, 3);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("\x1b[2J"));
+        assert!(out.contains("step 1") && out.contains("step 3"));
+    }
+
+    #[test]
+    fn test_keys() {
+        let (send, keys) = crossbeam_channel::unbounded();
+        // paused, a step shows one more frame and the quit ends it there
+        for key in [Key::Faster, Key::Step, Key::Quit] {
+            send.send(key).unwrap();
+        }
+        let mut out = Vec::new();
+        let frames = (1..=10).map(|i| frame(&format!("step {i}")));
+        let mut player = Player::new(&mut out, keys, 1.0).paused();
+        assert_eq!(player.play(frames).unwrap(), 2);
+        assert_eq!(player.delay, Duration::from_millis(500));
+
+        // slowing down stops at the slowest there is, however often it's asked
+        let (send, keys) = crossbeam_channel::unbounded();
+        for _ in 0..200 {
+            send.send(Key::Slower).unwrap();
+        }
+        send.send(Key::Quit).unwrap();
+        let mut player = Player::new(Vec::new(), keys, 1.0);
+        assert_eq!(player.play([frame("slow")].into_iter()).unwrap(), 1);
+        assert_eq!(player.delay, SLOWEST);
+        assert_eq!(delay(1e-300), SLOWEST);
+
+        assert_eq!(Key::from_char(' '), Some(Key::Pause));
+        assert_eq!(Key::from_char('x'), None);
+    }
+
+    #[test]
+    fn test_paused_without_keys() {
+        let (send, keys) = crossbeam_channel::unbounded();
+        drop(send);
+        let mut out = Vec::new();
+        let frames = (1..=2).map(|i| frame(&format!("step {i}")));
+        let shown = Player::new(&mut out, keys, 1000.0)
+            .paused()
+            .play(frames)
+            .unwrap();
+        assert_eq!(shown, 2);
+    }
+}
@@ -1,3 +1,5 @@
+use aoc24::days;
+use aoc24::solver::Answer;
 use assert_cmd::prelude::*;
 use assert_fs::prelude::*;
 use predicates::prelude::*;
@@ -17,3 +19,536 @@ fn test_day_three() {
 
     file.close().unwrap();
 }
+
+#[test]
+fn test_day_three_library() {
+    let answers = days::day3::solve(
+        "foobarbang     hello do don;t   do() mul(3,5) \n don't() mul(5,4) mul(   4,4)",
+    )
+    .unwrap();
+    assert_eq!(answers.part_one, Answer::Number(35));
+    assert_eq!(answers.part_two, Answer::Number(15));
+}
+
+#[test]
+fn test_path_wrappers() {
+    let file = assert_fs::NamedTempFile::new("day03.txt").unwrap();
+    file.write_str("mul(3,5) don't() mul(5,4)").unwrap();
+    days::day3::day_three(file.path().to_str().unwrap()).unwrap();
+
+    let missing = file.path().with_file_name("missing.txt");
+    let error = days::day1::day_one(missing.to_string_lossy().into_owned()).unwrap_err();
+    assert!(error.to_string().contains("missing.txt"));
+
+    file.close().unwrap();
+}
+
+#[test]
+fn test_registry_solve_matches_module() {
+    let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    let day = days::find("day_one").unwrap();
+    let answers = day.solve(input).unwrap();
+    assert_eq!(answers, days::day1::solve(input).unwrap());
+    assert_eq!(answers.part_one, Answer::Number(11));
+    assert_eq!(answers.part_two, Answer::Number(31));
+}
+
+#[test]
+fn test_malformed_input_points_at_the_error() {
+    let file = assert_fs::NamedTempFile::new("day01.txt").unwrap();
+    file.write_str("3   4\n4   x\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("day_one").arg(file.path());
+    cmd.assert().failure().stderr(
+        predicates::str::contains("day01.txt:2:5: malformed line, expected a location id")
+            .and(predicates::str::contains("2 | 4   x\n  |     ^")),
+    );
+
+    file.close().unwrap();
+}
+
+#[test]
+fn test_run_all() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    dir.child("day01.txt")
+        .write_str("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n")
+        .unwrap();
+    dir.child("day02.txt").write_str("1 2 x\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("run-all").arg(dir.path());
+    cmd.assert().failure().stdout(
+        predicates::str::is_match(r"(?m)^  1\s+11\s+31\s+\S+ ms  ok$")
+            .unwrap()
+            .and(predicates::str::contains("day02.txt:1:5: malformed line"))
+            .and(predicates::str::contains("skipped, no"))
+            .and(predicates::str::is_match(r"1 solved, \d+ skipped, 1 failed").unwrap()),
+    );
+
+    dir.close().unwrap();
+}
+
+#[test]
+fn test_verify_and_record() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    dir.child("day01.txt")
+        .write_str("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("verify").arg(dir.path()).arg("--record");
+    cmd.assert().success();
+    dir.child("answers.txt")
+        .assert(predicates::str::contains("1 1 11\n1 2 31\n"));
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("verify").arg(dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("2 passed, 0 failed"));
+
+    dir.child("answers.txt")
+        .write_str("1 1 11\n1 2 30\n")
+        .unwrap();
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("verify").arg(dir.path());
+    cmd.assert().failure().stdout(predicates::str::contains(
+        "day  1 part 2: FAIL expected 30, got 31",
+    ));
+
+    dir.close().unwrap();
+}
+
+#[test]
+fn test_bench_json() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    dir.child("day01.txt")
+        .write_str("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("bench").arg("day_one").arg(dir.path()).args([
+        "--iterations",
+        "3",
+        "--warmup",
+        "1",
+        "--format",
+        "json",
+    ]);
+    cmd.assert().success().stdout(
+        predicates::str::contains(r#"{"day":1,"phase":"part_two","iterations":3,"#)
+            .and(predicates::str::contains(r#""phase":"total""#)),
+    );
+
+    dir.close().unwrap();
+}
+
+#[test]
+fn test_formats() {
+    let file = assert_fs::NamedTempFile::new("day01.txt").unwrap();
+    file.write_str("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("day_one").arg(file.path());
+    cmd.assert().success().stdout("11\n31\n");
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["--format", "json", "day_one"]).arg(file.path());
+    cmd.assert().success().stdout(
+        predicates::str::is_match(
+            r#"^\{"day":1,"part":1,"answer":11,"elapsed_us":[0-9.]+\}\n\{"day":1,"part":2,"answer":31,"elapsed_us":[0-9.]+\}\n$"#,
+        )
+        .unwrap(),
+    );
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("day_one").arg(file.path()).arg("--format=tsv");
+    cmd.assert().success().stdout(
+        predicates::str::is_match(
+            r"^day\tpart\tanswer\telapsed_us\n1\t1\t11\t[0-9.]+\n1\t2\t31\t[0-9.]+\n$",
+        )
+        .unwrap(),
+    );
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["--format", "xml", "day_one"]).arg(file.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown format 'xml'"));
+
+    file.close().unwrap();
+}
+
+#[test]
+fn test_numeric_day_stdin_and_part() {
+    let mut cmd = assert_cmd::Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["01", "-", "--part", "2"])
+        .write_stdin("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n");
+    cmd.assert().success().stdout("31\n");
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["day_sixty", "-"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown day 'day_sixty'"));
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("--help");
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("usage:").and(predicates::str::contains("day_fourteen")));
+}
+
+#[test]
+fn test_params_and_config() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    dir.child("day11.txt").write_str("125 17\n").unwrap();
+    dir.child("aoc.conf")
+        .write_str("# the example only blinks six times\n[day11]\nblinks = 6\n")
+        .unwrap();
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["11", "--part", "2", "--param", "blinks=25"])
+        .arg(dir.child("day11.txt").path());
+    cmd.assert().success().stdout("55312\n");
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["11", "--part", "2", "--config"])
+        .arg(dir.child("aoc.conf").path())
+        .arg(dir.child("day11.txt").path());
+    cmd.assert().success().stdout("22\n");
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["11", "--param", "blink=6"])
+        .arg(dir.child("day11.txt").path());
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown parameter 'blink'"));
+
+    dir.close().unwrap();
+}
+
+#[test]
+fn test_examples() {
+    for day in days::DAYS {
+        assert!(
+            !day.examples.is_empty(),
+            "day {} has no example",
+            day.number
+        );
+        let mut cmd = Command::cargo_bin("aoc24").unwrap();
+        cmd.args([day.number.to_string().as_str(), "--example"]);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2 * day.examples.len(), "day {}", day.number);
+        for (example, answers) in day.examples.iter().zip(lines.chunks(2)) {
+            for (expected, answer) in [example.part_one, example.part_two].iter().zip(answers) {
+                if let Some(expected) = expected {
+                    assert_eq!(answer, expected, "day {}", day.number);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_example_overrides() {
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["11", "--example", "--part", "2", "--param", "blinks=6"]);
+    cmd.assert().success().stdout("22\n");
+}
+
+#[test]
+fn test_stored_inputs() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    dir.child("day01.txt")
+        .write_str("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n")
+        .unwrap();
+
+    // a cached input needs no session
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.env_remove("AOC_SESSION")
+        .args(["1", "--data-dir"])
+        .arg(dir.path());
+    cmd.assert().success().stdout("11\n31\n");
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.env_remove("AOC_SESSION")
+        .args(["fetch", "1", "--data-dir"])
+        .arg(dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("day 1: already in"));
+
+    // and a missing one can't be fetched without
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.env_remove("AOC_SESSION")
+        .args(["2", "--data-dir"])
+        .arg(dir.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("set AOC_SESSION or write it to"));
+    dir.child("day02.txt").assert(predicates::path::missing());
+}
+
+/// answer a single request with `page` on a local port, handing back the url
+/// and what was posted
+fn answer_once(page: &'static str) -> (String, std::thread::JoinHandle<String>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(&stream);
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(l) = line.strip_prefix("Content-Length: ") {
+                length = l.trim().parse().unwrap();
+            }
+            if line.trim().is_empty() {
+                break;
+            }
+        }
+        let mut body = String::new();
+        reader.take(length).read_to_string(&mut body).unwrap();
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+            page.len()
+        )
+        .unwrap();
+        body
+    });
+    (url, handle)
+}
+
+#[test]
+fn test_submit() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    dir.child("day01.txt")
+        .write_str("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n")
+        .unwrap();
+    let (url, server) =
+        answer_once("<article><p>That's not the right answer; your answer is too low.</p>");
+
+    // no answer given, so the day's own is worked out and sent
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.env("AOC_SESSION", "abc123")
+        .args(["submit", "1", "2", "--base-url", &url, "--data-dir"])
+        .arg(dir.path());
+    cmd.assert()
+        .failure()
+        .stdout("day 1 part 2: 31 was too low\n");
+    assert_eq!(server.join().unwrap(), "level=2&answer=31");
+    dir.child("history.txt")
+        .assert(predicates::str::contains("1 2 too-low 31\n"));
+
+    // then anything lower is refused without a session or a server
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.env_remove("AOC_SESSION")
+        .args(["submit", "1", "2", "30", "--data-dir"])
+        .arg(dir.path());
+    cmd.assert()
+        .failure()
+        .stderr("day 1 part 2: not sending 30, 31 was already too low\n");
+}
+
+#[test]
+fn test_leaderboard() {
+    let file = assert_fs::NamedTempFile::new("board.json").unwrap();
+    file.write_str(
+        r#"{"event":"2024","owner_id":1,"members":{"1":{"id":1,"name":"Alice","stars":2,
+"local_score":2,"last_star_ts":1733029780,"completion_day_level":{"1":{
+"1":{"get_star_ts":1733029512,"star_index":1},"2":{"get_star_ts":1733029780,"star_index":2}}}}}}"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("leaderboard").arg(file.path());
+    cmd.assert().success().stdout(
+        predicates::str::is_match(r"(?m)^   1  Alice      2      2$")
+            .unwrap()
+            .and(predicates::str::contains(
+                "    1     0:05:12     0:09:40     0:04:28",
+            )),
+    );
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["leaderboard", "--format", "json"])
+        .arg(file.path());
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains(r#""delta_s":268"#));
+
+    file.write_str("{\"event\": 2024}").unwrap();
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.arg("leaderboard").arg(file.path());
+    cmd.assert().failure().stderr(predicates::str::contains(
+        "board.json:1:14: invalid JSON, invalid type: integer `2024`",
+    ));
+}
+
+#[test]
+fn test_visualize() {
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["14", "--example", "--visualize", "--fps", "10000"]);
+    cmd.assert().success().stdout(
+        predicates::str::contains("second 0 of 77")
+            .and(predicates::str::contains("second 76 of 77"))
+            .and(predicates::str::contains("\x1b[32m#")),
+    );
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["3", "--example", "--visualize"]);
+    cmd.assert()
+        .failure()
+        .stderr("error: day 3 has nothing to visualize\n");
+}
+
+#[test]
+fn test_export() {
+    let dir = assert_fs::TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.current_dir(dir.path()).args([
+        "14",
+        "--example",
+        "--export",
+        "robots.gif",
+        "--frames",
+        "..10",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicates::str::contains("found '..10'"));
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.current_dir(dir.path()).args([
+        "14",
+        "--example",
+        "--export",
+        "robots.gif",
+        "--frames",
+        "0..10",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout("wrote 10 frames to robots.gif\n");
+    assert!(
+        std::fs::read(dir.child("robots.gif").path())
+            .unwrap()
+            .starts_with(b"GIF89a")
+    );
+
+    // one frame each, numbered by where it is in the whole run
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.current_dir(dir.path()).args([
+        "8",
+        "--example",
+        "--export",
+        "antinodes-{}.png",
+        "--scale",
+        "2",
+        "--frames",
+        "1..",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout("wrote 2 frames to antinodes-{}.png\n");
+    dir.child("antinodes-00001.png")
+        .assert(predicates::path::exists());
+    dir.child("antinodes-00002.png")
+        .assert(predicates::path::exists());
+    dir.child("antinodes-00000.png")
+        .assert(predicates::path::missing());
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.current_dir(dir.path())
+        .args(["12", "--example", "--export", "regions.ppm"]);
+    cmd.assert()
+        .success()
+        .stdout("wrote 1 frame to regions.ppm\n");
+}
+
+#[test]
+fn test_logging() {
+    // only the answers by default
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["2", "--example"]);
+    cmd.assert().success().stdout("2\n4\n").stderr("");
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["2", "--example", "-vv"]);
+    cmd.assert().success().stdout("2\n4\n").stderr(
+        "debug [aoc24::days::day2]: unsafe even with the dampener: 1 2 7 8 9\n\
+         debug [aoc24::days::day2]: unsafe even with the dampener: 9 7 6 2 1\n",
+    );
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["12", "--example", "--verbose", "-v"]);
+    cmd.assert().success().stderr(predicates::str::contains(
+        "region 0: 10 sides, area 12, cost 120",
+    ));
+
+    // a warning is quietened, the error still says what went wrong
+    let dir = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["run-all", "--format", "json"]).arg(dir.path());
+    cmd.assert()
+        .stderr(predicates::str::contains("warn: skipping day 1, no "));
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["run-all", "--format", "json", "-q"])
+        .arg(dir.path());
+    cmd.assert()
+        .stderr(predicates::str::contains("skipping").not());
+}
+
+#[test]
+fn test_generate() {
+    let generate = |seed: &str| {
+        let mut cmd = Command::cargo_bin("aoc24").unwrap();
+        cmd.args(["generate", "9", "--seed", seed, "--size", "files=50"]);
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let input = generate("3");
+    assert_eq!(input.trim_end().len(), 99);
+    assert_eq!(generate("3"), input);
+    assert_ne!(generate("4"), input);
+
+    // what's generated can be solved straight away
+    let dir = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.current_dir(dir.path()).args([
+        "generate",
+        "14",
+        "robots.txt",
+        "--size",
+        "width=31",
+        "--size",
+        "height=29",
+    ]);
+    cmd.assert().success().stdout("");
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.current_dir(dir.path()).args([
+        "14",
+        "robots.txt",
+        "--param",
+        "width=31",
+        "--param",
+        "height=29",
+    ]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("aoc24").unwrap();
+    cmd.args(["generate", "6", "--size", "width=0"]);
+    cmd.assert()
+        .failure()
+        .stderr("error: parameter 'width' is 0, it has to be from 1 to 1000\n");
+}